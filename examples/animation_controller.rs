@@ -1,85 +1,49 @@
 // This example demonstrates how you might go about managing multiple animations,
-// and switching between them based on the player's input. Something along these
-// lines may be added to Tetra itself later, but for now it's not too hard to
-// roll your own!
+// and switching between them based on the player's input, using an
+// `AnimationController`. It also shows how frame events can be used to trigger
+// things at specific points in an animation.
 
 use std::time::Duration;
 
-use tetra::graphics::animation::Animation;
-use tetra::graphics::{self, Color, DrawParams, Drawable, Rectangle, Texture};
+use tetra::graphics::animation::{Animation, AnimationController};
+use tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
 use tetra::input::{self, Key};
 use tetra::math::Vec2;
+use tetra::window;
 use tetra::{Context, ContextBuilder, State};
 
-#[derive(PartialEq)]
-enum PlayerState {
-    Idle,
-    Running,
-}
-
-struct PlayerAnimation {
-    state: PlayerState,
-    idle: Animation,
-    running: Animation,
-}
-
-impl PlayerAnimation {
-    fn new(ctx: &mut Context) -> tetra::Result<PlayerAnimation> {
-        let texture = Texture::new(ctx, "./examples/resources/tiles.png")?;
-
-        Ok(PlayerAnimation {
-            state: PlayerState::Idle,
-            idle: Animation::new(
-                // Remember, textures are cheap to clone, as they just point at GPU data.
-                texture.clone(),
-                Rectangle::row(0.0, 256.0, 16.0, 16.0).take(8).collect(),
-                Duration::from_secs_f64(0.1),
-            ),
-            running: Animation::new(
-                texture,
-                Rectangle::row(0.0, 272.0, 16.0, 16.0).take(8).collect(),
-                Duration::from_secs_f64(0.1),
-            ),
-        })
-    }
-
-    fn current(&self) -> &Animation {
-        match self.state {
-            PlayerState::Idle => &self.idle,
-            PlayerState::Running => &self.running,
-        }
-    }
-
-    fn current_mut(&mut self) -> &mut Animation {
-        match self.state {
-            PlayerState::Idle => &mut self.idle,
-            PlayerState::Running => &mut self.running,
-        }
-    }
-
-    fn advance(&mut self, ctx: &Context) {
-        self.current_mut().advance(ctx);
-    }
-
-    fn set_state(&mut self, state: PlayerState) {
-        if self.state != state {
-            self.state = state;
-            self.current_mut().restart();
-        }
-    }
-}
-
-impl Drawable for PlayerAnimation {
-    fn draw<P>(&self, ctx: &mut Context, params: P)
-    where
-        P: Into<DrawParams>,
-    {
-        self.current().draw(ctx, params)
-    }
+fn create_animations(ctx: &mut Context) -> tetra::Result<AnimationController> {
+    let texture = Texture::new(ctx, "./examples/resources/tiles.png")?;
+
+    let mut controller = AnimationController::new(
+        "idle",
+        Animation::new(
+            // Remember, textures are cheap to clone, as they just point at GPU data.
+            texture.clone(),
+            Rectangle::row(0.0, 256.0, 16.0, 16.0).take(8).collect(),
+            Duration::from_secs_f64(0.1),
+        ),
+    );
+
+    controller.add_animation(
+        "running",
+        Animation::new(
+            texture,
+            Rectangle::row(0.0, 272.0, 16.0, 16.0).take(8).collect(),
+            Duration::from_secs_f64(0.1),
+        ),
+    );
+
+    // The player's feet hit the ground twice during each loop of the running animation.
+    controller.add_event("running", 1, "footstep");
+    controller.add_event("running", 5, "footstep");
+
+    Ok(controller)
 }
 
 struct GameState {
-    animation: PlayerAnimation,
+    animation: AnimationController,
+    footsteps: u32,
     position: Vec2<f32>,
     velocity: Vec2<f32>,
 }
@@ -87,7 +51,8 @@ struct GameState {
 impl GameState {
     fn new(ctx: &mut Context) -> tetra::Result<GameState> {
         Ok(GameState {
-            animation: PlayerAnimation::new(ctx)?,
+            animation: create_animations(ctx)?,
+            footsteps: 0,
             position: Vec2::new(240.0, 160.0),
             velocity: Vec2::new(0.0, 0.0),
         })
@@ -107,9 +72,9 @@ impl State for GameState {
         self.position += self.velocity;
 
         if self.velocity.x.abs() > 0.0 {
-            self.animation.set_state(PlayerState::Running);
+            self.animation.play("running");
         } else {
-            self.animation.set_state(PlayerState::Idle);
+            self.animation.play("idle");
         }
 
         Ok(())
@@ -118,6 +83,16 @@ impl State for GameState {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
         self.animation.advance(ctx);
 
+        while let Some(event) = self.animation.poll_event() {
+            if event.name == "footstep" {
+                self.footsteps += 1;
+                window::set_title(
+                    ctx,
+                    format!("Controlling Animations - {} footsteps", self.footsteps),
+                );
+            }
+        }
+
         graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
 
         graphics::draw(
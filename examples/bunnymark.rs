@@ -115,11 +115,17 @@ impl State for GameState {
             graphics::draw(ctx, &self.texture, bunny.position);
         }
 
+        // Flush the batch, so that the stats include all of the bunnies.
+        graphics::flush(ctx);
+
+        let stats = graphics::get_stats(ctx);
+
         window::set_title(
             ctx,
             &format!(
-                "BunnyMark - {} bunnies - {:.0} FPS",
+                "BunnyMark - {} bunnies - {} draw calls - {:.0} FPS",
                 self.bunnies.len(),
+                stats.draw_calls,
                 time::get_fps(ctx)
             ),
         );
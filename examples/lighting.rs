@@ -0,0 +1,143 @@
+// This example demonstrates how to light a scene, with shadows being cast by walls.
+//
+// A point light follows the mouse, and a spot light sweeps around the room. Use the
+// arrow keys to move the camera, and press Space to toggle between hard and soft shadows.
+
+use tetra::graphics::lighting::{Light, LightKind, Lighting, Occluder, Shadows};
+use tetra::graphics::{self, Camera, Color, DrawParams, Rectangle, Texture};
+use tetra::input::{self, Key};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, State};
+
+const WIDTH: f32 = 1280.0;
+const HEIGHT: f32 = 720.0;
+
+const SOFT_SHADOWS: Shadows = Shadows::Soft {
+    spread: 12.0,
+    samples: 8,
+};
+
+struct GameState {
+    block: Texture,
+    walls: Vec<Rectangle>,
+    camera: Camera,
+    lighting: Lighting,
+    timer: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let walls = vec![
+            Rectangle::new(320.0, 160.0, 64.0, 64.0),
+            Rectangle::new(800.0, 200.0, 32.0, 160.0),
+            Rectangle::new(480.0, 480.0, 192.0, 32.0),
+            Rectangle::new(960.0, 520.0, 64.0, 64.0),
+            Rectangle::new(160.0, 440.0, 32.0, 32.0),
+        ];
+
+        let mut lighting = Lighting::with_window_size(ctx)?;
+
+        lighting.set_ambient(Color::rgb(0.1, 0.1, 0.2));
+
+        for wall in &walls {
+            lighting.add_occluder(Occluder::rectangle(*wall));
+        }
+
+        lighting.add_light(Light::point(Vec2::zero(), 400.0, Color::rgb(1.0, 0.9, 0.7)));
+
+        lighting.add_light(Light::spot(
+            Vec2::new(WIDTH / 2.0, HEIGHT / 2.0),
+            600.0,
+            Color::rgb(0.4, 0.6, 1.0),
+            0.0,
+            std::f32::consts::PI / 4.0,
+        ));
+
+        Ok(GameState {
+            block: Texture::new(ctx, "./examples/resources/block.png")?,
+            walls,
+            camera: Camera::new(WIDTH, HEIGHT),
+            lighting,
+            timer: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        self.timer += 0.01;
+
+        if input::is_key_down(ctx, Key::Left) {
+            self.camera.position.x -= 4.0;
+        }
+
+        if input::is_key_down(ctx, Key::Right) {
+            self.camera.position.x += 4.0;
+        }
+
+        if input::is_key_down(ctx, Key::Up) {
+            self.camera.position.y -= 4.0;
+        }
+
+        if input::is_key_down(ctx, Key::Down) {
+            self.camera.position.y += 4.0;
+        }
+
+        self.camera.update();
+
+        let soft = input::is_key_pressed(ctx, Key::Space);
+        let mouse = self.camera.mouse_position(ctx);
+        let lights = self.lighting.lights_mut();
+
+        lights[0].position = mouse;
+
+        if let LightKind::Spot { direction, .. } = &mut lights[1].kind {
+            *direction = self.timer;
+        }
+
+        if soft {
+            for light in lights {
+                light.shadows = match light.shadows {
+                    Shadows::Hard => SOFT_SHADOWS,
+                    _ => Shadows::Hard,
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        // The light map uses the same transformation as the rest of the scene.
+        self.lighting
+            .render_light_map(ctx, self.camera.as_matrix())?;
+
+        graphics::clear(ctx, Color::rgb(0.6, 0.6, 0.6));
+        graphics::set_transform_matrix(ctx, self.camera.as_matrix());
+
+        for wall in &self.walls {
+            graphics::draw(
+                ctx,
+                &self.block,
+                DrawParams::new()
+                    .position(Vec2::new(wall.x, wall.y))
+                    .scale(Vec2::new(wall.width / 16.0, wall.height / 16.0)),
+            );
+        }
+
+        // The light map is drawn without the camera's transformation, as it already
+        // lines up with the screen.
+        self.lighting.draw_light_map(ctx);
+
+        graphics::reset_transform_matrix(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Lighting", WIDTH as i32, HEIGHT as i32)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
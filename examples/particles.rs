@@ -0,0 +1,112 @@
+// This example demonstrates how to use particle emitters.
+//
+// A fountain of sparks follows the mouse, and clicking creates an explosion.
+
+use std::f32::consts::PI;
+
+use tetra::graphics::particles::{Curve, EmitterConfig, ParticleEmitter, ValueRange};
+use tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use tetra::input::{self, MouseButton};
+use tetra::math::Vec2;
+use tetra::window;
+use tetra::{Context, ContextBuilder, State};
+
+struct GameState {
+    fountain: ParticleEmitter,
+    explosion: ParticleEmitter,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let texture = Texture::new(ctx, "./examples/resources/block.png")?;
+
+        let fountain = ParticleEmitter::new(
+            texture.clone(),
+            EmitterConfig {
+                spawn_rate: 200.0,
+                lifetime: ValueRange::new(1.0, 2.0),
+                speed: ValueRange::new(150.0, 250.0),
+                direction: -PI / 2.0,
+                spread: PI / 8.0,
+                gravity: Vec2::new(0.0, 300.0),
+                angular_velocity: ValueRange::new(-4.0, 4.0),
+                scale: Curve::from_keys(vec![(0.0, 1.0), (1.0, 0.25)]),
+                color: Curve::from_keys(vec![
+                    (0.0, Color::rgb(1.0, 0.9, 0.4)),
+                    (0.5, Color::rgb(1.0, 0.4, 0.1)),
+                    (1.0, Color::rgba(0.5, 0.1, 0.1, 0.0)),
+                ]),
+                source: Some(Rectangle::new(0.0, 0.0, 8.0, 8.0)),
+                ..EmitterConfig::default()
+            },
+        );
+
+        let mut explosion = ParticleEmitter::new(
+            texture,
+            EmitterConfig {
+                lifetime: ValueRange::new(0.4, 0.8),
+                speed: ValueRange::new(50.0, 400.0),
+                rotation: ValueRange::new(0.0, PI),
+                scale: Curve::from_keys(vec![(0.0, 2.0), (1.0, 0.0)]),
+                color: Curve::from_keys(vec![
+                    (0.0, Color::WHITE),
+                    (1.0, Color::rgba(0.3, 0.6, 1.0, 0.0)),
+                ]),
+                ..EmitterConfig::default()
+            },
+        );
+
+        // The explosion should only spawn particles when we tell it to.
+        explosion.stop();
+
+        Ok(GameState {
+            fountain,
+            explosion,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        let mouse = input::get_mouse_position(ctx);
+
+        self.fountain.set_position(mouse);
+        self.fountain.update(ctx);
+
+        if input::is_mouse_button_pressed(ctx, MouseButton::Left) {
+            self.explosion.set_position(mouse);
+            self.explosion.burst(300);
+        }
+
+        self.explosion.update(ctx);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        graphics::draw(ctx, &self.fountain, DrawParams::new());
+        graphics::draw(ctx, &self.explosion, DrawParams::new());
+
+        let stats = graphics::get_stats(ctx);
+
+        window::set_title(
+            ctx,
+            format!(
+                "Particles - {} particles, {} draw calls",
+                self.fountain.particles().len() + self.explosion.particles().len(),
+                stats.draw_calls
+            ),
+        );
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Particles", 1280, 720)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
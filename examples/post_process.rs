@@ -0,0 +1,105 @@
+// This example demonstrates how to apply a chain of post-processing effects to a scene.
+//
+// Press 1-4 to toggle the blur, bloom, color grading and scanline effects.
+
+use tetra::graphics::post_process::{ColorGrade, Effect, PostProcess};
+use tetra::graphics::{self, Color, DrawParams, Texture};
+use tetra::input::{self, Key};
+use tetra::math::Vec2;
+use tetra::window;
+use tetra::{Context, ContextBuilder, State};
+
+const EFFECT_NAMES: [&str; 4] = ["blur", "bloom", "color grade", "scanlines"];
+
+struct GameState {
+    texture: Texture,
+    post_process: PostProcess,
+    timer: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let mut post_process = PostProcess::with_window_size(ctx)?;
+
+        let mut blur = Effect::gaussian_blur(ctx, 1.0)?;
+        blur.set_enabled(false);
+
+        post_process.add_effect(blur);
+        post_process.add_effect(Effect::bloom(ctx, 0.6, 1.2)?);
+        post_process.add_effect(Effect::color_grade(
+            ctx,
+            ColorGrade {
+                contrast: 1.1,
+                saturation: 1.3,
+                tint: Color::rgb(1.0, 0.95, 0.85),
+                ..ColorGrade::default()
+            },
+        )?);
+        post_process.add_effect(Effect::scanlines(ctx, 2.0, 0.25)?);
+
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/player.png")?,
+            post_process,
+            timer: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        self.timer += 0.02;
+
+        let keys = [Key::Num1, Key::Num2, Key::Num3, Key::Num4];
+
+        for (effect, key) in self.post_process.effects_mut().iter_mut().zip(&keys) {
+            if input::is_key_pressed(ctx, *key) {
+                effect.set_enabled(!effect.is_enabled());
+            }
+        }
+
+        let enabled: Vec<&str> = self
+            .post_process
+            .effects()
+            .iter()
+            .zip(&EFFECT_NAMES)
+            .filter(|(effect, _)| effect.is_enabled())
+            .map(|(_, name)| *name)
+            .collect();
+
+        window::set_title(ctx, format!("Post Processing - {}", enabled.join(", ")));
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        // Everything drawn between `begin` and `finish` will have the effects applied.
+        self.post_process.begin(ctx);
+
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        for i in 0..8 {
+            let angle = self.timer + i as f32 * std::f32::consts::PI / 4.0;
+
+            graphics::draw(
+                ctx,
+                &self.texture,
+                DrawParams::new()
+                    .position(Vec2::new(640.0, 360.0) + Vec2::new(angle.cos(), angle.sin()) * 200.0)
+                    .origin(Vec2::new(8.0, 8.0))
+                    .scale(Vec2::new(4.0, 4.0))
+                    .color(Color::rgb(1.0, 0.5 + (i as f32 / 16.0), 0.5)),
+            );
+        }
+
+        self.post_process.finish(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Post Processing", 1280, 720)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -0,0 +1,68 @@
+use tetra::graphics::{self, Color, Rectangle, Texture};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, State};
+
+const SEGMENTS: usize = 32;
+
+struct GameState {
+    texture: Texture,
+    timer: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/block.png")?,
+            timer: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, _: &mut Context) -> tetra::Result {
+        self.timer += 0.02;
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        let source = Rectangle::new(0.0, 0.0, 8.0, 8.0);
+
+        // Draw a wavy ribbon out of skewed quads. Because they all use the same
+        // texture, they will be batched into a single draw call.
+        for i in 0..SEGMENTS {
+            let x1 = 64.0 + i as f32 * 36.0;
+            let x2 = x1 + 36.0;
+
+            let y1 = 360.0 + (self.timer + i as f32 * 0.3).sin() * 96.0;
+            let y2 = 360.0 + (self.timer + (i + 1) as f32 * 0.3).sin() * 96.0;
+
+            let fade_in = Color::rgba(1.0, 1.0, 1.0, i as f32 / SEGMENTS as f32);
+            let fade_out = Color::rgba(1.0, 1.0, 1.0, (i + 1) as f32 / SEGMENTS as f32);
+
+            graphics::draw_quad(
+                ctx,
+                &self.texture,
+                source,
+                [
+                    Vec2::new(x1, y1 - 32.0),
+                    Vec2::new(x1, y1 + 32.0),
+                    Vec2::new(x2, y2 + 32.0),
+                    Vec2::new(x2, y2 - 32.0),
+                ],
+                [fade_in, fade_in, fade_out, fade_out],
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Drawing Quads", 1280, 720)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
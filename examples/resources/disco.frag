@@ -11,6 +11,9 @@ uniform float u_blue;
 
 out vec4 o_color;
 
+#include "overlay.glsl"
+
 void main() {
-    o_color = v_color * texture(u_texture, v_uv) * texture(u_overlay, v_uv) * vec4(u_red, u_green, u_blue, 1.0);
+    vec4 color = v_color * texture(u_texture, v_uv) * vec4(u_red, u_green, u_blue, 1.0);
+    o_color = apply_overlay(color, u_overlay, v_uv);
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+// Shared by any shader that wants to apply the overlay texture.
+//
+// OVERLAY_STRENGTH can be defined by the game via a ShaderBuilder - otherwise,
+// the overlay will be applied at full strength.
+
+#ifndef OVERLAY_STRENGTH
+#define OVERLAY_STRENGTH 1.0
+#endif
+
+vec4 apply_overlay(vec4 color, sampler2D overlay, vec2 uv) {
+    return mix(color, color * texture(overlay, uv), OVERLAY_STRENGTH);
+}
\ No newline at end of file
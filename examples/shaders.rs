@@ -1,11 +1,12 @@
 use tetra::graphics::text::{Font, Text};
-use tetra::graphics::{self, Color, DrawParams, Shader, Texture};
+use tetra::graphics::{self, Color, DrawParams, Shader, ShaderBuilder, ShaderWatcher, Texture};
 use tetra::math::Vec2;
-use tetra::{Context, ContextBuilder, State};
+use tetra::{Context, ContextBuilder, State, TetraError};
 
 struct GameState {
     texture: Texture,
     shader: Shader,
+    watcher: ShaderWatcher,
     text: Text,
 
     timer: f32,
@@ -19,9 +20,20 @@ impl GameState {
         let texture = Texture::new(ctx, "./examples/resources/player.png")?;
         let overlay = Texture::new(ctx, "./examples/resources/overlay.png")?;
 
-        let shader = Shader::from_fragment_file(ctx, "./examples/resources/disco.frag")?;
+        // The fragment shader includes some code from another file, which relies on
+        // a constant that we define here.
+        let shader = ShaderBuilder::new()
+            .fragment_file("./examples/resources/disco.frag")
+            .define("OVERLAY_STRENGTH", "0.75")
+            .build(ctx)?;
+
         shader.set_uniform(ctx, "u_overlay", overlay);
 
+        // Any changes made to the shader's files (including the included file) while
+        // the game is running will be picked up by the watcher.
+        let mut watcher = ShaderWatcher::new();
+        watcher.watch(&shader);
+
         let text = Text::new(
             "",
             Font::vector(ctx, "./examples/resources/DejaVuSansMono.ttf", 32.0)?,
@@ -30,6 +42,7 @@ impl GameState {
         Ok(GameState {
             texture,
             shader,
+            watcher,
             text,
 
             timer: 0.0,
@@ -41,7 +54,15 @@ impl GameState {
 }
 
 impl State for GameState {
-    fn update(&mut self, _ctx: &mut Context) -> tetra::Result {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        // If the shader fails to compile, the previous version will keep being used,
+        // so we can just log the error and carry on.
+        match self.watcher.update(ctx) {
+            Ok(_) => {}
+            Err(TetraError::InvalidShader(message)) => eprintln!("Invalid shader:\n{}", message),
+            Err(e) => eprintln!("Failed to reload shader: {}", e),
+        }
+
         self.timer += 1.0;
 
         self.red = ((self.timer / 10.0).sin() + 1.0) / 2.0;
@@ -0,0 +1,127 @@
+// This example demonstrates how to build a large tile map, and draw it efficiently
+// by only rendering the chunks that are visible through the camera.
+//
+// Use WASD to move the camera, and click to flip the tile under the mouse.
+
+use std::time::Duration;
+
+use tetra::graphics::tilemap::{Tile, TileFrame, TileMap, Tileset};
+use tetra::graphics::{self, Camera, Color, DrawParams, Texture};
+use tetra::input::{self, Key, MouseButton};
+use tetra::math::Vec2;
+use tetra::window;
+use tetra::{Context, ContextBuilder, State};
+
+const MAP_SIZE: i32 = 200;
+const TILE_SIZE: i32 = 16;
+const MOVEMENT_SPEED: f32 = 8.0;
+
+// The tileset is 32 tiles wide, and the player's idle animation starts on the 17th row.
+const ANIMATED_TILE: u32 = 16 * 32;
+
+struct GameState {
+    map: TileMap,
+    camera: Camera,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let texture = Texture::new(ctx, "./examples/resources/tiles.png")?;
+        let mut tileset = Tileset::new(texture, TILE_SIZE, TILE_SIZE);
+
+        tileset.set_animation(
+            ANIMATED_TILE,
+            (0..8)
+                .map(|i| TileFrame::new(ANIMATED_TILE + i, Duration::from_millis(100)))
+                .collect(),
+        );
+
+        let mut map = TileMap::new(MAP_SIZE, MAP_SIZE, TILE_SIZE as f32, TILE_SIZE as f32);
+
+        let ground = map.add_layer(tileset.clone());
+
+        for y in 0..MAP_SIZE {
+            for x in 0..MAP_SIZE {
+                ground.set_tile(x, y, Tile::new(((x + y) % 4) as u32));
+            }
+        }
+
+        let objects = map.add_layer(tileset);
+
+        for y in (0..MAP_SIZE).step_by(5) {
+            for x in (0..MAP_SIZE).step_by(5) {
+                objects.set_tile(x, y, Tile::new(ANIMATED_TILE));
+            }
+        }
+
+        let mut camera = Camera::with_window_size(ctx);
+        camera.position = Vec2::new(320.0, 240.0);
+
+        Ok(GameState { map, camera })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        if input::is_key_down(ctx, Key::W) {
+            self.camera.position.y -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::S) {
+            self.camera.position.y += MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::A) {
+            self.camera.position.x -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::D) {
+            self.camera.position.x += MOVEMENT_SPEED;
+        }
+
+        self.camera.update();
+
+        if input::is_mouse_button_pressed(ctx, MouseButton::Left) {
+            let mouse = self.camera.mouse_position(ctx);
+
+            let x = (mouse.x / TILE_SIZE as f32).floor() as i32;
+            let y = (mouse.y / TILE_SIZE as f32).floor() as i32;
+
+            let ground = self
+                .map
+                .layer_mut(0)
+                .expect("map should have a ground layer");
+
+            // Only the chunk containing this tile will be rebuilt.
+            if let Some(mut tile) = ground.get_tile(x, y) {
+                tile.flip_y = !tile.flip_y;
+                ground.set_tile(x, y, tile);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        self.map.advance(ctx);
+
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+        graphics::set_transform_matrix(ctx, self.camera.as_matrix());
+
+        self.map.draw_visible(ctx, &self.camera, DrawParams::new());
+
+        graphics::reset_transform_matrix(ctx);
+
+        let stats = graphics::get_stats(ctx);
+        window::set_title(ctx, format!("Tile Map - {} draw calls", stats.draw_calls));
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Tile Map", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
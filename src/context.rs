@@ -41,7 +41,12 @@ impl Context {
             println!("GLSL Version: {}", device.get_shading_language_version());
         }
 
-        let graphics = GraphicsContext::new(&mut device, window_width, window_height)?;
+        let graphics = GraphicsContext::new(
+            &mut device,
+            window_width,
+            window_height,
+            settings.batch_size,
+        )?;
         let input = InputContext::new();
         let time = TimeContext::new(settings.timestep);
 
@@ -191,6 +196,8 @@ pub struct ContextBuilder {
     pub(crate) resizable: bool,
     pub(crate) borderless: bool,
     pub(crate) multisampling: u8,
+    pub(crate) stencil_buffer: bool,
+    pub(crate) batch_size: usize,
     pub(crate) high_dpi: bool,
     pub(crate) screen_saver_enabled: bool,
     pub(crate) key_repeat: bool,
@@ -305,6 +312,33 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets whether or not the window's backbuffer should have a stencil buffer.
+    ///
+    /// This is required in order to use [`graphics::set_stencil_state`](crate::graphics::set_stencil_state)
+    /// while rendering to the window.
+    ///
+    /// Note that this setting only applies to the main backbuffer - [`Canvas`](crate::graphics::Canvas)es
+    /// can be given a stencil buffer via [`CanvasBuilder::stencil_buffer`](crate::graphics::CanvasBuilder::stencil_buffer).
+    ///
+    /// Defaults to `false`.
+    pub fn stencil_buffer(&mut self, stencil_buffer: bool) -> &mut ContextBuilder {
+        self.stencil_buffer = stencil_buffer;
+        self
+    }
+
+    /// Sets the maximum number of sprites that can be drawn in a single draw call.
+    ///
+    /// The sprite batch starts out small, and will grow as needed until it reaches this size -
+    /// after that, it will be flushed to the graphics hardware whenever it fills up. Increasing
+    /// this can reduce the number of draw calls in scenes with a lot of sprites that share a
+    /// texture, at the cost of more GPU memory.
+    ///
+    /// Defaults to `16384`.
+    pub fn batch_size(&mut self, batch_size: usize) -> &mut ContextBuilder {
+        self.batch_size = batch_size;
+        self
+    }
+
     /// Sets whether or not the window should use a high-DPI backbuffer, on platforms
     /// that support it (e.g. MacOS with a retina display).
     ///
@@ -423,6 +457,8 @@ impl Default for ContextBuilder {
             resizable: false,
             borderless: false,
             multisampling: 0,
+            stencil_buffer: false,
+            batch_size: 16384,
             high_dpi: false,
             screen_saver_enabled: false,
             key_repeat: false,
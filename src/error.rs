@@ -37,6 +37,16 @@ pub enum TetraError {
         path: PathBuf,
     },
 
+    /// Returned when your game fails to save an asset. This is usually caused by an
+    /// incorrect file path, or some form of permission issues.
+    FailedToSaveAsset {
+        /// The underlying reason for the error.
+        reason: io::Error,
+
+        /// The path that the asset was being saved to.
+        path: PathBuf,
+    },
+
     /// Returned when a color is invalid.
     InvalidColor,
 
@@ -46,9 +56,24 @@ pub enum TetraError {
     /// Returned when a shader fails to compile.
     InvalidShader(String),
 
+    /// Returned when a uniform could not be set on a shader, either because the shader has
+    /// no active uniform with that name, or because the value was of the wrong type.
+    InvalidUniform(String),
+
     /// Returned when a font could not be read.
     InvalidFont,
 
+    /// Returned when a texture atlas could not be built, or its layout data
+    /// could not be parsed.
+    InvalidAtlas(String),
+
+    /// Returned when a sprite sheet's data could not be parsed, or described a sheet
+    /// that is not supported.
+    InvalidSpriteSheet(String),
+
+    /// Returned when a tile map's data could not be parsed.
+    InvalidMap(String),
+
     /// Returned when a sound cannot be decoded.
     #[cfg(feature = "audio")]
     InvalidSound(DecoderError),
@@ -82,10 +107,17 @@ impl Display for TetraError {
             TetraError::FailedToLoadAsset { path, .. } => {
                 write!(f, "Failed to load asset from {}", path.to_string_lossy())
             }
+            TetraError::FailedToSaveAsset { path, .. } => {
+                write!(f, "Failed to save asset to {}", path.to_string_lossy())
+            }
             TetraError::InvalidColor => write!(f, "Invalid color"),
             TetraError::InvalidTexture(_) => write!(f, "Invalid texture data"),
             TetraError::InvalidShader(_) => write!(f, "Invalid shader source"),
+            TetraError::InvalidUniform(_) => write!(f, "Invalid shader uniform"),
             TetraError::InvalidFont => write!(f, "Invalid font data"),
+            TetraError::InvalidAtlas(_) => write!(f, "Invalid texture atlas"),
+            TetraError::InvalidSpriteSheet(_) => write!(f, "Invalid sprite sheet data"),
+            TetraError::InvalidMap(_) => write!(f, "Invalid tile map data"),
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(_) => write!(f, "Invalid sound data"),
             TetraError::NotEnoughData { expected, actual } => write!(
@@ -107,10 +139,15 @@ impl Error for TetraError {
         match self {
             TetraError::PlatformError(_) => None,
             TetraError::FailedToLoadAsset { reason, .. } => Some(reason),
+            TetraError::FailedToSaveAsset { reason, .. } => Some(reason),
             TetraError::InvalidColor => None,
             TetraError::InvalidTexture(reason) => Some(reason),
             TetraError::InvalidShader(_) => None,
+            TetraError::InvalidUniform(_) => None,
             TetraError::InvalidFont => None,
+            TetraError::InvalidAtlas(_) => None,
+            TetraError::InvalidSpriteSheet(_) => None,
+            TetraError::InvalidMap(_) => None,
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(reason) => Some(reason),
             TetraError::NotEnoughData { .. } => None,
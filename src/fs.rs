@@ -6,7 +6,7 @@
 use std::fs;
 use std::path::Path;
 
-use image::{self, DynamicImage, ImageError};
+use image::{self, ColorType, DynamicImage, ImageError, ImageFormat};
 
 use crate::error::{Result, TetraError};
 
@@ -48,3 +48,45 @@ where
         path: path_ref.to_owned(),
     })
 }
+
+pub(crate) fn write<P, C>(path: P, contents: C) -> Result
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let path_ref = path.as_ref();
+
+    fs::write(path_ref, contents).map_err(|e| TetraError::FailedToSaveAsset {
+        reason: e,
+        path: path_ref.to_owned(),
+    })
+}
+
+pub(crate) fn write_image<P>(
+    path: P,
+    data: &[u8],
+    width: i32,
+    height: i32,
+    format: ImageFormat,
+) -> Result
+where
+    P: AsRef<Path>,
+{
+    let path_ref = path.as_ref();
+
+    image::save_buffer_with_format(
+        path_ref,
+        data,
+        width as u32,
+        height as u32,
+        ColorType::Rgba8,
+        format,
+    )
+    .map_err(|e| match e {
+        ImageError::IoError(inner) => TetraError::FailedToSaveAsset {
+            reason: inner,
+            path: path_ref.to_owned(),
+        },
+        _ => TetraError::InvalidTexture(e),
+    })
+}
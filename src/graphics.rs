@@ -6,36 +6,71 @@
 //! rendering.
 
 pub mod animation;
+mod atlas;
+mod blend;
 mod camera;
 mod canvas;
 mod color;
 mod drawable;
+mod image_data;
+pub mod lighting;
 mod mesh;
+mod packer;
+pub mod particles;
+pub mod post_process;
 mod rectangle;
 pub mod scaling;
 mod shader;
+#[cfg(feature = "sprite_sheet_json")]
+mod sprite_sheet;
+mod stats;
+mod stencil;
 pub mod text;
 mod texture;
+mod texture_region;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+pub mod tilemap;
 pub mod ui;
+mod uniform_buffer;
 
+pub use atlas::*;
+pub use blend::*;
 pub use camera::*;
 pub use canvas::*;
 pub use color::*;
 pub use drawable::*;
+pub use image_data::*;
 pub use mesh::*;
 pub use rectangle::*;
 pub use shader::*;
+#[cfg(feature = "sprite_sheet_json")]
+pub use sprite_sheet::*;
+pub use stats::*;
+pub use stencil::*;
 pub use texture::*;
+pub use texture_region::*;
+pub use uniform_buffer::*;
+
+#[cfg(feature = "texture_png")]
+use std::path::Path;
+
+#[cfg(feature = "texture_png")]
+use image::error::{ImageError, ParameterError, ParameterErrorKind};
+#[cfg(feature = "texture_png")]
+use image::ImageFormat;
 
 use crate::error::Result;
+#[cfg(feature = "texture_png")]
+use crate::error::TetraError;
+#[cfg(feature = "texture_png")]
+use crate::fs;
 use crate::math::{FrustumPlanes, Mat4, Vec2};
 use crate::platform::{GraphicsDevice, RawIndexBuffer, RawVertexBuffer};
 use crate::window;
 use crate::Context;
 
-const MAX_SPRITES: usize = 2048;
-const MAX_VERTICES: usize = MAX_SPRITES * 4; // Cannot be greater than 32767!
-const MAX_INDICES: usize = MAX_SPRITES * 6;
+const INITIAL_BATCH_SIZE: usize = 2048;
 const INDEX_ARRAY: [u32; 6] = [0, 1, 2, 2, 3, 0];
 
 #[derive(PartialEq)]
@@ -59,6 +94,8 @@ pub(crate) enum ActiveCanvas {
 pub(crate) struct GraphicsContext {
     vertex_buffer: RawVertexBuffer,
     index_buffer: RawIndexBuffer,
+    batch_capacity: usize,
+    max_batch_size: usize,
 
     texture: ActiveTexture,
     default_texture: Texture,
@@ -69,12 +106,21 @@ pub(crate) struct GraphicsContext {
 
     canvas: ActiveCanvas,
 
+    blend_mode: BlendMode,
+    scissor_stack: Vec<Rectangle<i32>>,
+    stencil_state: StencilState,
+    color_mask: (bool, bool, bool, bool),
     winding: VertexWinding,
     projection_matrix: Mat4<f32>,
     transform_matrix: Mat4<f32>,
 
     vertex_data: Vec<Vertex>,
     element_count: usize,
+
+    stats: RenderStats,
+
+    screenshot_requested: bool,
+    screenshot: Option<ImageData>,
 }
 
 impl GraphicsContext {
@@ -82,22 +128,21 @@ impl GraphicsContext {
         device: &mut GraphicsDevice,
         window_width: i32,
         window_height: i32,
+        batch_size: usize,
     ) -> Result<GraphicsContext> {
-        let vertex_buffer = device.new_vertex_buffer(MAX_VERTICES, 8, BufferUsage::Dynamic)?;
-        let index_buffer = device.new_index_buffer(MAX_INDICES, BufferUsage::Static)?;
-
-        let indices: Vec<u32> = INDEX_ARRAY
-            .iter()
-            .cycle()
-            .take(MAX_INDICES)
-            .enumerate()
-            .map(|(i, vertex)| vertex + i as u32 / 6 * 4)
-            .collect();
+        let max_batch_size = batch_size.max(1);
+        let batch_capacity = usize::min(INITIAL_BATCH_SIZE, max_batch_size);
 
-        device.set_index_buffer_data(&index_buffer, &indices, 0);
+        let (vertex_buffer, index_buffer) = create_batch_buffers(device, batch_capacity)?;
 
-        let default_texture =
-            Texture::with_device(device, 1, 1, &[255, 255, 255, 255], FilterMode::Nearest)?;
+        let default_texture = Texture::with_device(
+            device,
+            1,
+            1,
+            &[255, 255, 255, 255],
+            FilterMode::Nearest,
+            false,
+        )?;
 
         let default_filter_mode = FilterMode::Nearest;
 
@@ -110,6 +155,8 @@ impl GraphicsContext {
         Ok(GraphicsContext {
             vertex_buffer,
             index_buffer,
+            batch_capacity,
+            max_batch_size,
 
             texture: ActiveTexture::Default,
             default_texture,
@@ -120,16 +167,46 @@ impl GraphicsContext {
 
             canvas: ActiveCanvas::Window,
 
+            blend_mode: BlendMode::Alpha,
+            scissor_stack: Vec::new(),
+            stencil_state: StencilState::disabled(),
+            color_mask: (true, true, true, true),
             winding: VertexWinding::CounterClockwise,
             projection_matrix: ortho(window_width as f32, window_height as f32, false),
             transform_matrix: Mat4::identity(),
 
-            vertex_data: Vec::with_capacity(MAX_VERTICES),
+            vertex_data: Vec::with_capacity(batch_capacity * 4),
             element_count: 0,
+
+            stats: RenderStats::default(),
+
+            screenshot_requested: false,
+            screenshot: None,
         })
     }
 }
 
+/// Creates a vertex buffer and index buffer that can hold the specified number of quads.
+fn create_batch_buffers(
+    device: &mut GraphicsDevice,
+    capacity: usize,
+) -> Result<(RawVertexBuffer, RawIndexBuffer)> {
+    let vertex_buffer = device.new_vertex_buffer(capacity * 4, 8, BufferUsage::Stream)?;
+    let index_buffer = device.new_index_buffer(capacity * 6, BufferUsage::Static)?;
+
+    let indices: Vec<u32> = INDEX_ARRAY
+        .iter()
+        .cycle()
+        .take(capacity * 6)
+        .enumerate()
+        .map(|(i, vertex)| vertex + i as u32 / 6 * 4)
+        .collect();
+
+    device.set_index_buffer_data(&index_buffer, &indices, 0);
+
+    Ok((vertex_buffer, index_buffer))
+}
+
 /// Clears the screen (or a canvas, if one is enabled) to the specified color.
 pub fn clear(ctx: &mut Context, color: Color) {
     ctx.device.clear(color.r, color.g, color.b, color.a);
@@ -152,11 +229,8 @@ pub(crate) fn push_quad(
     // frame by a *lot* (at least going by the BunnyMark example). The logic is roughly based
     // on how FNA and LibGDX implement their spritebatches.
     //
-    // TODO: This function really needs cleaning up before it can be exposed publicly.
-
-    if ctx.graphics.element_count + 6 > MAX_INDICES {
-        flush(ctx);
-    }
+    // If you need to draw a quad with arbitrary corners, use `push_quad_vertices` (or the
+    // public `draw_quad` API) instead.
 
     let mut fx = (x1 - params.origin.x) * params.scale.x;
     let mut fy = (y1 - params.origin.y) * params.scale.y;
@@ -200,16 +274,131 @@ pub(crate) fn push_quad(
         )
     };
 
-    ctx.graphics.vertex_data.extend_from_slice(&[
-        Vertex::new(Vec2::new(ox1, oy1), Vec2::new(u1, v1), params.color),
-        Vertex::new(Vec2::new(ox2, oy2), Vec2::new(u1, v2), params.color),
-        Vertex::new(Vec2::new(ox3, oy3), Vec2::new(u2, v2), params.color),
-        Vertex::new(Vec2::new(ox4, oy4), Vec2::new(u2, v1), params.color),
-    ]);
+    push_quad_vertices(
+        ctx,
+        &[
+            Vertex::new(Vec2::new(ox1, oy1), Vec2::new(u1, v1), params.color),
+            Vertex::new(Vec2::new(ox2, oy2), Vec2::new(u1, v2), params.color),
+            Vertex::new(Vec2::new(ox3, oy3), Vec2::new(u2, v2), params.color),
+            Vertex::new(Vec2::new(ox4, oy4), Vec2::new(u2, v1), params.color),
+        ],
+    );
+}
+
+/// Adds a quad to the batch, growing the batch or flushing if there is not enough space.
+///
+/// The vertices should be in counter-clockwise order, starting from the top left.
+pub(crate) fn push_quad_vertices(ctx: &mut Context, vertices: &[Vertex; 4]) {
+    if ctx.graphics.element_count + 6 > ctx.graphics.batch_capacity * 6 && !grow_batch(ctx) {
+        flush_for(ctx, FlushCause::BatchFull);
+    }
 
+    ctx.graphics.vertex_data.extend_from_slice(vertices);
     ctx.graphics.element_count += 6;
 }
 
+/// Attempts to double the capacity of the batch, up to the configured maximum size.
+///
+/// Returns `false` if the batch could not grow, in which case it must be flushed instead.
+fn grow_batch(ctx: &mut Context) -> bool {
+    let graphics = &mut ctx.graphics;
+
+    if graphics.batch_capacity >= graphics.max_batch_size {
+        return false;
+    }
+
+    let new_capacity = usize::min(graphics.batch_capacity * 2, graphics.max_batch_size);
+
+    // The batched vertices only get uploaded when flushing, so the old buffers can be
+    // swapped out without losing anything.
+    match create_batch_buffers(&mut ctx.device, new_capacity) {
+        Ok((vertex_buffer, index_buffer)) => {
+            graphics.vertex_buffer = vertex_buffer;
+            graphics.index_buffer = index_buffer;
+            graphics.batch_capacity = new_capacity;
+
+            true
+        }
+
+        Err(_) => {
+            // If we can't allocate any more memory, stick with the size we've got.
+            graphics.max_batch_size = graphics.batch_capacity;
+
+            false
+        }
+    }
+}
+
+/// Draws a textured quad with arbitrary corner positions to the screen (or to a canvas,
+/// if one is enabled).
+///
+/// This allows you to draw geometry that can't be expressed via [`DrawParams`] (such as skewed
+/// sprites, trails or tiles with per-corner lighting), while still taking advantage of Tetra's
+/// sprite batching. As long as the texture, shader and other render state stay the same,
+/// consecutive quads will be submitted to the GPU in a single draw call, without having to
+/// allocate a [`Mesh`].
+///
+/// * `source` is the region of the texture to draw, in pixels. To draw the whole texture,
+///   pass a rectangle with the same size as the texture.
+/// * `corners` is the position of each corner of the quad, in the order top-left, bottom-left,
+///   bottom-right, top-right. The transform matrix will be applied to these positions.
+/// * `colors` is the color that each corner should be multiplied by, in the same order as
+///   `corners`. Colors will be interpolated across the quad.
+///
+/// Note that the corners must be in counter-clockwise order when displayed on screen,
+/// otherwise the quad will be culled (see [`set_front_face_winding`]). To mirror a quad,
+/// flip the `source` rectangle (e.g. by giving it a negative width) rather than the corners.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{self, Color, Rectangle, Texture};
+/// # use tetra::math::Vec2;
+/// # fn draw(ctx: &mut Context, texture: &Texture) {
+/// // Draw a sprite skewed to the right, fading out towards the bottom:
+/// graphics::draw_quad(
+///     ctx,
+///     texture,
+///     Rectangle::new(0.0, 0.0, 16.0, 16.0),
+///     [
+///         Vec2::new(16.0, 0.0),
+///         Vec2::new(0.0, 32.0),
+///         Vec2::new(32.0, 32.0),
+///         Vec2::new(48.0, 0.0),
+///     ],
+///     [Color::WHITE, Color::BLACK, Color::BLACK, Color::WHITE],
+/// );
+/// # }
+/// ```
+pub fn draw_quad(
+    ctx: &mut Context,
+    texture: &Texture,
+    source: Rectangle,
+    corners: [Vec2<f32>; 4],
+    colors: [Color; 4],
+) {
+    let texture_width = texture.width() as f32;
+    let texture_height = texture.height() as f32;
+
+    let u1 = source.x / texture_width;
+    let v1 = source.y / texture_height;
+    let u2 = (source.x + source.width) / texture_width;
+    let v2 = (source.y + source.height) / texture_height;
+
+    set_texture(ctx, texture);
+
+    push_quad_vertices(
+        ctx,
+        &[
+            Vertex::new(corners[0], Vec2::new(u1, v1), colors[0]),
+            Vertex::new(corners[1], Vec2::new(u1, v2), colors[1]),
+            Vertex::new(corners[2], Vec2::new(u2, v2), colors[2]),
+            Vertex::new(corners[3], Vec2::new(u2, v1), colors[3]),
+        ],
+    );
+}
+
 /// Draws an object to the screen (or to a canvas, if one is enabled).
 ///
 /// This function simply calls [`Drawable::draw`] on the passed object - it is
@@ -230,7 +419,7 @@ pub(crate) fn set_texture(ctx: &mut Context, texture: &Texture) {
 
 pub(crate) fn set_texture_ex(ctx: &mut Context, texture: ActiveTexture) {
     if texture != ctx.graphics.texture {
-        flush(ctx);
+        flush_for(ctx, FlushCause::TextureChange);
         ctx.graphics.texture = texture;
     }
 }
@@ -251,7 +440,7 @@ pub fn reset_shader(ctx: &mut Context) {
 
 pub(crate) fn set_shader_ex(ctx: &mut Context, shader: ActiveShader) {
     if shader != ctx.graphics.shader {
-        flush(ctx);
+        flush_for(ctx, FlushCause::ShaderChange);
         ctx.graphics.shader = shader;
     }
 }
@@ -271,7 +460,15 @@ pub fn reset_canvas(ctx: &mut Context) {
 
 pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: ActiveCanvas) {
     if canvas != ctx.graphics.canvas {
-        flush(ctx);
+        flush_for(ctx, FlushCause::CanvasChange);
+
+        // Now that we're done rendering to the old canvas, its mipmaps need to be
+        // brought up to date with the new contents.
+        if let ActiveCanvas::User(old) = &ctx.graphics.canvas {
+            ctx.device
+                .generate_texture_mipmaps(&old.texture.data.handle);
+        }
+
         ctx.graphics.canvas = canvas;
 
         match &ctx.graphics.canvas {
@@ -294,6 +491,239 @@ pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: ActiveCanvas) {
                 ctx.device.viewport(0, 0, width, height);
             }
         }
+
+        apply_scissor(ctx);
+    }
+}
+
+/// Returns the scissor rectangle that is currently being used for rendering, if there is one.
+pub fn get_scissor(ctx: &Context) -> Option<Rectangle<i32>> {
+    ctx.graphics.scissor_stack.last().copied()
+}
+
+/// Sets the scissor rectangle that is currently being used for rendering.
+///
+/// While a scissor rectangle is set, anything drawn outside of its bounds will be
+/// discarded. This includes calls to [`clear`]. The rectangle is specified in pixels,
+/// relative to the top left of the screen (or the canvas, if one is enabled).
+///
+/// This will replace any scissor rectangles that were previously set or pushed
+/// via [`push_scissor`].
+///
+/// If the scissor rectangle is different from the one that is currently in use, this will
+/// trigger a [`flush`] to the graphics hardware.
+pub fn set_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
+    update_scissor_stack(ctx, |stack| {
+        stack.clear();
+        stack.push(scissor_rect);
+    });
+}
+
+/// Disables any scissor rectangles that are currently being used for rendering.
+///
+/// If a scissor rectangle was previously in use, this will trigger a [`flush`] to the
+/// graphics hardware.
+pub fn reset_scissor(ctx: &mut Context) {
+    update_scissor_stack(ctx, |stack| stack.clear());
+}
+
+/// Pushes a new scissor rectangle onto the stack.
+///
+/// The new rectangle will be clipped to the bounds of the current scissor rectangle
+/// (if there is one), which makes it possible to nest clipped regions - for example,
+/// a scrolling list within a UI panel. Calling [`pop_scissor`] will restore the previous
+/// scissor rectangle.
+///
+/// If the scissor rectangle is different from the one that is currently in use, this will
+/// trigger a [`flush`] to the graphics hardware.
+pub fn push_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
+    update_scissor_stack(ctx, |stack| {
+        let clipped = match stack.last() {
+            Some(current) => current
+                .intersection(&scissor_rect)
+                .unwrap_or_else(|| Rectangle::new(scissor_rect.x, scissor_rect.y, 0, 0)),
+            None => scissor_rect,
+        };
+
+        stack.push(clipped);
+    });
+}
+
+/// Pops the current scissor rectangle off of the stack, restoring the previous one.
+///
+/// If there are no scissor rectangles on the stack, this function does nothing.
+///
+/// If the scissor rectangle is different from the one that was previously in use, this
+/// will trigger a [`flush`] to the graphics hardware.
+pub fn pop_scissor(ctx: &mut Context) {
+    update_scissor_stack(ctx, |stack| {
+        stack.pop();
+    });
+}
+
+fn update_scissor_stack<F>(ctx: &mut Context, f: F)
+where
+    F: FnOnce(&mut Vec<Rectangle<i32>>),
+{
+    let previous = get_scissor(ctx);
+
+    f(&mut ctx.graphics.scissor_stack);
+
+    if get_scissor(ctx) != previous {
+        // The GPU-side scissor state hasn't been changed yet, so any queued data
+        // will still be drawn using the previous scissor rectangle.
+        flush(ctx);
+        apply_scissor(ctx);
+    }
+}
+
+fn apply_scissor(ctx: &mut Context) {
+    let scissor_rect = match get_scissor(ctx) {
+        Some(r) => r,
+        None => {
+            ctx.device.scissor_test(false);
+            return;
+        }
+    };
+
+    let (x, y, width, height) = match &ctx.graphics.canvas {
+        ActiveCanvas::Window => {
+            // The scissor rectangle is specified in window co-ordinates, but OpenGL
+            // expects it in physical pixels, with the origin at the bottom left.
+            let (window_width, window_height) = window::get_size(ctx);
+            let (pixel_width, pixel_height) = ctx.window.get_drawable_size();
+
+            let scale_x = pixel_width as f32 / window_width.max(1) as f32;
+            let scale_y = pixel_height as f32 / window_height.max(1) as f32;
+
+            let x = (scissor_rect.x as f32 * scale_x).round() as i32;
+            let width = (scissor_rect.width as f32 * scale_x).round() as i32;
+            let height = (scissor_rect.height as f32 * scale_y).round() as i32;
+            let y = pixel_height - (scissor_rect.y as f32 * scale_y).round() as i32 - height;
+
+            (x, y, width, height)
+        }
+
+        // Canvas rendering is effectively done upside-down, so the co-ordinates
+        // already match what OpenGL expects.
+        ActiveCanvas::User(_) => (
+            scissor_rect.x,
+            scissor_rect.y,
+            scissor_rect.width,
+            scissor_rect.height,
+        ),
+    };
+
+    ctx.device.scissor_test(true);
+    ctx.device.scissor(x, y, width.max(0), height.max(0));
+}
+
+/// Returns the blend mode that is currently being used for rendering.
+pub fn get_blend_mode(ctx: &Context) -> BlendMode {
+    ctx.graphics.blend_mode
+}
+
+/// Sets the blend mode that is currently being used for rendering.
+///
+/// The blend mode determines how newly drawn content is combined with what has already
+/// been drawn to the screen (or to a canvas, if one is enabled).
+///
+/// If the blend mode is different from the one that is currently in use, this will trigger a
+/// [`flush`] to the graphics hardware.
+pub fn set_blend_mode(ctx: &mut Context, blend_mode: BlendMode) {
+    if blend_mode != ctx.graphics.blend_mode {
+        flush(ctx);
+        ctx.graphics.blend_mode = blend_mode;
+
+        ctx.device.set_blend_mode(blend_mode);
+    }
+}
+
+/// Sets the renderer back to using the default blend mode ([`BlendMode::Alpha`]).
+pub fn reset_blend_mode(ctx: &mut Context) {
+    set_blend_mode(ctx, BlendMode::Alpha);
+}
+
+/// Returns the stencil state that is currently being used for rendering.
+pub fn get_stencil_state(ctx: &Context) -> StencilState {
+    ctx.graphics.stencil_state
+}
+
+/// Sets the stencil state that is currently being used for rendering.
+///
+/// This determines how subsequent drawing operations read from and write to the
+/// stencil buffer of the current render target. See [`StencilState`] for more details.
+///
+/// If the stencil state is different from the one that is currently in use, this will
+/// trigger a [`flush`] to the graphics hardware.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{self, Mesh, StencilAction, StencilState, StencilTest, Texture};
+/// # use tetra::math::Vec2;
+/// # fn draw(ctx: &mut Context, mask: &Mesh, texture: &Texture) {
+/// graphics::clear_stencil(ctx, 0);
+///
+/// // Draw the mask into the stencil buffer, without drawing it to the screen:
+/// graphics::set_color_mask(ctx, false, false, false, false);
+/// graphics::set_stencil_state(ctx, StencilState::write(StencilAction::Replace, 1));
+/// graphics::draw(ctx, mask, Vec2::zero());
+/// graphics::set_color_mask(ctx, true, true, true, true);
+///
+/// // Only draw the texture where the mask was drawn:
+/// graphics::set_stencil_state(ctx, StencilState::read(StencilTest::EqualTo, 1));
+/// graphics::draw(ctx, texture, Vec2::zero());
+///
+/// graphics::set_stencil_state(ctx, StencilState::disabled());
+/// # }
+/// ```
+pub fn set_stencil_state(ctx: &mut Context, stencil_state: StencilState) {
+    if stencil_state != ctx.graphics.stencil_state {
+        flush(ctx);
+        ctx.graphics.stencil_state = stencil_state;
+
+        ctx.device.set_stencil_state(stencil_state);
+    }
+}
+
+/// Clears the stencil buffer of the current render target to the specified value.
+///
+/// This will trigger a [`flush`] to the graphics hardware, so that anything queued up
+/// for drawing is rendered with the previous contents of the stencil buffer.
+pub fn clear_stencil(ctx: &mut Context, value: u8) {
+    flush(ctx);
+
+    ctx.device.clear_stencil(value);
+
+    // Clearing overrides the write mask, so the current state needs to be re-applied.
+    ctx.device.set_stencil_state(ctx.graphics.stencil_state);
+}
+
+/// Returns which color channels are currently being written to when rendering.
+///
+/// The values are returned in the order `(red, green, blue, alpha)`.
+pub fn get_color_mask(ctx: &Context) -> (bool, bool, bool, bool) {
+    ctx.graphics.color_mask
+}
+
+/// Sets which color channels will be written to when rendering.
+///
+/// Disabling all of the channels can be useful when drawing into the stencil buffer,
+/// if you do not want the shapes to also be visible. Note that this also affects
+/// [`clear`], so make sure to restore the mask afterwards.
+///
+/// If the color mask is different from the one that is currently in use, this will
+/// trigger a [`flush`] to the graphics hardware.
+pub fn set_color_mask(ctx: &mut Context, red: bool, green: bool, blue: bool, alpha: bool) {
+    let color_mask = (red, green, blue, alpha);
+
+    if color_mask != ctx.graphics.color_mask {
+        flush(ctx);
+        ctx.graphics.color_mask = color_mask;
+
+        ctx.device.set_color_mask(red, green, blue, alpha);
     }
 }
 
@@ -304,6 +734,19 @@ pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: ActiveCanvas) {
 /// as this will reduce the number of draw calls made to the
 /// graphics device.
 pub fn flush(ctx: &mut Context) {
+    flush_for(ctx, FlushCause::Other);
+}
+
+/// The reasons that the sprite batch can be flushed, for the purposes of [`RenderStats`].
+enum FlushCause {
+    TextureChange,
+    ShaderChange,
+    CanvasChange,
+    BatchFull,
+    Other,
+}
+
+fn flush_for(ctx: &mut Context, cause: FlushCause) {
     if !ctx.graphics.vertex_data.is_empty() {
         let texture = match &ctx.graphics.texture {
             ActiveTexture::Default => return,
@@ -323,6 +766,11 @@ pub fn flush(ctx: &mut Context) {
             Color::WHITE,
         );
 
+        // Orphaning the buffer before writing to it means that we don't have to wait for the
+        // GPU to finish with the previous batch's data.
+        ctx.device
+            .orphan_vertex_buffer(&ctx.graphics.vertex_buffer, BufferUsage::Stream);
+
         ctx.device.set_vertex_buffer_data(
             &ctx.graphics.vertex_buffer,
             bytemuck::cast_slice(&ctx.graphics.vertex_data),
@@ -333,11 +781,24 @@ pub fn flush(ctx: &mut Context) {
             &ctx.graphics.vertex_buffer,
             &ctx.graphics.index_buffer,
             &texture.data.handle,
-            &shader.data.handle,
+            &shader.data.handle.borrow(),
             0,
             ctx.graphics.element_count,
         );
 
+        let stats = &mut ctx.graphics.stats;
+
+        stats.draw_calls += 1;
+        stats.vertices += ctx.graphics.vertex_data.len();
+
+        match cause {
+            FlushCause::TextureChange => stats.texture_flushes += 1,
+            FlushCause::ShaderChange => stats.shader_flushes += 1,
+            FlushCause::CanvasChange => stats.canvas_flushes += 1,
+            FlushCause::BatchFull => stats.batch_full_flushes += 1,
+            FlushCause::Other => stats.other_flushes += 1,
+        }
+
         ctx.graphics.vertex_data.clear();
         ctx.graphics.element_count = 0;
     }
@@ -353,7 +814,132 @@ pub fn flush(ctx: &mut Context) {
 pub fn present(ctx: &mut Context) {
     flush(ctx);
 
+    // This is the only point at which the backbuffer is guaranteed to contain
+    // the complete frame.
+    if ctx.graphics.screenshot_requested {
+        ctx.graphics.screenshot_requested = false;
+
+        let (width, height) = window::get_physical_size(ctx);
+
+        ctx.graphics.screenshot = if width > 0 && height > 0 {
+            let data = read_window_pixels(ctx, width, height);
+            ImageData::from_rgba(width, height, data).ok()
+        } else {
+            None
+        };
+    }
+
     ctx.window.swap_buffers();
+
+    ctx.graphics.stats = RenderStats::default();
+}
+
+/// Returns statistics about the rendering work that has been done so far this frame.
+///
+/// This can be useful for checking how effectively your draw calls are being batched,
+/// and for finding out what is causing the batch to be broken up.
+/// The per-frame statistics are reset when [`present`] is called, so calling this at the end
+/// of [`State::draw`](crate::State::draw) will give you the totals for the frame.
+///
+/// Note that anything still waiting in the sprite batch will not be included until
+/// the next [`flush`].
+pub fn get_stats(ctx: &Context) -> RenderStats {
+    RenderStats {
+        texture_memory: ctx.device.texture_memory(),
+        buffer_memory: ctx.device.buffer_memory(),
+        ..ctx.graphics.stats
+    }
+}
+
+/// Requests that the contents of the window be captured the next time that [`present`]
+/// is called (which usually happens at the end of the current frame).
+///
+/// The capture is performed after the final [`flush`] of the frame, so it will contain
+/// exactly what is displayed on the screen. Once it has been taken, it can be retrieved via
+/// [`take_screenshot`].
+///
+/// # Performance
+///
+/// Capturing a screenshot is a relatively expensive operation, as the CPU has to wait for
+/// the GPU to finish rendering. Try to avoid requesting one every frame.
+pub fn request_screenshot(ctx: &mut Context) {
+    ctx.graphics.screenshot_requested = true;
+}
+
+/// Returns the screenshot that was captured in response to [`request_screenshot`], if
+/// there is one.
+///
+/// The image's dimensions will match the value of [`window::get_physical_size`] at the
+/// time of the capture (which may be larger than the window size, if high-DPI rendering
+/// is enabled). It will not be affected by the active canvas, if there is one.
+///
+/// The screenshot will only be returned once - subsequent calls will return [`None`],
+/// until another screenshot is captured. If the window had no drawable area when
+/// the screenshot was captured, [`None`] will be returned.
+pub fn take_screenshot(ctx: &mut Context) -> Option<ImageData> {
+    ctx.graphics.screenshot.take()
+}
+
+fn read_window_pixels(ctx: &mut Context, width: i32, height: i32) -> Vec<u8> {
+    if let ActiveCanvas::User(_) = &ctx.graphics.canvas {
+        ctx.device.bind_framebuffer(None);
+    }
+
+    let data = ctx.device.read_pixels(0, 0, width, height);
+
+    if let ActiveCanvas::User(canvas) = &ctx.graphics.canvas {
+        ctx.device.bind_framebuffer(Some(&canvas.framebuffer));
+    }
+
+    // OpenGL returns the rows from bottom to top, so they need to be flipped.
+    data.chunks_exact(width as usize * 4)
+        .rev()
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Captures the current contents of the window immediately, and saves them to a PNG file.
+///
+/// The image's dimensions will match [`window::get_physical_size`] (which may be larger
+/// than the window size, if high-DPI rendering is enabled). This will not be affected by
+/// the active canvas, if there is one.
+///
+/// This will trigger a [`flush`] to the graphics hardware, so that everything that has been
+/// drawn so far is included. However, anything that is drawn after this function is called
+/// will not be captured, and calling it outside of [`State::draw`](crate::State::draw) will
+/// not give useful results, as the contents of the window are undefined after they have been
+/// presented. To capture the finished frame instead, use [`request_screenshot`].
+///
+/// # Errors
+///
+/// * [`TetraError::FailedToSaveAsset`](crate::TetraError::FailedToSaveAsset) will be returned
+///   if the file could not be written.
+/// * [`TetraError::InvalidTexture`](crate::TetraError::InvalidTexture) will be returned if
+///   the window has no drawable area, or if the image could not be encoded.
+///
+/// # Performance
+///
+/// This is a relatively expensive operation, as the CPU has to wait for the GPU to finish
+/// rendering. Try to avoid calling it every frame.
+#[cfg(feature = "texture_png")]
+pub fn save_screenshot<P>(ctx: &mut Context, path: P) -> Result
+where
+    P: AsRef<Path>,
+{
+    flush(ctx);
+
+    let (width, height) = window::get_physical_size(ctx);
+
+    if width <= 0 || height <= 0 {
+        return Err(TetraError::InvalidTexture(ImageError::Parameter(
+            ParameterError::from_kind(ParameterErrorKind::DimensionMismatch),
+        )));
+    }
+
+    let data = read_window_pixels(ctx, width, height);
+
+    fs::write_image(path, &data, width, height, ImageFormat::Png)
 }
 
 /// Returns the filter mode that will be used by newly created textures and canvases.
@@ -458,6 +1044,8 @@ pub(crate) fn set_viewport_size(
     if let ActiveCanvas::Window = ctx.graphics.canvas {
         ctx.graphics.projection_matrix = ortho(width as f32, height as f32, false);
         ctx.device.viewport(0, 0, pixel_width, pixel_height);
+
+        apply_scissor(ctx);
     }
 }
 
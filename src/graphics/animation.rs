@@ -1,61 +1,112 @@
 //! Functions and types relating to animations.
 
+use std::collections::VecDeque;
 use std::time::Duration;
 
+use hashbrown::HashMap;
+
 use crate::graphics::texture::Texture;
-use crate::graphics::{DrawParams, Drawable, Rectangle};
+use crate::graphics::{DrawParams, Drawable, Rectangle, TextureRegion};
 use crate::time;
 use crate::Context;
 
-/// An animation, cycling between regions of a texture at a regular interval.
+/// An animation, cycling between regions of a texture over time.
+///
+/// Each frame of the animation is a [`TextureRegion`], so the frames can come from a
+/// spritesheet, a [`TextureAtlas`](super::TextureAtlas), or even from multiple textures.
+/// Trimmed regions are supported, and will be positioned as if they were untrimmed.
 ///
 /// Calling [`advance`](Self::advance) or [`advance`](Self::advance_by) within [`State::draw`](crate::State::draw)
 /// will drive the animation, switching the texture region once the specified
 /// time has passed.
 ///
+/// Each frame can have its own length, and the animation can be played
+/// [forwards, backwards or back-and-forth](AnimationDirection), at different
+/// [speeds](Self::set_speed), and either indefinitely or a [set number of times](Self::set_loop_limit).
+///
 /// # Examples
 ///
 /// The [`animation`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation.rs)
 /// example demonstrates basic usage of an `Animation` with a spritesheet.
 ///
 /// The [`animation_controller`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation_controller.rs)
-/// example demonstrates how multiple `Animation`s can be combined using a
-/// simple state machine.
+/// example demonstrates how multiple `Animation`s can be combined using an
+/// [`AnimationController`].
 #[derive(Debug, Clone)]
 pub struct Animation {
-    texture: Texture,
-    frames: Vec<Rectangle>,
-    frame_length: Duration,
+    regions: Vec<TextureRegion>,
+    frame_lengths: Vec<Duration>,
+    direction: AnimationDirection,
+    loop_limit: Option<u32>,
+    speed: f32,
 
     current_frame: usize,
+    position: usize,
     timer: Duration,
-    repeating: bool,
+    loops_completed: u32,
 }
 
 impl Animation {
     /// Creates a new looping animation.
+    ///
+    /// Each of the `frames` is a section of the `texture`.
     pub fn new(texture: Texture, frames: Vec<Rectangle>, frame_length: Duration) -> Animation {
-        Animation {
-            texture,
-            frames,
-            frame_length,
+        let regions = to_regions(&texture, &frames);
 
-            current_frame: 0,
-            timer: Duration::from_secs(0),
-            repeating: true,
-        }
+        Animation::from_regions(regions, frame_length)
     }
 
     /// Creates a new animation that does not repeat once all of the frames have been displayed.
+    ///
+    /// Each of the `frames` is a section of the `texture`.
     pub fn once(texture: Texture, frames: Vec<Rectangle>, frame_length: Duration) -> Animation {
+        let mut animation = Animation::new(texture, frames, frame_length);
+        animation.loop_limit = Some(1);
+        animation
+    }
+
+    /// Creates a new looping animation from a list of texture regions.
+    pub fn from_regions(frames: Vec<TextureRegion>, frame_length: Duration) -> Animation {
+        let frame_lengths = vec![frame_length; frames.len()];
+
+        Animation::from_regions_with_lengths(frames, frame_lengths)
+    }
+
+    /// Creates a new animation from a list of texture regions, which does not repeat once
+    /// all of the frames have been displayed.
+    pub fn once_from_regions(frames: Vec<TextureRegion>, frame_length: Duration) -> Animation {
+        let mut animation = Animation::from_regions(frames, frame_length);
+        animation.loop_limit = Some(1);
+        animation
+    }
+
+    /// Creates a new looping animation from a list of texture regions, with a different
+    /// length for each frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn from_regions_with_lengths(
+        frames: Vec<TextureRegion>,
+        frame_lengths: Vec<Duration>,
+    ) -> Animation {
+        assert_eq!(
+            frames.len(),
+            frame_lengths.len(),
+            "each frame of an animation must have a length"
+        );
+
         Animation {
-            texture,
-            frames,
-            frame_length,
+            regions: frames,
+            frame_lengths,
+            direction: AnimationDirection::Forward,
+            loop_limit: None,
+            speed: 1.0,
 
             current_frame: 0,
+            position: 0,
             timer: Duration::from_secs(0),
-            repeating: false,
+            loops_completed: 0,
         }
     }
 
@@ -75,32 +126,107 @@ impl Animation {
     /// Advances the animation's timer by a specified amount, switching the texture
     /// region if required.
     ///
-    /// If the specified duration is longer than the frame length, frames will be
+    /// The duration will be multiplied by the animation's [`speed`](Self::speed). If the
+    /// resulting duration is longer than the current frame's length, frames will be
     /// skipped.
     pub fn advance_by(&mut self, duration: Duration) {
-        self.timer += duration;
+        self.advance_by_with(duration, None, |_| {});
+    }
 
-        let frames_remaining = self.current_frame < self.frames.len() - 1;
+    /// Advances the animation's timer, calling `on_frame` with the index of each frame
+    /// that is entered (including any that are skipped over).
+    ///
+    /// If `stop_after` is set, the animation will stop once it has completed that many
+    /// loops, as if it had finished.
+    ///
+    /// Returns the amount of time that was left over after the animation finished or
+    /// stopped, before it was multiplied by the speed.
+    fn advance_by_with<F>(
+        &mut self,
+        duration: Duration,
+        stop_after: Option<u32>,
+        mut on_frame: F,
+    ) -> Duration
+    where
+        F: FnMut(usize),
+    {
+        if self.frame_lengths.is_empty() {
+            return duration;
+        }
+
+        if self.speed == 1.0 {
+            self.timer += duration;
+        } else {
+            self.timer += duration.mul_f64(f64::from(self.speed));
+        }
+
+        // If every frame is zero-length, a looping animation would never stop advancing.
+        if self.loop_limit.is_none() && self.frame_lengths.iter().all(|l| l.as_nanos() == 0) {
+            self.timer = Duration::from_secs(0);
+            return Duration::from_secs(0);
+        }
+
+        loop {
+            let frame_length = self.frame_lengths[self.current_frame];
 
-        if frames_remaining || self.repeating {
-            while self.timer >= self.frame_length {
-                self.current_frame = (self.current_frame + 1) % self.frames.len();
-                self.timer -= self.frame_length;
+            if self.is_finished() || matches!(stop_after, Some(n) if self.loops_completed >= n) {
+                if self.timer > frame_length {
+                    let leftover = self.timer - frame_length;
+                    self.timer = frame_length;
+
+                    // The timer can only overshoot with a speed of zero if it was set manually.
+                    if self.speed > 0.0 {
+                        return leftover.div_f64(f64::from(self.speed));
+                    }
+                }
+
+                break;
+            }
+
+            if self.timer < frame_length {
+                break;
+            }
+
+            if self.position + 1 < self.cycle_length() {
+                self.position += 1;
+            } else {
+                self.loops_completed += 1;
+
+                if self.is_finished() || stop_after == Some(self.loops_completed) {
+                    continue;
+                }
+
+                self.position = 0;
             }
-        } else if self.timer > self.frame_length {
-            self.timer = self.frame_length;
+
+            self.timer -= frame_length;
+            self.current_frame =
+                frame_index(self.direction, self.frame_lengths.len(), self.position);
+
+            on_frame(self.current_frame);
         }
+
+        Duration::from_secs(0)
     }
 
     /// Restarts the animation from the first frame.
+    ///
+    /// This also resets the number of [completed loops](Self::loops_completed).
     pub fn restart(&mut self) {
-        self.current_frame = 0;
+        self.position = 0;
+        self.current_frame = frame_index(self.direction, self.frame_lengths.len(), 0);
         self.timer = Duration::from_secs(0);
+        self.loops_completed = 0;
     }
 
     /// Returns a reference to the texture currently being used by the animation.
-    pub fn texture(&self) -> &Texture {
-        &self.texture
+    ///
+    /// If the animation's frames come from multiple textures, this is the texture of
+    /// the current frame. If the animation has no frames, this will return `None`.
+    pub fn texture(&self) -> Option<&Texture> {
+        self.regions
+            .get(self.current_frame)
+            .map(TextureRegion::texture)
     }
 
     /// Sets the texture that will be used by the animation.
@@ -108,43 +234,184 @@ impl Animation {
     /// This method will not change the frame definitions or current state of the animation,
     /// so it can be used for e.g. swapping spritesheets. If you need to change the slicing
     /// for the new texture, call [`set_frames`](Self::set_frames).
+    ///
+    /// If the animation's frames came from multiple textures, every frame will now
+    /// use the new texture.
     pub fn set_texture(&mut self, texture: Texture) {
-        self.texture = texture;
+        for region in &mut self.regions {
+            region.set_texture(texture.clone());
+        }
     }
 
     /// Gets the sections of the texture being displayed for each frame of the animation.
-    pub fn frames(&self) -> &[Rectangle] {
-        &self.frames
+    ///
+    /// These are the bounds of each of the animation's [`regions`](Self::regions).
+    pub fn frames(&self) -> Vec<Rectangle> {
+        self.regions.iter().map(TextureRegion::bounds).collect()
     }
 
     /// Sets the sections of the texture being displayed for each frame of the animation.
     ///
+    /// If there are more frames than there were previously, the new frames will have the
+    /// same length as the previous last frame. If you need to set the length of each frame,
+    /// call [`set_frame_lengths`](Self::set_frame_lengths).
+    ///
+    /// The new frames will be sections of the first frame's texture. An animation with no
+    /// frames has no texture to take sections from, so calling this method on one will
+    /// have no effect - use [`set_regions`](Self::set_regions) instead.
+    ///
     /// This method will reset the animation back to frame zero.
     pub fn set_frames(&mut self, new_frames: Vec<Rectangle>) {
-        self.frames = new_frames;
+        if let Some(region) = self.regions.first() {
+            let regions = to_regions(region.texture(), &new_frames);
+
+            self.set_regions(regions);
+        }
+    }
+
+    /// Gets the texture regions being displayed for each frame of the animation.
+    pub fn regions(&self) -> &[TextureRegion] {
+        &self.regions
+    }
+
+    /// Sets the texture regions being displayed for each frame of the animation.
+    ///
+    /// If there are more frames than there were previously, the new frames will have the
+    /// same length as the previous last frame. If you need to set the length of each frame,
+    /// call [`set_frame_lengths`](Self::set_frame_lengths).
+    ///
+    /// This method will reset the animation back to frame zero.
+    pub fn set_regions(&mut self, new_regions: Vec<TextureRegion>) {
+        let last_length = self
+            .frame_lengths
+            .last()
+            .copied()
+            .unwrap_or_else(|| Duration::from_secs(0));
+
+        self.frame_lengths.resize(new_regions.len(), last_length);
+        self.regions = new_regions;
 
         self.restart();
     }
 
-    /// Gets the amount of time that each frame of the animation lasts for.
+    /// Gets the amount of time that the current frame of the animation lasts for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frames.
     pub fn frame_length(&self) -> Duration {
-        self.frame_length
+        self.frame_lengths[self.current_frame]
     }
 
-    /// Sets the amount of time that each frame of the animation lasts for.
+    /// Sets the amount of time that every frame of the animation lasts for.
     pub fn set_frame_length(&mut self, new_frame_length: Duration) {
-        self.frame_length = new_frame_length;
+        for frame_length in &mut self.frame_lengths {
+            *frame_length = new_frame_length;
+        }
+    }
+
+    /// Gets the amount of time that each frame of the animation lasts for.
+    pub fn frame_lengths(&self) -> &[Duration] {
+        &self.frame_lengths
+    }
+
+    /// Sets the amount of time that each frame of the animation lasts for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn set_frame_lengths(&mut self, new_frame_lengths: Vec<Duration>) {
+        assert_eq!(
+            self.frame_lengths.len(),
+            new_frame_lengths.len(),
+            "each frame of an animation must have a length"
+        );
+
+        self.frame_lengths = new_frame_lengths;
+    }
+
+    /// Gets the order in which the frames of the animation are played.
+    pub fn direction(&self) -> AnimationDirection {
+        self.direction
+    }
+
+    /// Sets the order in which the frames of the animation are played.
+    ///
+    /// This method will reset the animation back to the start.
+    pub fn set_direction(&mut self, direction: AnimationDirection) {
+        self.direction = direction;
+
+        self.restart();
     }
 
-    /// Gets whether or not the animation is currently set to repeat when it reaches the end
-    /// of the frames.
+    /// Gets whether or not the animation is currently set to repeat indefinitely when it
+    /// reaches the end of the frames.
     pub fn repeating(&self) -> bool {
-        self.repeating
+        self.loop_limit.is_none()
     }
 
     /// Sets whether or not the animation should repeat when it reaches the end of the frames.
+    ///
+    /// This is a shortcut for setting the [`loop_limit`](Self::set_loop_limit) to `None`
+    /// (if `repeating` is `true`) or `Some(1)` (if `repeating` is `false`).
     pub fn set_repeating(&mut self, repeating: bool) {
-        self.repeating = repeating;
+        self.loop_limit = if repeating { None } else { Some(1) };
+    }
+
+    /// Gets the number of times that the animation will play before stopping, or `None`
+    /// if it repeats indefinitely.
+    pub fn loop_limit(&self) -> Option<u32> {
+        self.loop_limit
+    }
+
+    /// Sets the number of times that the animation will play before stopping.
+    ///
+    /// If set to `None`, the animation will repeat indefinitely. Once the animation has
+    /// finished, it will stay on its final frame until it is [restarted](Self::restart).
+    pub fn set_loop_limit(&mut self, loop_limit: Option<u32>) {
+        self.loop_limit = loop_limit;
+    }
+
+    /// Gets the speed multiplier for the animation.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the speed multiplier for the animation.
+    ///
+    /// For example, a speed of `2.0` will play the animation twice as fast, and a speed of
+    /// `0.5` will play it at half speed. Defaults to `1.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the speed is negative, infinite or NaN.
+    pub fn set_speed(&mut self, speed: f32) {
+        assert!(
+            speed >= 0.0 && speed.is_finite(),
+            "animation speed must be positive and finite"
+        );
+
+        self.speed = speed;
+    }
+
+    /// Returns the number of times that the animation has played all the way through
+    /// since it was last [restarted](Self::restart).
+    ///
+    /// For [`PingPong`](AnimationDirection::PingPong) and [`PingPongReverse`](AnimationDirection::PingPongReverse)
+    /// animations, a loop consists of playing the frames in both directions.
+    pub fn loops_completed(&self) -> u32 {
+        self.loops_completed
+    }
+
+    /// Returns whether or not the animation has reached its [`loop_limit`](Self::loop_limit)
+    /// and stopped.
+    ///
+    /// This will always be `false` for animations that repeat indefinitely.
+    pub fn is_finished(&self) -> bool {
+        match self.loop_limit {
+            Some(limit) => self.loops_completed >= limit,
+            None => false,
+        }
     }
 
     /// Gets the index of the frame that is currently being displayed.
@@ -161,16 +428,26 @@ impl Animation {
     /// or [`advance`](Self::advance_by), but this method can be useful for more
     /// fine-grained control.
     ///
+    /// For [`PingPong`](AnimationDirection::PingPong) and [`PingPongReverse`](AnimationDirection::PingPongReverse)
+    /// animations, playback will continue from the first time that the frame is displayed
+    /// in each loop.
+    ///
     /// # Panics
     ///
     /// The index is zero-based, and must be within the bounds of the animation's
     /// [`frames`](Self::frames), otherwise this method will panic.
     pub fn set_current_frame_index(&mut self, index: usize) {
-        // Without this check, the code would panic in `Drawable::draw` because `self.frames[self.current_frame]`
+        // Without this check, the code would panic in `Drawable::draw` because `self.regions[self.current_frame]`
         // is invalid, but the developer would have no clue where it was set.
-        assert!(index < self.frames.len());
+        assert!(index < self.frame_lengths.len());
+
+        let frame_count = self.frame_lengths.len();
+        let direction = self.direction;
 
         self.current_frame = index;
+        self.position = (0..self.cycle_length())
+            .find(|&position| frame_index(direction, frame_count, position) == index)
+            .unwrap_or(0);
     }
 
     /// Gets the duration that the current frame has been visible.
@@ -194,6 +471,40 @@ impl Animation {
     pub fn set_current_frame_time(&mut self, duration: Duration) {
         self.timer = duration;
     }
+
+    /// The number of steps in a single loop of the animation.
+    fn cycle_length(&self) -> usize {
+        let frame_count = self.frame_lengths.len();
+
+        match self.direction {
+            AnimationDirection::PingPong | AnimationDirection::PingPongReverse
+                if frame_count > 1 =>
+            {
+                frame_count * 2 - 2
+            }
+            _ => frame_count,
+        }
+    }
+}
+
+/// The order in which the frames of an [`Animation`] are played.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnimationDirection {
+    /// The frames are played from first to last.
+    Forward,
+
+    /// The frames are played from last to first.
+    Reverse,
+
+    /// The frames are played from first to last, and then back again.
+    ///
+    /// The first and last frames are not repeated when the animation changes direction.
+    PingPong,
+
+    /// The frames are played from last to first, and then back again.
+    ///
+    /// The first and last frames are not repeated when the animation changes direction.
+    PingPongReverse,
 }
 
 impl Drawable for Animation {
@@ -201,22 +512,645 @@ impl Drawable for Animation {
     where
         P: Into<DrawParams>,
     {
-        let frame_clip = self.frames[self.current_frame];
+        self.regions[self.current_frame].draw(ctx, params)
+    }
+}
 
-        let mut params = params.into();
+/// A collection of named [`Animation`]s, which can be switched between and can emit
+/// events when specific frames are reached.
+///
+/// Only one of the animations is active at any given time. Calling [`play`](Self::play)
+/// switches to a different animation immediately, whereas [`queue`](Self::queue) waits
+/// for the current animation to finish (or complete its current loop, if it repeats
+/// indefinitely) before switching.
+///
+/// # Events
+///
+/// Events can be attached to specific frames of an animation via [`add_event`](Self::add_event).
+/// When one of those frames is displayed (either by the controller switching to a new
+/// animation, or by the animation being advanced), an [`AnimationEvent`] will be queued.
+/// Frames that are skipped over due to a large time step will still trigger their events.
+///
+/// Events are stored until they are retrieved via [`poll_event`](Self::poll_event), so
+/// you should poll for them regularly (e.g. after calling [`advance`](Self::advance)).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use tetra::Context;
+/// # use tetra::graphics::animation::{Animation, AnimationController};
+/// # use tetra::graphics::{Rectangle, Texture};
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let texture = Texture::new(ctx, "./player.png")?;
+/// let frames = |y| Rectangle::row(0.0, y, 16.0, 16.0).take(8).collect();
+/// let frame_length = Duration::from_millis(100);
+///
+/// let mut controller = AnimationController::new(
+///     "idle",
+///     Animation::new(texture.clone(), frames(0.0), frame_length),
+/// );
+///
+/// controller.add_animation("run", Animation::new(texture, frames(16.0), frame_length));
+/// controller.add_event("run", 3, "footstep");
+/// controller.add_event("run", 7, "footstep");
+///
+/// controller.play("run");
+/// controller.advance(ctx);
+///
+/// while let Some(event) = controller.poll_event() {
+///     if event.name == "footstep" {
+///         // Play a sound, spawn some dust, etc.
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The [`animation_controller`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation_controller.rs)
+/// example demonstrates how to switch between animations based on the player's input.
+#[derive(Debug, Clone)]
+pub struct AnimationController {
+    animations: HashMap<String, Animation>,
+    current: String,
+    queued: Option<QueuedAnimation>,
+
+    triggers: Vec<AnimationEvent>,
+    events: VecDeque<AnimationEvent>,
+}
+
+#[derive(Debug, Clone)]
+struct QueuedAnimation {
+    name: String,
+    loops_completed: u32,
+}
+
+impl AnimationController {
+    /// Creates a new controller, with the specified animation playing.
+    pub fn new<S>(name: S, animation: Animation) -> AnimationController
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+        let mut animations = HashMap::new();
 
-        params.clip = match params.clip {
-            Some(mut clip) => {
-                clip.x += frame_clip.x;
-                clip.y += frame_clip.y;
-                clip.width += frame_clip.width;
-                clip.height += frame_clip.height;
+        animations.insert(name.clone(), animation);
 
-                Some(clip)
+        let mut controller = AnimationController {
+            animations,
+            current: name,
+            queued: None,
+
+            triggers: Vec::new(),
+            events: VecDeque::new(),
+        };
+
+        controller.restart();
+        controller
+    }
+
+    /// Adds an animation to the controller.
+    ///
+    /// If an animation with the same name already exists, it will be replaced.
+    pub fn add_animation<S>(&mut self, name: S, animation: Animation)
+    where
+        S: Into<String>,
+    {
+        self.animations.insert(name.into(), animation);
+    }
+
+    /// Adds an event, which will be emitted whenever the specified frame of an
+    /// animation is displayed.
+    ///
+    /// Multiple events can be attached to the same frame.
+    pub fn add_event<A, S>(&mut self, animation: A, frame: usize, name: S)
+    where
+        A: Into<String>,
+        S: Into<String>,
+    {
+        self.triggers.push(AnimationEvent {
+            animation: animation.into(),
+            frame,
+            name: name.into(),
+        });
+    }
+
+    /// Removes all of the events attached to an animation.
+    pub fn clear_events(&mut self, animation: &str) {
+        self.triggers
+            .retain(|trigger| trigger.animation != animation);
+    }
+
+    /// Returns a reference to the animation with the specified name, or `None` if there
+    /// is no animation with that name.
+    pub fn get(&self, name: &str) -> Option<&Animation> {
+        self.animations.get(name)
+    }
+
+    /// Returns a mutable reference to the animation with the specified name, or `None`
+    /// if there is no animation with that name.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Animation> {
+        self.animations.get_mut(name)
+    }
+
+    /// Returns the name of the animation that is currently playing.
+    pub fn current_name(&self) -> &str {
+        &self.current
+    }
+
+    /// Returns a reference to the animation that is currently playing.
+    pub fn current(&self) -> &Animation {
+        &self.animations[&self.current]
+    }
+
+    /// Returns a mutable reference to the animation that is currently playing.
+    pub fn current_mut(&mut self) -> &mut Animation {
+        self.animations
+            .get_mut(&self.current)
+            .expect("current animation should exist")
+    }
+
+    /// Returns the name of the animation that will be played once the current one
+    /// finishes, if one has been [queued](Self::queue).
+    pub fn queued_name(&self) -> Option<&str> {
+        self.queued.as_ref().map(|queued| queued.name.as_str())
+    }
+
+    /// Switches to the animation with the specified name, restarting it from the
+    /// beginning.
+    ///
+    /// If the animation is already playing, it will not be restarted. Any
+    /// [queued](Self::queue) animation will be cancelled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no animation with the specified name.
+    pub fn play(&mut self, name: &str) {
+        self.queued = None;
+
+        if self.current != name {
+            self.switch_to(name);
+        }
+    }
+
+    /// Switches to the animation with the specified name once the current animation
+    /// finishes.
+    ///
+    /// If the current animation repeats indefinitely, the switch will happen at the end
+    /// of its current loop. If the current animation has already finished, the switch
+    /// will happen immediately. Only one animation can be queued at a time - queueing
+    /// another animation will replace it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no animation with the specified name.
+    pub fn queue(&mut self, name: &str) {
+        assert!(
+            self.animations.contains_key(name),
+            "no animation named '{}'",
+            name
+        );
+
+        if self.current().is_finished() {
+            self.queued = None;
+            self.switch_to(name);
+        } else {
+            self.queued = Some(QueuedAnimation {
+                name: name.to_owned(),
+                loops_completed: self.current().loops_completed(),
+            });
+        }
+    }
+
+    /// Restarts the current animation from the beginning.
+    pub fn restart(&mut self) {
+        self.current_mut().restart();
+        self.emit_events(self.current().current_frame_index());
+    }
+
+    /// Advances the current animation, switching to the queued animation if required.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed - see [`Animation::advance`] for more details.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the current animation by a specified amount, switching to the queued
+    /// animation if required.
+    ///
+    /// If the switch happens partway through the specified duration, the remaining
+    /// time will be used to advance the queued animation.
+    pub fn advance_by(&mut self, duration: Duration) {
+        let current = &self.current;
+        let triggers = &self.triggers;
+        let events = &mut self.events;
+
+        let animation = self
+            .animations
+            .get_mut(current)
+            .expect("current animation should exist");
+
+        let stop_after = self
+            .queued
+            .as_ref()
+            .map(|queued| queued.loops_completed + 1);
+
+        let leftover = animation.advance_by_with(duration, stop_after, |frame| {
+            queue_events(triggers, events, current, frame);
+        });
+
+        let should_switch = match &self.queued {
+            Some(queued) => {
+                animation.is_finished() || animation.loops_completed() > queued.loops_completed
             }
-            None => Some(frame_clip),
+            None => false,
+        };
+
+        if should_switch {
+            if let Some(queued) = self.queued.take() {
+                self.switch_to(&queued.name);
+                self.advance_by(leftover);
+            }
+        }
+    }
+
+    /// Returns the next event that has been emitted, or `None` if there are no events
+    /// waiting.
+    pub fn poll_event(&mut self) -> Option<AnimationEvent> {
+        self.events.pop_front()
+    }
+
+    fn switch_to(&mut self, name: &str) {
+        assert!(
+            self.animations.contains_key(name),
+            "no animation named '{}'",
+            name
+        );
+
+        self.current = name.to_owned();
+        self.restart();
+    }
+
+    fn emit_events(&mut self, frame: usize) {
+        queue_events(&self.triggers, &mut self.events, &self.current, frame);
+    }
+}
+
+impl Drawable for AnimationController {
+    fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        self.current().draw(ctx, params)
+    }
+}
+
+/// An event emitted by an [`AnimationController`] when a frame is displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationEvent {
+    /// The name of the animation that emitted the event.
+    pub animation: String,
+
+    /// The index of the frame that emitted the event.
+    pub frame: usize,
+
+    /// The name of the event.
+    pub name: String,
+}
+
+fn queue_events(
+    triggers: &[AnimationEvent],
+    events: &mut VecDeque<AnimationEvent>,
+    animation: &str,
+    frame: usize,
+) {
+    events.extend(
+        triggers
+            .iter()
+            .filter(|trigger| trigger.animation == animation && trigger.frame == frame)
+            .cloned(),
+    );
+}
+
+fn to_regions(texture: &Texture, frames: &[Rectangle]) -> Vec<TextureRegion> {
+    frames
+        .iter()
+        .map(|&bounds| TextureRegion::new(texture.clone(), bounds))
+        .collect()
+}
+
+/// Returns the index of the frame that should be displayed at the given step of a loop.
+fn frame_index(direction: AnimationDirection, frame_count: usize, position: usize) -> usize {
+    if frame_count == 0 {
+        return 0;
+    }
+
+    match direction {
+        AnimationDirection::Forward => position,
+        AnimationDirection::Reverse => frame_count - 1 - position,
+        AnimationDirection::PingPong => {
+            if position < frame_count {
+                position
+            } else {
+                (frame_count - 1) * 2 - position
+            }
+        }
+        AnimationDirection::PingPongReverse => {
+            if position < frame_count {
+                frame_count - 1 - position
+            } else {
+                position - (frame_count - 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an animation without a texture, which is enough to test playback.
+    fn animation(frame_lengths: &[u64]) -> Animation {
+        Animation {
+            regions: Vec::new(),
+            frame_lengths: frame_lengths
+                .iter()
+                .map(|&ms| Duration::from_millis(ms))
+                .collect(),
+            direction: AnimationDirection::Forward,
+            loop_limit: None,
+            speed: 1.0,
+
+            current_frame: 0,
+            position: 0,
+            timer: Duration::from_secs(0),
+            loops_completed: 0,
+        }
+    }
+
+    /// Advances the animation by the specified number of milliseconds at a time, and
+    /// returns the frame that was displayed after each step.
+    fn play(animation: &mut Animation, step: u64, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animation.advance_by(Duration::from_millis(step));
+                animation.current_frame_index()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn per_frame_lengths() {
+        let mut animation = animation(&[100, 200, 50]);
+
+        assert_eq!(play(&mut animation, 50, 9), [0, 1, 1, 1, 1, 2, 0, 0, 1]);
+        assert_eq!(animation.current_frame_time(), Duration::from_millis(0));
+        assert_eq!(animation.frame_length(), Duration::from_millis(200));
+        assert_eq!(animation.loops_completed(), 1);
+    }
+
+    #[test]
+    fn directions() {
+        let mut animation = animation(&[100, 100, 100]);
+
+        animation.set_direction(AnimationDirection::Reverse);
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(play(&mut animation, 100, 4), [1, 0, 2, 1]);
+
+        animation.set_direction(AnimationDirection::PingPong);
+        assert_eq!(animation.current_frame_index(), 0);
+        assert_eq!(play(&mut animation, 100, 6), [1, 2, 1, 0, 1, 2]);
+
+        // A ping-pong loop ends when the animation gets back to the first frame.
+        assert_eq!(animation.loops_completed(), 1);
+
+        animation.set_direction(AnimationDirection::PingPongReverse);
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(play(&mut animation, 100, 4), [1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn large_deltas_skip_frames() {
+        let mut animation = animation(&[100, 100, 100, 100]);
+
+        animation.advance_by(Duration::from_millis(250));
+
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(animation.current_frame_time(), Duration::from_millis(50));
+
+        animation.advance_by(Duration::from_millis(800));
+
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(animation.current_frame_time(), Duration::from_millis(50));
+        assert_eq!(animation.loops_completed(), 2);
+    }
+
+    #[test]
+    fn loop_limit() {
+        let mut animation = animation(&[100, 100, 100]);
+        animation.set_loop_limit(Some(2));
+
+        animation.advance_by(Duration::from_millis(550));
+
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(animation.loops_completed(), 1);
+        assert!(!animation.is_finished());
+
+        // Once the animation finishes, it stays on the last frame, and the timer is
+        // clamped to the length of that frame.
+        animation.advance_by(Duration::from_millis(1000));
+
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(animation.current_frame_time(), Duration::from_millis(100));
+        assert_eq!(animation.loops_completed(), 2);
+        assert!(animation.is_finished());
+
+        animation.advance_by(Duration::from_millis(1000));
+
+        assert_eq!(animation.current_frame_index(), 2);
+        assert_eq!(animation.current_frame_time(), Duration::from_millis(100));
+        assert_eq!(animation.loops_completed(), 2);
+
+        animation.restart();
+
+        assert_eq!(animation.current_frame_index(), 0);
+        assert_eq!(animation.loops_completed(), 0);
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn speed() {
+        let mut animation = animation(&[100, 100, 100]);
+
+        animation.set_speed(2.0);
+        assert_eq!(play(&mut animation, 50, 3), [1, 2, 0]);
+
+        animation.set_speed(0.5);
+        assert_eq!(play(&mut animation, 100, 3), [0, 1, 1]);
+
+        animation.set_speed(0.0);
+        assert_eq!(play(&mut animation, 1000, 1), [1]);
+    }
+
+    fn controller() -> AnimationController {
+        let mut controller = AnimationController::new("idle", animation(&[100, 100]));
+
+        let mut attack = animation(&[100, 100]);
+        attack.set_loop_limit(Some(1));
+
+        controller.add_animation("run", animation(&[100, 100, 100, 100]));
+        controller.add_animation("attack", attack);
+
+        controller
+    }
+
+    fn poll_events(controller: &mut AnimationController) -> Vec<String> {
+        std::iter::from_fn(|| controller.poll_event())
+            .map(|event| format!("{} {}", event.animation, event.name))
+            .collect()
+    }
+
+    #[test]
+    fn play_switches_immediately() {
+        let mut controller = controller();
+
+        controller.advance_by(Duration::from_millis(150));
+        controller.queue("attack");
+        controller.play("run");
+
+        assert_eq!(controller.current_name(), "run");
+        assert_eq!(controller.current().current_frame_index(), 0);
+        assert_eq!(controller.queued_name(), None);
+
+        // Playing the current animation again should not restart it.
+        controller.advance_by(Duration::from_millis(150));
+        controller.play("run");
+
+        assert_eq!(controller.current().current_frame_index(), 1);
+        assert_eq!(
+            controller.current().current_frame_time(),
+            Duration::from_millis(50)
+        );
+    }
+
+    #[test]
+    fn queue_waits_for_current_animation() {
+        let mut controller = controller();
+
+        // Looping animations switch at the end of their current loop.
+        controller.advance_by(Duration::from_millis(150));
+        controller.queue("attack");
+        controller.advance_by(Duration::from_millis(40));
+
+        assert_eq!(controller.current_name(), "idle");
+        assert_eq!(controller.queued_name(), Some("attack"));
+
+        // The time left over after the switch is carried into the queued animation.
+        controller.advance_by(Duration::from_millis(120));
+
+        assert_eq!(controller.current_name(), "attack");
+        assert_eq!(controller.queued_name(), None);
+        assert_eq!(controller.current().current_frame_index(), 1);
+        assert_eq!(
+            controller.current().current_frame_time(),
+            Duration::from_millis(10)
+        );
+
+        // Animations with a loop limit switch once they have finished.
+        controller.queue("run");
+        controller.advance_by(Duration::from_millis(350));
+
+        assert_eq!(controller.current_name(), "run");
+        assert_eq!(controller.current().current_frame_index(), 2);
+        assert_eq!(
+            controller.current().current_frame_time(),
+            Duration::from_millis(60)
+        );
+    }
+
+    #[test]
+    fn queue_switches_if_finished() {
+        let mut controller = controller();
+
+        controller.play("attack");
+        controller.advance_by(Duration::from_millis(500));
+
+        assert!(controller.current().is_finished());
+
+        controller.queue("run");
+
+        assert_eq!(controller.current_name(), "run");
+        assert_eq!(controller.queued_name(), None);
+    }
+
+    #[test]
+    fn events_trigger_on_skipped_frames() {
+        let mut controller = controller();
+
+        controller.add_event("run", 0, "start");
+        controller.add_event("run", 1, "step");
+        controller.add_event("run", 2, "step");
+        controller.add_event("run", 2, "dust");
+
+        controller.play("run");
+        controller.advance_by(Duration::from_millis(250));
+
+        assert_eq!(
+            poll_events(&mut controller),
+            ["run start", "run step", "run step", "run dust"]
+        );
+
+        assert_eq!(controller.poll_event(), None);
+
+        controller.clear_events("run");
+        controller.advance_by(Duration::from_millis(1000));
+
+        assert_eq!(controller.poll_event(), None);
+    }
+
+    #[test]
+    fn events_are_polled_in_order() {
+        let mut controller = controller();
+
+        controller.add_event("idle", 0, "start");
+        controller.add_event("idle", 1, "end");
+        controller.add_event("run", 0, "start");
+        controller.add_event("run", 1, "step");
+
+        controller.restart();
+
+        assert_eq!(poll_events(&mut controller), ["idle start"]);
+
+        // The idle animation should not loop back round to its first frame before
+        // the queued animation takes over.
+        controller.queue("run");
+        controller.advance_by(Duration::from_millis(350));
+
+        assert_eq!(
+            poll_events(&mut controller),
+            ["idle end", "run start", "run step"]
+        );
+    }
+
+    #[test]
+    fn frame_order() {
+        let order = |direction, frame_count, cycle_length| {
+            (0..cycle_length)
+                .map(|position| frame_index(direction, frame_count, position))
+                .collect::<Vec<_>>()
         };
 
-        self.texture.draw(ctx, params)
+        assert_eq!(order(AnimationDirection::Forward, 3, 3), [0, 1, 2]);
+        assert_eq!(order(AnimationDirection::Reverse, 3, 3), [2, 1, 0]);
+        assert_eq!(
+            order(AnimationDirection::PingPong, 4, 6),
+            [0, 1, 2, 3, 2, 1]
+        );
+        assert_eq!(
+            order(AnimationDirection::PingPongReverse, 4, 6),
+            [3, 2, 1, 0, 1, 2]
+        );
+        assert_eq!(order(AnimationDirection::PingPong, 1, 1), [0]);
     }
 }
@@ -0,0 +1,515 @@
+//! Functions and types relating to texture atlases.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+#[cfg(feature = "texture_png")]
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+use crate::graphics::packer::ShelfAllocator;
+use crate::graphics::{Color, ImageData, Rectangle, Texture, TextureRegion};
+use crate::math::Vec2;
+use crate::Context;
+
+/// The location of a named image within a [`TextureAtlas`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtlasRegion {
+    /// The index of the page that the image is stored on.
+    pub page: usize,
+
+    /// The bounds of the image within the page, in pixels.
+    pub bounds: Rectangle,
+
+    /// The position of the bounds within the original image.
+    ///
+    /// This will be zero, unless the image was trimmed.
+    pub offset: Vec2<f32>,
+
+    /// The size of the original image.
+    ///
+    /// This will be the same as the size of the bounds, unless the image was trimmed.
+    pub original_size: Vec2<f32>,
+}
+
+/// A collection of images, packed together into one or more large textures.
+///
+/// Switching between textures breaks up Tetra's sprite batching, as each texture
+/// requires a separate draw call. Combining lots of small images into an atlas
+/// allows them all to be drawn in a single draw call, as long as they end up on
+/// the same page.
+///
+/// An atlas can be created at runtime via a [`TextureAtlasBuilder`] (which can be
+/// created via [`TextureAtlas::builder`]). Once an atlas has been built, its layout
+/// and pages can be [saved](Self::save) to disk, so that they can be
+/// [loaded](Self::load) in future without having to pack the images again.
+///
+/// # Performance
+///
+/// Cloning a `TextureAtlas` is relatively cheap, as the pages are [`Texture`]s, which
+/// are reference-counted. The names of the regions will be cloned, however.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{Drawable, TextureAtlas};
+/// # use tetra::math::Vec2;
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let atlas = TextureAtlas::builder(1024, 1024)
+///     .add_file("./player.png")?
+///     .add_file("./enemy.png")?
+///     .build(ctx)?;
+///
+/// if let Some(player) = atlas.get_texture_region("player") {
+///     player.draw(ctx, Vec2::new(16.0, 16.0));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    pages: Vec<Texture>,
+    regions: BTreeMap<String, AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// Creates a new [`TextureAtlasBuilder`], which will pack images into pages of
+    /// the specified size.
+    pub fn builder(page_width: i32, page_height: i32) -> TextureAtlasBuilder {
+        TextureAtlasBuilder::new(page_width, page_height)
+    }
+
+    /// Loads an atlas that was previously saved via [`save`](Self::save).
+    ///
+    /// The page images will be loaded from the paths listed in the layout file,
+    /// relative to the directory that the layout file is in.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the layout file or
+    ///   any of the page images could not be loaded.
+    /// * [`TetraError::InvalidAtlas`] will be returned if the layout file is invalid.
+    /// * [`TetraError::InvalidTexture`] will be returned if any of the page images
+    ///   are invalid.
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn load<P>(ctx: &mut Context, path: P) -> Result<TextureAtlas>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let layout = fs::read_to_string(path)?;
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut pages = Vec::new();
+        let mut regions = BTreeMap::new();
+
+        for (index, line) in layout.lines().enumerate() {
+            // Only leading whitespace is trimmed, as region names can end with spaces.
+            let line = line.trim_start();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || {
+                TetraError::InvalidAtlas(format!(
+                    "invalid layout data in {} on line {}",
+                    path.display(),
+                    index + 1
+                ))
+            };
+
+            let mut parts = line.splitn(2, ' ');
+
+            match (parts.next(), parts.next()) {
+                (Some("page"), Some(file)) => {
+                    pages.push(Texture::new(ctx, directory.join(file.trim()))?);
+                }
+
+                (Some("region"), Some(data)) => {
+                    let (name, region) = parse_region(data).ok_or_else(invalid_line)?;
+
+                    if region.page >= pages.len() {
+                        return Err(invalid_line());
+                    }
+
+                    regions.insert(name, region);
+                }
+
+                _ => return Err(invalid_line()),
+            }
+        }
+
+        Ok(TextureAtlas { pages, regions })
+    }
+
+    /// Saves the atlas to disk, so that it can be reloaded later via [`load`](Self::load).
+    ///
+    /// The layout will be written to the specified path, in a simple text-based format.
+    /// Each page will be saved as a PNG file in the same directory, named after the layout
+    /// file (e.g. saving to `sprites.atlas` will create `sprites_0.png`, `sprites_1.png`,
+    /// and so on). This requires the `texture_png` feature to be enabled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToSaveAsset`] will be returned if any of the files could not be written.
+    /// * [`TetraError::InvalidTexture`] will be returned if the page images could not be encoded.
+    /// * [`TetraError::InvalidAtlas`] will be returned if any of the image names contain a
+    ///   line break, as they cannot be stored in the layout file.
+    ///
+    /// # Performance
+    ///
+    /// This function has to read the pages back from the GPU, which is relatively slow.
+    #[cfg(feature = "texture_png")]
+    pub fn save<P>(&self, ctx: &mut Context, path: P) -> Result
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "atlas".into());
+
+        if let Some(name) = self.names().find(|name| name.contains(&['\n', '\r'][..])) {
+            return Err(TetraError::InvalidAtlas(format!(
+                "image name {:?} contains a line break",
+                name
+            )));
+        }
+
+        let mut layout = String::new();
+
+        for (index, page) in self.pages.iter().enumerate() {
+            let file_name = format!("{}_{}.png", stem, index);
+            let (width, height) = page.size();
+
+            ImageData::from_rgba(width, height, page.get_data(ctx))?
+                .save(directory.join(&file_name))?;
+
+            // Writing to a String can't fail.
+            let _ = writeln!(layout, "page {}", file_name);
+        }
+
+        for (name, region) in &self.regions {
+            let _ = writeln!(layout, "region {}", format_region(name, region));
+        }
+
+        fs::write(path, layout)
+    }
+
+    /// Returns the atlas' pages.
+    pub fn pages(&self) -> &[Texture] {
+        &self.pages
+    }
+
+    /// Returns the page texture and bounds of the image with the specified name,
+    /// or `None` if there is no image with that name.
+    ///
+    /// The bounds can be passed to [`DrawParams::clip`](super::DrawParams::clip) in order
+    /// to draw the image. Note that this does not take trimming into account - if the
+    /// atlas was trimmed, you should use [`get_texture_region`](Self::get_texture_region)
+    /// instead.
+    pub fn get(&self, name: &str) -> Option<(&Texture, Rectangle)> {
+        self.regions
+            .get(name)
+            .map(|region| (&self.pages[region.page], region.bounds))
+    }
+
+    /// Returns the location of the image with the specified name, or `None` if there
+    /// is no image with that name.
+    pub fn get_region(&self, name: &str) -> Option<AtlasRegion> {
+        self.regions.get(name).copied()
+    }
+
+    /// Returns a [`TextureRegion`] for the image with the specified name, or `None` if there
+    /// is no image with that name.
+    pub fn get_texture_region(&self, name: &str) -> Option<TextureRegion> {
+        self.regions.get(name).map(|region| {
+            TextureRegion::trimmed(
+                self.pages[region.page].clone(),
+                region.bounds,
+                region.offset,
+                region.original_size,
+            )
+        })
+    }
+
+    /// Returns an iterator over the names of the images in the atlas, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.regions.keys().map(String::as_str)
+    }
+}
+
+#[cfg(any(feature = "texture_png", test))]
+fn format_region(name: &str, region: &AtlasRegion) -> String {
+    format!(
+        "{} {} {} {} {} {} {} {} {} {}",
+        region.page,
+        region.bounds.x,
+        region.bounds.y,
+        region.bounds.width,
+        region.bounds.height,
+        region.offset.x,
+        region.offset.y,
+        region.original_size.x,
+        region.original_size.y,
+        name
+    )
+}
+
+fn parse_region(data: &str) -> Option<(String, AtlasRegion)> {
+    let mut parts = data.splitn(10, ' ');
+
+    let page = parts.next()?.parse().ok()?;
+
+    let mut next_f32 = || parts.next()?.parse::<f32>().ok();
+
+    let bounds = Rectangle::new(next_f32()?, next_f32()?, next_f32()?, next_f32()?);
+    let offset = Vec2::new(next_f32()?, next_f32()?);
+    let original_size = Vec2::new(next_f32()?, next_f32()?);
+
+    let name = parts.next()?;
+
+    Some((
+        name.to_owned(),
+        AtlasRegion {
+            page,
+            bounds,
+            offset,
+            original_size,
+        },
+    ))
+}
+
+/// A builder for creating a [`TextureAtlas`].
+///
+/// Images are packed into pages using a shelf-packing algorithm, with the tallest images
+/// being packed first. If an image does not fit into any of the existing pages, a new
+/// page will be created.
+#[derive(Debug, Clone)]
+pub struct TextureAtlasBuilder {
+    page_width: i32,
+    page_height: i32,
+    padding: i32,
+    trim: bool,
+    images: Vec<(String, ImageData)>,
+}
+
+impl TextureAtlasBuilder {
+    /// Creates a new `TextureAtlasBuilder`, which will pack images into pages of
+    /// the specified size.
+    pub fn new(page_width: i32, page_height: i32) -> TextureAtlasBuilder {
+        TextureAtlasBuilder {
+            page_width,
+            page_height,
+            padding: 1,
+            trim: false,
+            images: Vec::new(),
+        }
+    }
+
+    /// Sets the amount of empty space that should be left around each image, in pixels.
+    ///
+    /// Padding prevents neighbouring images from bleeding into each other when drawn with
+    /// linear filtering or at non-integer positions.
+    ///
+    /// Defaults to `1`.
+    pub fn padding(&mut self, padding: i32) -> &mut TextureAtlasBuilder {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets whether or not the transparent space around each image should be removed
+    /// before packing.
+    ///
+    /// Trimming allows more images to fit on each page. The original size of each image
+    /// will be stored in the atlas, and the [`TextureRegion`]s returned by
+    /// [`TextureAtlas::get_texture_region`] will account for the trimming when drawn.
+    ///
+    /// Defaults to `false`.
+    pub fn trim(&mut self, trim: bool) -> &mut TextureAtlasBuilder {
+        self.trim = trim;
+        self
+    }
+
+    /// Adds an image to the atlas, with the specified name.
+    ///
+    /// If an image with the same name has already been added, it will be replaced.
+    pub fn add_image<S>(&mut self, name: S, image: ImageData) -> &mut TextureAtlasBuilder
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+
+        self.images.retain(|(existing, _)| *existing != name);
+        self.images.push((name, image));
+
+        self
+    }
+
+    /// Loads an image from the given file, and adds it to the atlas.
+    ///
+    /// The image will be named after the file, minus its extension - for example,
+    /// `./sprites/player.png` will be named `player`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+    /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid.
+    pub fn add_file<P>(&mut self, path: P) -> Result<&mut TextureAtlasBuilder>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let image = ImageData::new(path)?;
+
+        Ok(self.add_image(name, image))
+    }
+
+    /// Packs the images and uploads the resulting pages to the GPU.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidAtlas`] will be returned if an image is too large to fit
+    ///   on a page.
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn build(&self, ctx: &mut Context) -> Result<TextureAtlas> {
+        let mut images: Vec<_> = self
+            .images
+            .iter()
+            .map(|(name, image)| {
+                let (width, height) = image.size();
+                let original_size = Vec2::new(width as f32, height as f32);
+
+                // Fully transparent images are left as-is, rather than trimmed down to nothing.
+                let trimmed_bounds = if self.trim {
+                    image.opaque_bounds()
+                } else {
+                    None
+                };
+
+                match trimmed_bounds {
+                    Some(bounds) => {
+                        let offset = Vec2::new(bounds.x as f32, bounds.y as f32);
+                        (
+                            name.as_str(),
+                            Cow::Owned(image.region(bounds)),
+                            offset,
+                            original_size,
+                        )
+                    }
+
+                    None => (
+                        name.as_str(),
+                        Cow::Borrowed(image),
+                        Vec2::zero(),
+                        original_size,
+                    ),
+                }
+            })
+            .collect();
+
+        images.sort_by_key(|(_, image, _, _)| Reverse(image.height()));
+
+        let mut pages: Vec<(ShelfAllocator, ImageData)> = Vec::new();
+        let mut regions = BTreeMap::new();
+
+        for (name, image, offset, original_size) in images {
+            let (width, height) = image.size();
+
+            let existing = pages
+                .iter_mut()
+                .enumerate()
+                .find_map(|(index, (allocator, _))| {
+                    allocator
+                        .allocate(width, height)
+                        .map(|position| (index, position))
+                });
+
+            let (page, (x, y)) = match existing {
+                Some(placement) => placement,
+                None => {
+                    let mut allocator =
+                        ShelfAllocator::new(self.page_width, self.page_height, self.padding);
+
+                    let position = allocator.allocate(width, height).ok_or_else(|| {
+                        TetraError::InvalidAtlas(format!(
+                            "image '{}' ({}x{}) is too large to fit on an atlas page ({}x{})",
+                            name, width, height, self.page_width, self.page_height
+                        ))
+                    })?;
+
+                    pages.push((
+                        allocator,
+                        ImageData::from_color(
+                            self.page_width,
+                            self.page_height,
+                            Color::rgba(0.0, 0.0, 0.0, 0.0),
+                        ),
+                    ));
+
+                    (pages.len() - 1, position)
+                }
+            };
+
+            pages[page].1.copy_from(&image, x, y);
+
+            regions.insert(
+                name.to_owned(),
+                AtlasRegion {
+                    page,
+                    bounds: Rectangle::new(x as f32, y as f32, width as f32, height as f32),
+                    offset,
+                    original_size,
+                },
+            );
+        }
+
+        let pages = pages
+            .iter()
+            .map(|(_, image)| Texture::from_image_data(ctx, image))
+            .collect::<Result<_>>()?;
+
+        Ok(TextureAtlas { pages, regions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_round_trip() {
+        let region = AtlasRegion {
+            page: 2,
+            bounds: Rectangle::new(16.0, 32.0, 8.5, 12.0),
+            offset: Vec2::new(1.0, 2.0),
+            original_size: Vec2::new(10.0, 14.0),
+        };
+
+        for &name in &["player", "enemy 1", " padded name ", ""] {
+            let data = format_region(name, &region);
+
+            assert_eq!(parse_region(&data), Some((name.to_owned(), region)));
+        }
+    }
+
+    #[test]
+    fn invalid_regions() {
+        assert_eq!(parse_region("0 0 0 16 16 0 0 16 16"), None);
+        assert_eq!(parse_region("0 0 0 16 sixteen 0 0 16 16 player"), None);
+        assert_eq!(parse_region("-1 0 0 16 16 0 0 16 16 player"), None);
+    }
+}
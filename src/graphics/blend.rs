@@ -0,0 +1,126 @@
+//! Functions and types relating to blending.
+
+/// The ways in which newly drawn colors can be combined with the colors that are
+/// already in the render target.
+///
+/// The default blend mode is [`BlendMode::Alpha`].
+///
+/// # Canvases
+///
+/// When drawing to a [`Canvas`](super::Canvas) with [`BlendMode::Alpha`], the color
+/// channels stored in the canvas will effectively be premultiplied by their alpha.
+/// If you then draw the canvas to the screen, you should switch to
+/// [`BlendMode::Premultiplied`] to avoid the alpha being applied twice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The alpha of the drawn content will determine its opacity.
+    ///
+    /// This is the default behaviour.
+    Alpha,
+
+    /// The drawn content's colors will be added to the existing colors,
+    /// weighted by the drawn content's alpha.
+    ///
+    /// This is useful for effects such as glows, fire and lasers.
+    Add,
+
+    /// The drawn content's colors will be subtracted from the existing colors,
+    /// weighted by the drawn content's alpha.
+    Subtract,
+
+    /// The drawn content's colors will be multiplied by the existing colors.
+    ///
+    /// This is useful for effects such as shadows and light maps.
+    Multiply,
+
+    /// The drawn content's colors are assumed to already have been multiplied by its
+    /// alpha, so only the existing colors will be weighted by the alpha.
+    ///
+    /// This should be used when drawing content with premultiplied alpha, such as
+    /// [`Canvas`](super::Canvas)es that have been drawn to with [`BlendMode::Alpha`].
+    Premultiplied,
+
+    /// The drawn content will overwrite the existing colors, including the alpha channel.
+    Replace,
+
+    /// A custom combination of blend factors and equations.
+    ///
+    /// The resulting color is calculated as `color_equation(src * src_color, dst * dst_color)`,
+    /// where `src` is the color being drawn and `dst` is the existing color. The alpha channel
+    /// is calculated in the same way, using the alpha fields.
+    Custom {
+        /// The equation used to combine the color channels.
+        color_equation: BlendEquation,
+
+        /// The factor that the drawn color channels are multiplied by.
+        src_color: BlendFactor,
+
+        /// The factor that the existing color channels are multiplied by.
+        dst_color: BlendFactor,
+
+        /// The equation used to combine the alpha channels.
+        alpha_equation: BlendEquation,
+
+        /// The factor that the drawn alpha channel is multiplied by.
+        src_alpha: BlendFactor,
+
+        /// The factor that the existing alpha channel is multiplied by.
+        dst_alpha: BlendFactor,
+    },
+}
+
+/// Values that colors can be multiplied by as part of a [`BlendMode::Custom`].
+///
+/// In the descriptions below, 'source' refers to the color being drawn, and 'destination'
+/// refers to the color that is already in the render target.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendFactor {
+    /// Multiplies by zero.
+    Zero,
+
+    /// Multiplies by one.
+    One,
+
+    /// Multiplies by the source color.
+    SrcColor,
+
+    /// Multiplies by one minus the source color.
+    OneMinusSrcColor,
+
+    /// Multiplies by the source alpha.
+    SrcAlpha,
+
+    /// Multiplies by one minus the source alpha.
+    OneMinusSrcAlpha,
+
+    /// Multiplies by the destination color.
+    DstColor,
+
+    /// Multiplies by one minus the destination color.
+    OneMinusDstColor,
+
+    /// Multiplies by the destination alpha.
+    DstAlpha,
+
+    /// Multiplies by one minus the destination alpha.
+    OneMinusDstAlpha,
+}
+
+/// Ways of combining the source and destination values as part of a [`BlendMode::Custom`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendEquation {
+    /// `src + dst`
+    Add,
+
+    /// `src - dst`
+    Subtract,
+
+    /// `dst - src`
+    ReverseSubtract,
+
+    /// The minimum of `src` and `dst`. The blend factors are ignored.
+    Min,
+
+    /// The maximum of `src` and `dst`. The blend factors are ignored.
+    Max,
+}
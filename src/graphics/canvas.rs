@@ -1,8 +1,8 @@
 use std::rc::Rc;
 
 use crate::error::Result;
-use crate::graphics::{DrawParams, Drawable, FilterMode, Texture};
-use crate::platform::{GraphicsDevice, RawFramebuffer};
+use crate::graphics::{DrawParams, Drawable, FilterMode, Texture, WrapMode};
+use crate::platform::{GraphicsDevice, RawFramebuffer, RawRenderbuffer};
 use crate::Context;
 
 /// A texture that can be used for off-screen rendering.
@@ -33,22 +33,29 @@ use crate::Context;
 pub struct Canvas {
     pub(crate) texture: Texture,
     pub(crate) framebuffer: Rc<RawFramebuffer>,
+    pub(crate) stencil_buffer: Option<Rc<RawRenderbuffer>>,
 }
 
 impl Canvas {
     /// Creates a new canvas.
     ///
+    /// If you need to configure additional settings (e.g. a stencil buffer), use
+    /// [`Canvas::builder`] instead.
+    ///
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
     /// graphics API encounters an error.
     pub fn new(ctx: &mut Context, width: i32, height: i32) -> Result<Canvas> {
-        Canvas::with_device(
-            &mut ctx.device,
-            width,
-            height,
-            ctx.graphics.default_filter_mode,
-        )
+        CanvasBuilder::new(width, height).build(ctx)
+    }
+
+    /// Creates a new canvas builder, which can be used to create a canvas with
+    /// additional settings.
+    ///
+    /// This is a shortcut for calling [`CanvasBuilder::new`].
+    pub fn builder(width: i32, height: i32) -> CanvasBuilder {
+        CanvasBuilder::new(width, height)
     }
 
     pub(crate) fn with_device(
@@ -56,13 +63,24 @@ impl Canvas {
         width: i32,
         height: i32,
         filter_mode: FilterMode,
+        stencil_buffer: bool,
+        mipmaps: bool,
     ) -> Result<Canvas> {
-        let texture = Texture::with_device_empty(device, width, height, filter_mode)?;
-        let framebuffer = device.new_framebuffer(&texture.data.handle, true)?;
+        let texture = Texture::with_device_empty(device, width, height, filter_mode, mipmaps)?;
+
+        let stencil_buffer = if stencil_buffer {
+            Some(device.new_renderbuffer(width, height)?)
+        } else {
+            None
+        };
+
+        let framebuffer =
+            device.new_framebuffer(&texture.data.handle, stencil_buffer.as_ref(), true)?;
 
         Ok(Canvas {
             texture,
             framebuffer: Rc::new(framebuffer),
+            stencil_buffer: stencil_buffer.map(Rc::new),
         })
     }
 
@@ -91,6 +109,31 @@ impl Canvas {
         self.texture.set_filter_mode(ctx, filter_mode);
     }
 
+    /// Returns whether or not the canvas has mipmaps.
+    pub fn has_mipmaps(&self) -> bool {
+        self.texture.has_mipmaps()
+    }
+
+    /// Sets whether or not the canvas should have mipmaps.
+    ///
+    /// When mipmaps are enabled, they will be regenerated whenever the canvas stops being
+    /// the active render target.
+    ///
+    /// See [`Texture::set_mipmaps`] for more details.
+    pub fn set_mipmaps(&mut self, ctx: &mut Context, mipmaps: bool) {
+        self.texture.set_mipmaps(ctx, mipmaps);
+    }
+
+    /// Returns the wrap mode being used by the canvas.
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.texture.wrap_mode()
+    }
+
+    /// Sets the wrap mode that should be used by the canvas.
+    pub fn set_wrap_mode(&mut self, ctx: &mut Context, wrap_mode: WrapMode) {
+        self.texture.set_wrap_mode(ctx, wrap_mode);
+    }
+
     /// Writes RGBA pixel data to a specified region of the canvas.
     ///
     /// This method requires you to provide enough data to fill the target rectangle.
@@ -121,6 +164,27 @@ impl Canvas {
         self.texture.set_data(ctx, x, y, width, height, data)
     }
 
+    /// Reads the canvas' pixel data back from the GPU.
+    ///
+    /// The data is returned in RGBA format, with the rows ordered from top to bottom
+    /// (i.e. the same order that [`set_data`](Self::set_data) expects). This can be used
+    /// to save the output of your rendering, or to generate data such as collision
+    /// masks at runtime.
+    ///
+    /// This will trigger a [`flush`](super::flush) to the graphics hardware, to ensure
+    /// that any pending drawing operations are taken into account.
+    ///
+    /// # Performance
+    ///
+    /// This is a relatively expensive operation, as the CPU has to wait for the GPU
+    /// to finish rendering to the canvas. Try to avoid calling it every frame.
+    pub fn get_data(&self, ctx: &mut Context) -> Vec<u8> {
+        // Because canvas rendering is done upside-down (see `graphics::ortho`), the
+        // first row of the underlying texture is already the top of the image, so
+        // no flipping is required here.
+        self.texture.get_data(ctx)
+    }
+
     /// Overwrites the entire canvas with new RGBA pixel data.
     ///
     /// This method requires you to provide enough data to fill the canvas.
@@ -143,6 +207,11 @@ impl Canvas {
     pub fn texture(&self) -> &Texture {
         &self.texture
     }
+
+    /// Returns whether or not the canvas has a stencil buffer.
+    pub fn has_stencil_buffer(&self) -> bool {
+        self.stencil_buffer.is_some()
+    }
 }
 
 impl Drawable for Canvas {
@@ -153,3 +222,74 @@ impl Drawable for Canvas {
         self.texture.draw(ctx, params)
     }
 }
+
+/// A builder for creating a [`Canvas`] with additional settings.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::Canvas;
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let canvas = Canvas::builder(640, 480).stencil_buffer(true).build(ctx)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanvasBuilder {
+    width: i32,
+    height: i32,
+    stencil_buffer: bool,
+    mipmaps: bool,
+}
+
+impl CanvasBuilder {
+    /// Creates a new `CanvasBuilder`, with the specified size.
+    pub fn new(width: i32, height: i32) -> CanvasBuilder {
+        CanvasBuilder {
+            width,
+            height,
+            stencil_buffer: false,
+            mipmaps: false,
+        }
+    }
+
+    /// Sets whether or not the canvas should have a stencil buffer.
+    ///
+    /// This is required in order to use [`graphics::set_stencil_state`](super::set_stencil_state)
+    /// while rendering to the canvas.
+    ///
+    /// Defaults to `false`.
+    pub fn stencil_buffer(&mut self, stencil_buffer: bool) -> &mut CanvasBuilder {
+        self.stencil_buffer = stencil_buffer;
+        self
+    }
+
+    /// Sets whether or not the canvas should have mipmaps.
+    ///
+    /// The mipmaps will be generated whenever the canvas stops being the active render
+    /// target. See [`Texture::set_mipmaps`] for more details.
+    ///
+    /// Defaults to `false`.
+    pub fn mipmaps(&mut self, mipmaps: bool) -> &mut CanvasBuilder {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Builds the canvas.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    ///   graphics API encounters an error.
+    pub fn build(&self, ctx: &mut Context) -> Result<Canvas> {
+        Canvas::with_device(
+            &mut ctx.device,
+            self.width,
+            self.height,
+            ctx.graphics.default_filter_mode,
+            self.stencil_buffer,
+            self.mipmaps,
+        )
+    }
+}
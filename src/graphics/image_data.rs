@@ -0,0 +1,375 @@
+//! Functions and types relating to raw image data.
+
+use std::path::Path;
+
+use image::{imageops, ImageFormat, RgbaImage};
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+use crate::graphics::{Color, Rectangle, Texture};
+use crate::Context;
+
+/// Raw RGBA pixel data, stored in CPU memory.
+///
+/// Unlike a [`Texture`], an `ImageData` can be freely read from and modified, without having
+/// to go through the graphics hardware. This makes it useful for preprocessing images before
+/// they are uploaded to the GPU (e.g. recolouring or cropping them), or for generating images
+/// at runtime.
+///
+/// Once you are done editing an `ImageData`, you can upload it to the GPU via
+/// [`to_texture`](Self::to_texture) or [`Texture::from_image_data`].
+///
+/// # Supported Formats
+///
+/// `ImageData` supports the same file formats as [`Texture`], and they are enabled or disabled
+/// via the same Cargo features.
+///
+/// # Performance
+///
+/// Loading and manipulating images on the CPU is relatively slow compared to doing it on the
+/// GPU - try to do it ahead of time (e.g. at startup), rather than every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageData {
+    data: RgbaImage,
+}
+
+impl ImageData {
+    /// Loads image data from the given file.
+    ///
+    /// The format will be determined based on the file extension.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+    /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid.
+    pub fn new<P>(path: P) -> Result<ImageData>
+    where
+        P: AsRef<Path>,
+    {
+        let data = fs::read_to_image(path)?.to_rgba8();
+
+        Ok(ImageData { data })
+    }
+
+    /// Loads image data from a slice of data, encoded in one of Tetra's supported
+    /// file formats (except for TGA).
+    ///
+    /// The format will be determined based on the 'magic bytes' at the beginning of the
+    /// data. Note that TGA files do not have recognizable magic bytes, so this function
+    /// will not recognize them.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid.
+    pub fn from_file_data(data: &[u8]) -> Result<ImageData> {
+        let data = image::load_from_memory(data)
+            .map_err(TetraError::InvalidTexture)?
+            .to_rgba8();
+
+        Ok(ImageData { data })
+    }
+
+    /// Creates image data from a buffer of RGBA pixel data.
+    ///
+    /// This method requires you to provide enough data to fill the image.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
+    ///   the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is negative.
+    pub fn from_rgba(width: i32, height: i32, mut data: Vec<u8>) -> Result<ImageData> {
+        assert!(width >= 0 && height >= 0, "image size cannot be negative");
+
+        let expected = width as usize * height as usize * 4;
+        let actual = data.len();
+
+        if expected > actual {
+            return Err(TetraError::NotEnoughData { expected, actual });
+        }
+
+        data.truncate(expected);
+
+        // This can't fail, as we've already checked the buffer size.
+        let data = RgbaImage::from_raw(width as u32, height as u32, data)
+            .expect("buffer should be large enough");
+
+        Ok(ImageData { data })
+    }
+
+    /// Creates image data of the specified size, filled with a single color.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is negative.
+    pub fn from_color(width: i32, height: i32, color: Color) -> ImageData {
+        assert!(width >= 0 && height >= 0, "image size cannot be negative");
+
+        ImageData {
+            data: RgbaImage::from_pixel(width as u32, height as u32, to_pixel(color)),
+        }
+    }
+
+    /// Saves the image data to the given file.
+    ///
+    /// The format will be determined based on the file extension. Only formats whose
+    /// corresponding `texture_*` Cargo feature is enabled can be written.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToSaveAsset`] will be returned if the file could not be written.
+    /// * [`TetraError::InvalidTexture`] will be returned if the format could not be determined
+    ///   from the file extension, or if the image could not be encoded.
+    pub fn save<P>(&self, path: P) -> Result
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).map_err(TetraError::InvalidTexture)?;
+
+        fs::write_image(path, &self.data, self.width(), self.height(), format)
+    }
+
+    /// Creates a new [`Texture`] from the image data.
+    ///
+    /// This is a shortcut for calling [`Texture::from_image_data`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn to_texture(&self, ctx: &mut Context) -> Result<Texture> {
+        Texture::from_image_data(ctx, self)
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> i32 {
+        self.data.width() as i32
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> i32 {
+        self.data.height() as i32
+    }
+
+    /// Returns the size of the image.
+    pub fn size(&self) -> (i32, i32) {
+        (self.width(), self.height())
+    }
+
+    /// Returns the image's RGBA pixel data, with the rows ordered from top to bottom.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes the image, returning its RGBA pixel data.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data.into_raw()
+    }
+
+    /// Returns the color of the pixel at the specified coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinates are outside the bounds of the image.
+    pub fn get_pixel_color(&self, x: i32, y: i32) -> Color {
+        self.assert_in_bounds(x, y);
+
+        let [r, g, b, a] = self.data.get_pixel(x as u32, y as u32).0;
+
+        Color::rgba8(r, g, b, a)
+    }
+
+    /// Sets the color of the pixel at the specified coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinates are outside the bounds of the image.
+    pub fn set_pixel_color(&mut self, x: i32, y: i32, color: Color) {
+        self.assert_in_bounds(x, y);
+
+        self.data.put_pixel(x as u32, y as u32, to_pixel(color));
+    }
+
+    /// Creates a new `ImageData` containing a copy of a region of this image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any part of the region is outside the bounds of the image.
+    pub fn region(&self, region: Rectangle<i32>) -> ImageData {
+        assert!(
+            region.x >= 0
+                && region.y >= 0
+                && region.width >= 0
+                && region.height >= 0
+                && region.right() <= self.width()
+                && region.bottom() <= self.height(),
+            "tried to read outside of image bounds"
+        );
+
+        let data = imageops::crop_imm(
+            &self.data,
+            region.x as u32,
+            region.y as u32,
+            region.width as u32,
+            region.height as u32,
+        )
+        .to_image();
+
+        ImageData { data }
+    }
+
+    /// Multiplies the color channels of each pixel by its alpha.
+    ///
+    /// This should be done if you are going to draw the image using
+    /// [`BlendMode::Premultiplied`](super::BlendMode::Premultiplied).
+    pub fn premultiply(&mut self) {
+        for pixel in self.data.pixels_mut() {
+            let [r, g, b, a] = pixel.0;
+
+            let multiply = |channel: u8| (channel as u16 * a as u16 / 255) as u8;
+
+            pixel.0 = [multiply(r), multiply(g), multiply(b), a];
+        }
+    }
+
+    /// Flips the image horizontally.
+    pub fn flip_horizontal(&mut self) {
+        self.data = imageops::flip_horizontal(&self.data);
+    }
+
+    /// Flips the image vertically.
+    pub fn flip_vertical(&mut self) {
+        self.data = imageops::flip_vertical(&self.data);
+    }
+
+    /// Replaces every pixel of one color with another color.
+    ///
+    /// Colors are compared after being converted to 8-bit RGBA, so colors that are very
+    /// slightly different (e.g. due to floating point rounding) will still match.
+    pub fn replace_color(&mut self, from: Color, to: Color) {
+        let from = to_pixel(from);
+        let to = to_pixel(to);
+
+        for pixel in self.data.pixels_mut() {
+            if *pixel == from {
+                *pixel = to;
+            }
+        }
+    }
+
+    /// Copies the contents of another image into this one, with its top left corner
+    /// at the specified position. No blending is performed.
+    pub(crate) fn copy_from(&mut self, source: &ImageData, x: i32, y: i32) {
+        imageops::replace(&mut self.data, &source.data, x as u32, y as u32);
+    }
+
+    /// Returns the smallest rectangle that contains all of the image's non-transparent
+    /// pixels, or `None` if the image is fully transparent.
+    pub(crate) fn opaque_bounds(&self) -> Option<Rectangle<i32>> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+
+        for (x, y, pixel) in self.data.enumerate_pixels() {
+            if pixel.0[3] > 0 {
+                bounds = Some(match bounds {
+                    Some((left, top, right, bottom)) => {
+                        (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                    }
+                    None => (x, y, x, y),
+                });
+            }
+        }
+
+        bounds.map(|(left, top, right, bottom)| {
+            Rectangle::new(
+                left as i32,
+                top as i32,
+                (right - left + 1) as i32,
+                (bottom - top + 1) as i32,
+            )
+        })
+    }
+
+    fn assert_in_bounds(&self, x: i32, y: i32) {
+        assert!(
+            x >= 0 && y >= 0 && x < self.width() && y < self.height(),
+            "pixel coordinates were outside of image bounds"
+        );
+    }
+}
+
+fn to_pixel(color: Color) -> image::Rgba<u8> {
+    // Float to int casts saturate, so out of range values will be clamped.
+    let convert = |channel: f32| (channel * 255.0).round() as u8;
+
+    image::Rgba([
+        convert(color.r),
+        convert(color.g),
+        convert(color.b),
+        convert(color.a),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_access() {
+        let mut image = ImageData::from_color(2, 2, Color::BLACK);
+
+        image.set_pixel_color(1, 0, Color::RED);
+
+        assert_eq!(image.get_pixel_color(0, 0), Color::BLACK);
+        assert_eq!(image.get_pixel_color(1, 0), Color::RED);
+        assert_eq!(&image.as_bytes()[4..8], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn not_enough_data() {
+        assert!(ImageData::from_rgba(2, 2, vec![0; 15]).is_err());
+        assert!(ImageData::from_rgba(2, 2, vec![0; 17]).is_ok());
+    }
+
+    #[test]
+    fn region_and_flip() {
+        let mut image = ImageData::from_color(3, 2, Color::BLACK);
+
+        image.set_pixel_color(1, 1, Color::RED);
+
+        let mut region = image.region(Rectangle::new(1, 0, 2, 2));
+
+        assert_eq!(region.size(), (2, 2));
+        assert_eq!(region.get_pixel_color(0, 1), Color::RED);
+
+        region.flip_horizontal();
+        assert_eq!(region.get_pixel_color(1, 1), Color::RED);
+
+        region.flip_vertical();
+        assert_eq!(region.get_pixel_color(1, 0), Color::RED);
+    }
+
+    #[test]
+    fn premultiply() {
+        let mut image = ImageData::from_rgba(1, 1, vec![255, 128, 0, 128]).unwrap();
+
+        image.premultiply();
+
+        assert_eq!(image.as_bytes(), &[128, 64, 0, 128]);
+    }
+
+    #[test]
+    fn replace_color() {
+        let mut image = ImageData::from_color(2, 1, Color::BLACK);
+
+        image.set_pixel_color(0, 0, Color::WHITE);
+        image.replace_color(Color::BLACK, Color::BLUE);
+
+        assert_eq!(image.get_pixel_color(0, 0), Color::WHITE);
+        assert_eq!(image.get_pixel_color(1, 0), Color::BLUE);
+    }
+}
@@ -0,0 +1,804 @@
+//! Functions and types relating to 2D lighting and shadows.
+
+use lyon_tessellation::math::Point;
+use lyon_tessellation::path::builder::PathBuilder;
+use lyon_tessellation::{
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, FillVertexConstructor,
+    VertexBuffers,
+};
+
+use crate::error::{Result, TetraError};
+use crate::graphics::mesh::Vertex;
+use crate::graphics::scaling::ScreenScaler;
+use crate::graphics::{
+    self, ActiveCanvas, ActiveShader, ActiveTexture, BlendEquation, BlendFactor, BlendMode, Canvas,
+    Color, DrawParams, Rectangle, Shader, DEFAULT_VERTEX_SHADER,
+};
+use crate::math::{Mat4, Vec2, Vec4};
+use crate::window;
+use crate::Context;
+
+const LIGHT_SHADER: &str = include_str!("../resources/lighting/light.frag");
+
+/// The blend mode used to cut shadows out of a light. This leaves the colors untouched,
+/// and subtracts from the alpha channel, which is later used to weight the light's
+/// contribution to the light map.
+const SHADOW_BLEND_MODE: BlendMode = BlendMode::Custom {
+    color_equation: BlendEquation::Add,
+    src_color: BlendFactor::Zero,
+    dst_color: BlendFactor::One,
+    alpha_equation: BlendEquation::ReverseSubtract,
+    src_alpha: BlendFactor::One,
+    dst_alpha: BlendFactor::One,
+};
+
+/// How far (as a multiple of the light's radius) that shadows are projected away from
+/// their occluders. This needs to be far enough that the end of the shadow is never
+/// visible, even for edges that are very close to the light.
+const SHADOW_LENGTH: f32 = 4.0;
+
+/// A 2D lighting system, which renders lights and shadows into a light map.
+///
+/// Lights are added to the scene via [`add_light`](Self::add_light), and polygons
+/// that block the light (such as walls) are added via
+/// [`add_occluder`](Self::add_occluder). Both are specified in world co-ordinates.
+///
+/// Each frame, the light map should be rendered via
+/// [`render_light_map`](Self::render_light_map), which takes the same transform matrix as the
+/// rest of your scene (e.g. the one returned by [`Camera::as_matrix`](super::Camera::as_matrix)).
+/// Once the scene has been drawn, the light map can then be drawn over the top of it via
+/// [`draw_light_map`](Self::draw_light_map), which uses [`BlendMode::Multiply`] to darken the
+/// parts of the scene that are not lit.
+///
+/// The light map should be the same size as the render target that it is being drawn to.
+/// [`with_window_size`](Self::with_window_size) and [`for_scaler`](Self::for_scaler) can be used
+/// to create a light map that matches the window or a [`ScreenScaler`], respectively.
+///
+/// # Performance
+///
+/// Each light is rendered to an intermediate canvas before being added to the light map,
+/// so every light requires several draw calls. Lights which are not visible on screen are
+/// skipped entirely.
+///
+/// Soft shadows are rendered by projecting the shadows once per sample, so lights with a
+/// high number of samples may be expensive if there are lots of occluders nearby.
+///
+/// # Examples
+///
+/// The [`lighting`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/lighting.rs)
+/// example demonstrates how to light a scene with a camera.
+#[derive(Debug)]
+pub struct Lighting {
+    light_map: Canvas,
+    scratch: Canvas,
+    shader: Shader,
+    ambient: Color,
+    lights: Vec<Light>,
+    occluders: Vec<Occluder>,
+}
+
+impl Lighting {
+    /// Creates a new lighting system, with a light map of the specified size.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32) -> Result<Lighting> {
+        Ok(Lighting {
+            light_map: Canvas::new(ctx, width, height)?,
+            scratch: Canvas::new(ctx, width, height)?,
+            shader: Shader::from_string(ctx, DEFAULT_VERTEX_SHADER, LIGHT_SHADER)?,
+            ambient: Color::BLACK,
+            lights: Vec::new(),
+            occluders: Vec::new(),
+        })
+    }
+
+    /// Creates a new lighting system, with a light map that is the same size as the window.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn with_window_size(ctx: &mut Context) -> Result<Lighting> {
+        let (width, height) = window::get_size(ctx);
+
+        Lighting::new(ctx, width, height)
+    }
+
+    /// Creates a new lighting system, with a light map that is the same size as the
+    /// inner canvas of a [`ScreenScaler`].
+    ///
+    /// The light map should then be drawn while the scaler's canvas is active.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn for_scaler(ctx: &mut Context, scaler: &ScreenScaler) -> Result<Lighting> {
+        let (width, height) = scaler.canvas().size();
+
+        Lighting::new(ctx, width, height)
+    }
+
+    /// Returns the width of the light map.
+    pub fn width(&self) -> i32 {
+        self.light_map.width()
+    }
+
+    /// Returns the height of the light map.
+    pub fn height(&self) -> i32 {
+        self.light_map.height()
+    }
+
+    /// Returns the size of the light map.
+    pub fn size(&self) -> (i32, i32) {
+        self.light_map.size()
+    }
+
+    /// Resizes the light map. This is useful for keeping the light map in sync with
+    /// the size of the window, in response to a [`Event::Resized`](crate::Event::Resized).
+    ///
+    /// If the size has not changed, this does nothing. Otherwise, the light map will be
+    /// recreated, and its contents will be lost.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn set_size(&mut self, ctx: &mut Context, width: i32, height: i32) -> Result {
+        if self.size() != (width, height) {
+            self.light_map = Canvas::new(ctx, width, height)?;
+            self.scratch = Canvas::new(ctx, width, height)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the canvas containing the light map.
+    ///
+    /// The contents of this canvas will only be updated when
+    /// [`render_light_map`](Self::render_light_map) is called.
+    pub fn light_map(&self) -> &Canvas {
+        &self.light_map
+    }
+
+    /// Returns the ambient light color, which is applied to the whole scene.
+    pub fn ambient(&self) -> Color {
+        self.ambient
+    }
+
+    /// Sets the ambient light color, which is applied to the whole scene.
+    ///
+    /// Defaults to [`Color::BLACK`] (i.e. anything that is not lit will be completely dark).
+    pub fn set_ambient(&mut self, ambient: Color) {
+        self.ambient = ambient;
+    }
+
+    /// Adds a light to the scene.
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    /// Returns the lights in the scene.
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Returns a mutable reference to the lights in the scene.
+    pub fn lights_mut(&mut self) -> &mut [Light] {
+        &mut self.lights
+    }
+
+    /// Removes the light at the specified index from the scene.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove_light(&mut self, index: usize) -> Light {
+        self.lights.remove(index)
+    }
+
+    /// Removes all of the lights from the scene.
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    /// Adds an occluder to the scene.
+    pub fn add_occluder(&mut self, occluder: Occluder) {
+        self.occluders.push(occluder);
+    }
+
+    /// Returns the occluders in the scene.
+    pub fn occluders(&self) -> &[Occluder] {
+        &self.occluders
+    }
+
+    /// Returns a mutable reference to the occluders in the scene.
+    pub fn occluders_mut(&mut self) -> &mut [Occluder] {
+        &mut self.occluders
+    }
+
+    /// Removes the occluder at the specified index from the scene.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove_occluder(&mut self, index: usize) -> Occluder {
+        self.occluders.remove(index)
+    }
+
+    /// Removes all of the occluders from the scene.
+    pub fn clear_occluders(&mut self) {
+        self.occluders.clear();
+    }
+
+    /// Renders the lights and shadows into the light map.
+    ///
+    /// `transform` is the matrix that will be used to draw the scene, such as the one
+    /// returned by [`Camera::as_matrix`](super::Camera::as_matrix). Pass [`Mat4::identity`]
+    /// if the scene is not transformed.
+    ///
+    /// This can be called at any point before the light map is drawn - afterwards, the
+    /// previously active canvas, shader, blend mode and transform matrix will be restored.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned
+    ///   if the shadow geometry could not be turned into vertex data.
+    pub fn render_light_map(&self, ctx: &mut Context, transform: Mat4<f32>) -> Result {
+        let previous_canvas = match &ctx.graphics.canvas {
+            ActiveCanvas::Window => ActiveCanvas::Window,
+            ActiveCanvas::User(canvas) => ActiveCanvas::User(canvas.clone()),
+        };
+
+        let previous_shader = match &ctx.graphics.shader {
+            ActiveShader::Default => ActiveShader::Default,
+            ActiveShader::User(shader) => ActiveShader::User(shader.clone()),
+        };
+
+        let blend_mode = graphics::get_blend_mode(ctx);
+        let previous_transform = graphics::get_transform_matrix(ctx);
+        let scissor = graphics::get_scissor(ctx);
+
+        graphics::reset_scissor(ctx);
+        graphics::set_texture_ex(ctx, ActiveTexture::Default);
+
+        graphics::set_canvas(ctx, &self.light_map);
+        graphics::clear(ctx, self.ambient);
+
+        let result = self.render_lights(ctx, transform);
+
+        graphics::set_shader_ex(ctx, previous_shader);
+        graphics::set_canvas_ex(ctx, previous_canvas);
+        graphics::set_blend_mode(ctx, blend_mode);
+        graphics::set_transform_matrix(ctx, previous_transform);
+
+        if let Some(scissor) = scissor {
+            graphics::set_scissor(ctx, scissor);
+        }
+
+        result
+    }
+
+    fn render_lights(&self, ctx: &mut Context, transform: Mat4<f32>) -> Result {
+        let screen_rect = Rectangle::new(0.0, 0.0, self.width() as f32, self.height() as f32);
+
+        let mut tessellator = FillTessellator::new();
+        let mut geometry = VertexBuffers::new();
+
+        for light in self.lights.iter().filter(|light| light.enabled) {
+            let bounds = light.bounds();
+
+            let visible = match screen_bounds(bounds, transform).intersection(&screen_rect) {
+                Some(visible) => visible,
+                None => continue,
+            };
+
+            // First, the light is drawn to the scratch canvas, with the alpha channel
+            // being set to one.
+            graphics::set_canvas(ctx, &self.scratch);
+            graphics::clear(ctx, Color::rgba(0.0, 0.0, 0.0, 0.0));
+
+            graphics::set_transform_matrix(ctx, transform);
+            graphics::set_blend_mode(ctx, BlendMode::Replace);
+            graphics::set_shader(ctx, &self.shader);
+
+            self.set_light_uniforms(ctx, light);
+
+            graphics::push_quad(
+                ctx,
+                bounds.x,
+                bounds.y,
+                bounds.right(),
+                bounds.bottom(),
+                0.0,
+                0.0,
+                1.0,
+                1.0,
+                &DrawParams::new().color(light.color),
+            );
+
+            // Then, the shadows are subtracted from the alpha channel. Soft shadows are
+            // drawn once per sample, with each sample removing an equal share of the light.
+            if let Some((spread, samples)) = light.shadows.settings() {
+                graphics::reset_shader(ctx);
+                graphics::set_blend_mode(ctx, SHADOW_BLEND_MODE);
+
+                let shadow_color = Color::rgba(0.0, 0.0, 0.0, 1.0 / samples as f32);
+
+                for offset in sample_offsets(spread, samples) {
+                    geometry.vertices.clear();
+                    geometry.indices.clear();
+
+                    self.tessellate_shadows(
+                        &mut tessellator,
+                        &mut geometry,
+                        light.position + offset,
+                        light.radius,
+                        bounds,
+                    )?;
+
+                    push_triangles(ctx, &geometry, shadow_color);
+                }
+            }
+
+            // Finally, the visible part of the light is added to the light map, weighted
+            // by the alpha channel.
+            graphics::set_canvas(ctx, &self.light_map);
+            graphics::reset_shader(ctx);
+            graphics::set_transform_matrix(ctx, Mat4::identity());
+            graphics::set_blend_mode(ctx, BlendMode::Add);
+            graphics::set_texture(ctx, self.scratch.texture());
+
+            graphics::push_quad(
+                ctx,
+                visible.x,
+                visible.y,
+                visible.right(),
+                visible.bottom(),
+                visible.x / screen_rect.width,
+                visible.y / screen_rect.height,
+                visible.right() / screen_rect.width,
+                visible.bottom() / screen_rect.height,
+                &DrawParams::new(),
+            );
+
+            graphics::set_texture_ex(ctx, ActiveTexture::Default);
+        }
+
+        Ok(())
+    }
+
+    fn set_light_uniforms(&self, ctx: &mut Context, light: &Light) {
+        let (direction, cone) = match light.kind {
+            // The cone of a point light is wide enough to cover every direction.
+            LightKind::Point => (Vec2::unit_x(), Vec2::new(-2.0, -1.5)),
+
+            LightKind::Spot { direction, angle } => {
+                let outer = angle / 2.0;
+                let inner = outer * 0.8;
+
+                (
+                    Vec2::new(direction.cos(), direction.sin()),
+                    Vec2::new(outer.cos(), inner.cos()),
+                )
+            }
+        };
+
+        self.shader
+            .set_uniform(ctx, "u_falloff", light.falloff.max(0.0));
+        self.shader.set_uniform(ctx, "u_direction", direction);
+        self.shader.set_uniform(ctx, "u_cone", cone);
+    }
+
+    fn tessellate_shadows(
+        &self,
+        tessellator: &mut FillTessellator,
+        geometry: &mut VertexBuffers<Vec2<f32>, u32>,
+        light_position: Vec2<f32>,
+        light_radius: f32,
+        light_bounds: Rectangle,
+    ) -> Result {
+        // The shadows cast by each edge overlap, so the non-zero fill rule is used to
+        // merge them into a single shape. This stops overlapping areas from being
+        // subtracted more than once.
+        let options = FillOptions::default().with_fill_rule(FillRule::NonZero);
+
+        let mut output = BuffersBuilder::new(geometry, ShadowVertexConstructor);
+        let mut builder = tessellator.builder(&options, &mut output);
+
+        let length = light_radius * SHADOW_LENGTH;
+
+        for occluder in &self.occluders {
+            if !occluder.enabled || !occluder.bounds().intersects(&light_bounds) {
+                continue;
+            }
+
+            let points = &occluder.points;
+
+            for (i, &start) in points.iter().enumerate() {
+                let end = points[(i + 1) % points.len()];
+
+                if let Some(quad) = shadow_quad(light_position, start, end, length) {
+                    builder.begin(Point::new(quad[0].x, quad[0].y));
+
+                    for point in &quad[1..] {
+                        builder.line_to(Point::new(point.x, point.y));
+                    }
+
+                    builder.end(true);
+                }
+            }
+        }
+
+        builder.build().map_err(TetraError::TessellationError)?;
+
+        Ok(())
+    }
+
+    /// Draws the light map over the top of the scene, using [`BlendMode::Multiply`].
+    ///
+    /// The light map is drawn at the top left of the current render target, without
+    /// the transform matrix being applied. Afterwards, the previous blend mode and
+    /// transform matrix will be restored.
+    pub fn draw_light_map(&self, ctx: &mut Context) {
+        let blend_mode = graphics::get_blend_mode(ctx);
+        let transform = graphics::get_transform_matrix(ctx);
+
+        graphics::set_blend_mode(ctx, BlendMode::Multiply);
+        graphics::set_transform_matrix(ctx, Mat4::identity());
+
+        graphics::draw(ctx, &self.light_map, Vec2::zero());
+
+        graphics::set_blend_mode(ctx, blend_mode);
+        graphics::set_transform_matrix(ctx, transform);
+    }
+}
+
+/// A light source.
+///
+/// Lights fade out from their position to their radius, with the shape of the
+/// fade being controlled by the `falloff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    /// The shape of the light.
+    pub kind: LightKind,
+
+    /// The position of the light, in world co-ordinates.
+    pub position: Vec2<f32>,
+
+    /// The distance that the light reaches, in world co-ordinates.
+    pub radius: f32,
+
+    /// The color of the light. The alpha channel can be used to control the brightness.
+    pub color: Color,
+
+    /// The exponent of the light's attenuation curve. `1.0` fades out linearly, while
+    /// higher values concentrate the light around its center. Defaults to `2.0`.
+    pub falloff: f32,
+
+    /// How the light is blocked by [`Occluder`]s. Defaults to [`Shadows::Hard`].
+    pub shadows: Shadows,
+
+    /// Whether the light is enabled. Disabled lights are skipped when rendering
+    /// the light map.
+    pub enabled: bool,
+}
+
+impl Light {
+    /// Creates a new point light, which shines in all directions.
+    pub fn point(position: Vec2<f32>, radius: f32, color: Color) -> Light {
+        Light {
+            kind: LightKind::Point,
+            position,
+            radius,
+            color,
+            falloff: 2.0,
+            shadows: Shadows::Hard,
+            enabled: true,
+        }
+    }
+
+    /// Creates a new spot light, which shines in a cone.
+    ///
+    /// `direction` is the angle that the center of the cone points in, and `angle` is
+    /// the width of the cone. Both are specified in radians.
+    pub fn spot(
+        position: Vec2<f32>,
+        radius: f32,
+        color: Color,
+        direction: f32,
+        angle: f32,
+    ) -> Light {
+        Light {
+            kind: LightKind::Spot { direction, angle },
+            ..Light::point(position, radius, color)
+        }
+    }
+
+    /// Returns the area that the light can reach, in world co-ordinates.
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(
+            self.position.x - self.radius,
+            self.position.y - self.radius,
+            self.radius * 2.0,
+            self.radius * 2.0,
+        )
+    }
+}
+
+/// The shapes that a [`Light`] can take.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LightKind {
+    /// A light that shines in all directions.
+    Point,
+
+    /// A light that shines in a cone, with soft edges.
+    Spot {
+        /// The angle that the center of the cone points in, in radians.
+        direction: f32,
+
+        /// The width of the cone, in radians.
+        angle: f32,
+    },
+}
+
+/// The ways in which a [`Light`] can cast shadows.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Shadows {
+    /// The light passes through occluders.
+    None,
+
+    /// The light is completely blocked by occluders, creating shadows with sharp edges.
+    Hard,
+
+    /// The light is treated as an area, rather than a single point, creating shadows
+    /// with soft edges.
+    Soft {
+        /// The radius of the light's area, in world co-ordinates. Larger values will
+        /// create softer shadows.
+        spread: f32,
+
+        /// The number of points within the area that shadows will be cast from. Higher
+        /// values will create smoother shadows, at the cost of performance.
+        samples: u32,
+    },
+}
+
+impl Shadows {
+    fn settings(self) -> Option<(f32, u32)> {
+        match self {
+            Shadows::None => None,
+            Shadows::Hard => Some((0.0, 1)),
+            Shadows::Soft { spread, samples } => Some((spread, samples.max(1))),
+        }
+    }
+}
+
+/// A polygon that blocks light, casting shadows.
+///
+/// The area covered by the occluder itself is treated as being in shadow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occluder {
+    /// The points of the polygon, in world co-ordinates. The polygon will automatically
+    /// be closed.
+    pub points: Vec<Vec2<f32>>,
+
+    /// Whether the occluder is enabled. Disabled occluders do not cast shadows.
+    pub enabled: bool,
+}
+
+impl Occluder {
+    /// Creates a new occluder from the points of a polygon.
+    pub fn new(points: Vec<Vec2<f32>>) -> Occluder {
+        Occluder {
+            points,
+            enabled: true,
+        }
+    }
+
+    /// Creates a new rectangular occluder.
+    pub fn rectangle(rectangle: Rectangle) -> Occluder {
+        Occluder::new(vec![
+            rectangle.top_left(),
+            rectangle.top_right(),
+            rectangle.bottom_right(),
+            rectangle.bottom_left(),
+        ])
+    }
+
+    /// Returns the bounding box of the occluder, in world co-ordinates.
+    pub fn bounds(&self) -> Rectangle {
+        bounding_box(self.points.iter().copied())
+    }
+}
+
+/// Returns the screen-space bounding box of a world-space rectangle.
+fn screen_bounds(bounds: Rectangle, transform: Mat4<f32>) -> Rectangle {
+    let corners = [
+        bounds.top_left(),
+        bounds.top_right(),
+        bounds.bottom_right(),
+        bounds.bottom_left(),
+    ];
+
+    bounding_box(corners.iter().map(|corner| {
+        let point = transform * Vec4::new(corner.x, corner.y, 0.0, 1.0);
+
+        Vec2::new(point.x, point.y)
+    }))
+}
+
+/// Returns the smallest rectangle that contains all of the given points.
+fn bounding_box(points: impl Iterator<Item = Vec2<f32>>) -> Rectangle {
+    let mut bounds: Option<(Vec2<f32>, Vec2<f32>)> = None;
+
+    for point in points {
+        bounds = Some(match bounds {
+            Some((min, max)) => (
+                Vec2::new(min.x.min(point.x), min.y.min(point.y)),
+                Vec2::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+            None => (point, point),
+        });
+    }
+
+    match bounds {
+        Some((min, max)) => Rectangle::new(min.x, min.y, max.x - min.x, max.y - min.y),
+        None => Rectangle::default(),
+    }
+}
+
+/// Returns the quad that is in shadow behind an edge, with a consistent (positive) winding
+/// order, or [`None`] if the edge is in line with the light.
+fn shadow_quad(
+    light: Vec2<f32>,
+    start: Vec2<f32>,
+    end: Vec2<f32>,
+    length: f32,
+) -> Option<[Vec2<f32>; 4]> {
+    let start_direction = direction(light, start)?;
+    let end_direction = direction(light, end)?;
+
+    let quad = [
+        start,
+        end,
+        end + end_direction * length,
+        start + start_direction * length,
+    ];
+
+    let area: f32 = (0..4)
+        .map(|i| {
+            let a = quad[i];
+            let b = quad[(i + 1) % 4];
+
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+
+    if area > 0.0 {
+        Some(quad)
+    } else if area < 0.0 {
+        Some([quad[1], quad[0], quad[3], quad[2]])
+    } else {
+        None
+    }
+}
+
+/// Returns the normalized direction from one point to another, or [`None`] if the
+/// points are the same.
+fn direction(from: Vec2<f32>, to: Vec2<f32>) -> Option<Vec2<f32>> {
+    let offset = to - from;
+    let distance = offset.magnitude();
+
+    if distance > 0.0 {
+        Some(offset / distance)
+    } else {
+        None
+    }
+}
+
+/// Returns the offsets that soft shadows are sampled from, evenly spread over a circle.
+fn sample_offsets(spread: f32, samples: u32) -> impl Iterator<Item = Vec2<f32>> {
+    // Spacing the samples by the golden angle gives a fairly even spread, without
+    // the samples lining up with each other.
+    const GOLDEN_ANGLE: f32 = 2.399_963;
+
+    (0..samples).map(move |i| {
+        if samples == 1 {
+            return Vec2::zero();
+        }
+
+        let distance = spread * ((i as f32 + 0.5) / samples as f32).sqrt();
+        let angle = i as f32 * GOLDEN_ANGLE;
+
+        Vec2::new(angle.cos(), angle.sin()) * distance
+    })
+}
+
+/// Adds a list of triangles to the batch.
+///
+/// The batch only supports quads, so each triangle is pushed with its first vertex
+/// repeated, which makes the second triangle of the quad degenerate.
+fn push_triangles(ctx: &mut Context, geometry: &VertexBuffers<Vec2<f32>, u32>, color: Color) {
+    let vertex = |index: u32| Vertex::new(geometry.vertices[index as usize], Vec2::zero(), color);
+
+    for triangle in geometry.indices.chunks_exact(3) {
+        let first = vertex(triangle[0]);
+
+        graphics::push_quad_vertices(
+            ctx,
+            &[first, vertex(triangle[1]), vertex(triangle[2]), first],
+        );
+    }
+}
+
+struct ShadowVertexConstructor;
+
+impl FillVertexConstructor<Vec2<f32>> for ShadowVertexConstructor {
+    fn new_vertex(&mut self, vertex: FillVertex) -> Vec2<f32> {
+        let position = vertex.position();
+
+        Vec2::new(position.x, position.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_area(points: &[Vec2<f32>]) -> f32 {
+        (0..points.len())
+            .map(|i| {
+                let a = points[i];
+                let b = points[(i + 1) % points.len()];
+
+                a.x * b.y - b.x * a.y
+            })
+            .sum()
+    }
+
+    #[test]
+    fn shadow_quads_have_consistent_winding() {
+        let light = Vec2::new(0.0, 0.0);
+
+        let forwards = shadow_quad(light, Vec2::new(10.0, -5.0), Vec2::new(10.0, 5.0), 100.0)
+            .expect("edge should cast a shadow");
+
+        let backwards = shadow_quad(light, Vec2::new(10.0, 5.0), Vec2::new(10.0, -5.0), 100.0)
+            .expect("edge should cast a shadow");
+
+        assert!(signed_area(&forwards) > 0.0);
+        assert!(signed_area(&backwards) > 0.0);
+
+        // The far edge of the shadow should be projected away from the light.
+        assert!(forwards.iter().any(|point| point.x > 50.0));
+    }
+
+    #[test]
+    fn edges_in_line_with_light_cast_no_shadow() {
+        let light = Vec2::new(0.0, 0.0);
+
+        assert_eq!(
+            shadow_quad(light, Vec2::new(10.0, 0.0), Vec2::new(20.0, 0.0), 100.0),
+            None
+        );
+
+        assert_eq!(shadow_quad(light, light, Vec2::new(20.0, 0.0), 100.0), None);
+    }
+
+    #[test]
+    fn sample_offsets_stay_within_spread() {
+        assert_eq!(
+            sample_offsets(10.0, 1).collect::<Vec<_>>(),
+            vec![Vec2::zero()]
+        );
+
+        let offsets: Vec<_> = sample_offsets(10.0, 16).collect();
+
+        assert_eq!(offsets.len(), 16);
+        assert!(offsets.iter().all(|offset| offset.magnitude() <= 10.0));
+    }
+}
@@ -531,10 +531,12 @@ impl Drawable for Mesh {
                     &self.vertex_buffer.handle,
                     &index_buffer.handle,
                     &texture.data.handle,
-                    &shader.data.handle,
+                    &shader.data.handle.borrow(),
                     start,
                     count,
                 );
+
+                ctx.graphics.stats.vertices += self.vertex_buffer.handle.count();
             }
             None => {
                 let (start, count) =
@@ -543,12 +545,16 @@ impl Drawable for Mesh {
                 ctx.device.draw_arrays(
                     &self.vertex_buffer.handle,
                     &texture.data.handle,
-                    &shader.data.handle,
+                    &shader.data.handle.borrow(),
                     start,
                     count,
                 );
+
+                ctx.graphics.stats.vertices += count;
             }
         }
+
+        ctx.graphics.stats.draw_calls += 1;
     }
 }
 
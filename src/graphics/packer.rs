@@ -0,0 +1,186 @@
+use crate::error::Result;
+use crate::graphics::{FilterMode, Texture};
+use crate::platform::GraphicsDevice;
+
+/// An individual shelf within the packed atlas, tracking how much space
+/// is currently taken up.
+#[derive(Copy, Clone, Debug)]
+struct Shelf {
+    current_x: i32,
+    start_y: i32,
+    height: i32,
+}
+
+/// Allocates space within a fixed-size area using a naive shelf-packing algorithm.
+///
+/// This only keeps track of which space is taken up - it's up to the caller to
+/// actually store the data somewhere.
+#[derive(Clone, Debug)]
+pub(crate) struct ShelfAllocator {
+    width: i32,
+    height: i32,
+    padding: i32,
+    shelves: Vec<Shelf>,
+    next_y: i32,
+}
+
+impl ShelfAllocator {
+    /// Creates a new `ShelfAllocator`, which will leave the specified amount of
+    /// padding around each allocation.
+    pub(crate) fn new(width: i32, height: i32, padding: i32) -> ShelfAllocator {
+        ShelfAllocator {
+            width,
+            height,
+            padding,
+            shelves: Vec::new(),
+            next_y: padding,
+        }
+    }
+
+    /// Resizes the allocator, clearing any existing shelf data.
+    pub(crate) fn reset(&mut self, width: i32, height: i32) {
+        self.width = width;
+        self.height = height;
+        self.shelves.clear();
+        self.next_y = self.padding;
+    }
+
+    /// Finds a space that can fit a rectangle of the specified width and height,
+    /// marks it as used, and returns the position.
+    ///
+    /// If it would not fit into the remaining space, `None` will be returned.
+    pub(crate) fn allocate(&mut self, source_width: i32, source_height: i32) -> Option<(i32, i32)> {
+        let width = self.width;
+        let height = self.height;
+        let padding = self.padding;
+
+        self.shelves
+            .iter_mut()
+            .find(|shelf| {
+                shelf.height >= source_height && width - shelf.current_x - padding >= source_width
+            })
+            .map(|shelf| {
+                // Use existing shelf:
+                let position = (shelf.current_x, shelf.start_y);
+                shelf.current_x += source_width + padding;
+                position
+            })
+            .or_else(|| {
+                if self.next_y + source_height < height && source_width + padding * 2 <= width {
+                    // Create new shelf:
+                    let position = (padding, self.next_y);
+
+                    self.shelves.push(Shelf {
+                        current_x: source_width + padding * 2,
+                        start_y: self.next_y,
+                        height: source_height,
+                    });
+
+                    self.next_y += source_height + padding;
+
+                    Some(position)
+                } else {
+                    // Won't fit:
+                    None
+                }
+            })
+    }
+}
+
+/// Packs texture data into an atlas using a naive shelf-packing algorithm.
+pub(crate) struct ShelfPacker {
+    texture: Texture,
+    allocator: ShelfAllocator,
+}
+
+impl ShelfPacker {
+    const PADDING: i32 = 1;
+
+    /// Creates a new `ShelfPacker`.
+    pub(crate) fn new(
+        device: &mut GraphicsDevice,
+        texture_width: i32,
+        texture_height: i32,
+    ) -> Result<ShelfPacker> {
+        Ok(ShelfPacker {
+            texture: Texture::with_device_empty(
+                device,
+                texture_width,
+                texture_height,
+                FilterMode::Nearest,
+                false,
+            )?,
+            allocator: ShelfAllocator::new(texture_width, texture_height, Self::PADDING),
+        })
+    }
+
+    /// Returns a reference to the current atlas texture.
+    pub(crate) fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Resize the atlas texture, clearing any existing shelf data.
+    pub(crate) fn resize(
+        &mut self,
+        device: &mut GraphicsDevice,
+        texture_width: i32,
+        texture_height: i32,
+    ) -> Result {
+        self.texture = Texture::with_device_empty(
+            device,
+            texture_width,
+            texture_height,
+            FilterMode::Nearest,
+            false,
+        )?;
+
+        self.allocator.reset(texture_width, texture_height);
+
+        Ok(())
+    }
+
+    /// Tries to insert RGBA data into the atlas, and returns the position.
+    ///
+    /// If the data will not fit into the remaining space, `None` will be returned.
+    pub(crate) fn insert(
+        &mut self,
+        device: &mut GraphicsDevice,
+        data: &[u8],
+        width: i32,
+        height: i32,
+    ) -> Option<(i32, i32)> {
+        let space = self.allocator.allocate(width, height);
+
+        if let Some((x, y)) = space {
+            device
+                .set_texture_data(&self.texture.data.handle, data, x, y, width, height)
+                .expect("glyph packer should never write out of bounds");
+        }
+
+        space
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShelfAllocator;
+
+    #[test]
+    fn allocate() {
+        let mut allocator = ShelfAllocator::new(16, 16, 1);
+
+        assert_eq!(allocator.allocate(4, 4), Some((1, 1)));
+        assert_eq!(allocator.allocate(4, 2), Some((6, 1)));
+        assert_eq!(allocator.allocate(4, 6), Some((1, 6)));
+
+        // Too wide to fit on a shelf:
+        assert_eq!(allocator.allocate(15, 1), None);
+
+        // Too tall to fit in the remaining space:
+        assert_eq!(allocator.allocate(4, 8), None);
+
+        allocator.reset(32, 32);
+
+        assert_eq!(allocator.allocate(15, 1), Some((1, 1)));
+    }
+}
@@ -0,0 +1,821 @@
+//! Functions and types relating to particle effects.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::f32::consts::PI;
+use std::sync::atomic::{self, AtomicU64};
+use std::time::Duration;
+
+use crate::graphics::{self, Color, DrawParams, Drawable, Rectangle, Texture, Vertex};
+use crate::math::Vec2;
+use crate::time;
+use crate::Context;
+
+/// The settings that control how a [`ParticleEmitter`] spawns and animates its particles.
+///
+/// As with [`DrawParams`], this can either be constructed via its [`Default`] implementation
+/// and then modified, or constructed manually.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature. Any fields that are missing from the
+/// serialized data will be set to their default values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct EmitterConfig {
+    /// The number of particles that will be spawned per second while the emitter is
+    /// emitting. Defaults to `10.0`.
+    pub spawn_rate: f32,
+
+    /// The number of particles that will be spawned at once when the emitter is started.
+    /// Defaults to `0`.
+    pub burst: u32,
+
+    /// The maximum number of particles that can be alive at once. Once this limit is
+    /// reached, no more particles will be spawned until some have died. Defaults to `1000`.
+    pub max_particles: usize,
+
+    /// How long each particle lives for, in seconds. Defaults to `1.0`.
+    pub lifetime: ValueRange,
+
+    /// The speed that particles are spawned with, in pixels per second. Defaults to `50.0`.
+    pub speed: ValueRange,
+
+    /// The direction that particles are spawned in, in radians. Defaults to `0.0` (to the right).
+    pub direction: f32,
+
+    /// How far particles can deviate from [`direction`](Self::direction), in radians.
+    /// This is half the angle of the cone that particles are spawned in - for example,
+    /// a spread of `PI` will spawn particles in every direction. Defaults to `PI`.
+    pub spread: f32,
+
+    /// The acceleration that is applied to each particle, in pixels per second squared.
+    /// Defaults to `(0.0, 0.0)`.
+    pub gravity: Vec2<f32>,
+
+    /// The rotation that particles are spawned with, in radians. Defaults to `0.0`.
+    pub rotation: ValueRange,
+
+    /// How quickly particles rotate, in radians per second. Defaults to `0.0`.
+    pub angular_velocity: ValueRange,
+
+    /// The scale of each particle over the course of its lifetime. Defaults to `1.0`.
+    pub scale: Curve<f32>,
+
+    /// The color of each particle over the course of its lifetime. Defaults to
+    /// [`Color::WHITE`].
+    pub color: Curve<Color>,
+
+    /// The region of the emitter's texture that will be drawn for each particle. Defaults
+    /// to [`None`], which means the full texture will be drawn.
+    pub source: Option<Rectangle>,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> EmitterConfig {
+        EmitterConfig {
+            spawn_rate: 10.0,
+            burst: 0,
+            max_particles: 1000,
+            lifetime: ValueRange::constant(1.0),
+            speed: ValueRange::constant(50.0),
+            direction: 0.0,
+            spread: PI,
+            gravity: Vec2::zero(),
+            rotation: ValueRange::constant(0.0),
+            angular_velocity: ValueRange::constant(0.0),
+            scale: Curve::new(1.0),
+            color: Curve::new(Color::WHITE),
+            source: None,
+        }
+    }
+}
+
+/// A range of values, which a random value will be picked from.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ValueRange {
+    /// The minimum value (inclusive).
+    pub min: f32,
+
+    /// The maximum value.
+    pub max: f32,
+}
+
+impl ValueRange {
+    /// Creates a new range.
+    pub const fn new(min: f32, max: f32) -> ValueRange {
+        ValueRange { min, max }
+    }
+
+    /// Creates a range that only contains a single value.
+    pub const fn constant(value: f32) -> ValueRange {
+        ValueRange::new(value, value)
+    }
+}
+
+impl From<f32> for ValueRange {
+    fn from(value: f32) -> ValueRange {
+        ValueRange::constant(value)
+    }
+}
+
+/// A value that changes over the course of a particle's lifetime.
+///
+/// A curve is made up of keys, each of which pairs a time (from `0.0` at the start of a
+/// particle's lifetime, to `1.0` at the end) with a value. When the curve is sampled,
+/// the value will be linearly interpolated between the two nearest keys. Before the first
+/// key and after the last key, the value of that key will be used.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature. A curve is serialized as a list of
+/// `(time, value)` pairs, which will be sorted when deserialized. Deserializing an
+/// empty list will fail, as a curve must have at least one key.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(
+    feature = "serde_support",
+    serde(
+        try_from = "Vec<(f32, T)>",
+        into = "Vec<(f32, T)>",
+        bound(
+            serialize = "T: Clone + serde::Serialize",
+            deserialize = "T: Copy + serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T> Curve<T>
+where
+    T: Copy,
+{
+    /// Creates a curve with a single, constant value.
+    pub fn new(value: T) -> Curve<T> {
+        Curve {
+            keys: vec![(0.0, value)],
+        }
+    }
+
+    /// Creates a curve from a list of keys. The keys do not need to be sorted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no keys are provided.
+    pub fn from_keys(mut keys: Vec<(f32, T)>) -> Curve<T> {
+        assert!(!keys.is_empty(), "curve must have at least one key");
+
+        keys.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Curve { keys }
+    }
+
+    /// Returns the keys of the curve, sorted by time.
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.keys
+    }
+
+    /// Adds a key to the curve.
+    pub fn add_key(&mut self, time: f32, value: T) {
+        let index = self.index_after(time);
+        self.keys.insert(index, (time, value));
+    }
+
+    /// Returns the index of the first key that comes after the specified time.
+    fn index_after(&self, time: f32) -> usize {
+        self.keys
+            .iter()
+            .position(|(t, _)| *t > time)
+            .unwrap_or(self.keys.len())
+    }
+
+    fn sample_with<F>(&self, time: f32, lerp: F) -> T
+    where
+        F: FnOnce(T, T, f32) -> T,
+    {
+        let index = self.index_after(time);
+
+        if index == 0 {
+            return self.keys[0].1;
+        }
+
+        if index == self.keys.len() {
+            return self.keys[index - 1].1;
+        }
+
+        let (start_time, start) = self.keys[index - 1];
+        let (end_time, end) = self.keys[index];
+
+        lerp(start, end, (time - start_time) / (end_time - start_time))
+    }
+}
+
+impl<T> TryFrom<Vec<(f32, T)>> for Curve<T>
+where
+    T: Copy,
+{
+    type Error = &'static str;
+
+    /// Creates a curve from a list of keys, failing if no keys are provided.
+    ///
+    /// The keys do not need to be sorted.
+    fn try_from(keys: Vec<(f32, T)>) -> Result<Curve<T>, &'static str> {
+        if keys.is_empty() {
+            Err("curve must have at least one key")
+        } else {
+            Ok(Curve::from_keys(keys))
+        }
+    }
+}
+
+impl<T> From<Curve<T>> for Vec<(f32, T)> {
+    fn from(curve: Curve<T>) -> Vec<(f32, T)> {
+        curve.keys
+    }
+}
+
+impl Curve<f32> {
+    /// Returns the value of the curve at the specified time.
+    pub fn sample(&self, time: f32) -> f32 {
+        self.sample_with(time, |a, b, t| a + (b - a) * t)
+    }
+}
+
+impl Curve<Color> {
+    /// Returns the value of the curve at the specified time.
+    pub fn sample(&self, time: f32) -> Color {
+        self.sample_with(time, |a, b, t| {
+            Color::rgba(
+                a.r + (b.r - a.r) * t,
+                a.g + (b.g - a.g) * t,
+                a.b + (b.b - a.b) * t,
+                a.a + (b.a - a.a) * t,
+            )
+        })
+    }
+}
+
+/// A single particle, spawned by a [`ParticleEmitter`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Particle {
+    /// The position of the particle.
+    pub position: Vec2<f32>,
+
+    /// The velocity of the particle, in pixels per second.
+    pub velocity: Vec2<f32>,
+
+    /// The rotation of the particle, in radians.
+    pub rotation: f32,
+
+    /// How quickly the particle is rotating, in radians per second.
+    pub angular_velocity: f32,
+
+    /// How long the particle has been alive for, in seconds.
+    pub age: f32,
+
+    /// How long the particle will live for, in seconds.
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// Returns how far through its lifetime the particle is, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        if self.lifetime > 0.0 {
+            (self.age / self.lifetime).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// An emitter that spawns, simulates and draws particles.
+///
+/// Particles are spawned at the emitter's [`position`](Self::position), and are then
+/// simulated independently - moving the emitter will not move particles that have already
+/// been spawned. The behaviour of the particles is controlled by an [`EmitterConfig`].
+///
+/// The simulation is advanced by calling [`update`](Self::update) (usually from
+/// [`State::update`](crate::State::update)).
+///
+/// # Performance
+///
+/// All of an emitter's particles are drawn with a single texture, so (as long as nothing
+/// else is drawn in between) they will be added to the same batch, and drawn in a
+/// single draw call.
+///
+/// Cloning an emitter is fairly cheap, as the underlying texture is
+/// [reference-counted](https://doc.rust-lang.org/std/rc/struct.Rc.html). The particles
+/// will be copied, however.
+///
+/// # Examples
+///
+/// The [`particles`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/particles.rs)
+/// example demonstrates how to use a particle emitter.
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    texture: Texture,
+    simulation: Simulation,
+}
+
+impl ParticleEmitter {
+    /// Creates a new emitter, using the specified texture and config.
+    ///
+    /// The emitter will start emitting immediately, including the initial
+    /// [`burst`](EmitterConfig::burst).
+    pub fn new(texture: Texture, config: EmitterConfig) -> ParticleEmitter {
+        ParticleEmitter {
+            texture,
+            simulation: Simulation::new(config),
+        }
+    }
+
+    /// Returns a reference to the texture that is used to draw the particles.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Sets the texture that is used to draw the particles.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    /// Returns a reference to the emitter's config.
+    pub fn config(&self) -> &EmitterConfig {
+        &self.simulation.config
+    }
+
+    /// Returns a mutable reference to the emitter's config.
+    ///
+    /// Changes will only affect particles that are spawned afterwards, with the exception
+    /// of [`gravity`](EmitterConfig::gravity), [`scale`](EmitterConfig::scale),
+    /// [`color`](EmitterConfig::color) and [`source`](EmitterConfig::source), which
+    /// apply to every particle.
+    pub fn config_mut(&mut self) -> &mut EmitterConfig {
+        &mut self.simulation.config
+    }
+
+    /// Sets the emitter's config.
+    pub fn set_config(&mut self, config: EmitterConfig) {
+        self.simulation.config = config;
+    }
+
+    /// Returns the position that particles will be spawned at.
+    pub fn position(&self) -> Vec2<f32> {
+        self.simulation.position
+    }
+
+    /// Sets the position that particles will be spawned at.
+    pub fn set_position(&mut self, position: Vec2<f32>) {
+        self.simulation.position = position;
+    }
+
+    /// Returns whether the emitter is currently spawning particles.
+    pub fn is_emitting(&self) -> bool {
+        self.simulation.emitting
+    }
+
+    /// Starts spawning particles, beginning with the config's
+    /// [`burst`](EmitterConfig::burst).
+    ///
+    /// If the emitter is already emitting, the burst will still be spawned.
+    pub fn start(&mut self) {
+        self.simulation.start();
+    }
+
+    /// Stops spawning particles. Particles that are already alive will continue to be
+    /// simulated until they die.
+    pub fn stop(&mut self) {
+        self.simulation.stop();
+    }
+
+    /// Spawns a number of particles at once, the next time the emitter is updated.
+    ///
+    /// This works even if the emitter is not emitting, which is useful for one-off
+    /// effects such as explosions.
+    pub fn burst(&mut self, count: u32) {
+        self.simulation.pending_burst += count;
+    }
+
+    /// Returns whether the emitter has stopped emitting, and all of its particles have died.
+    pub fn is_finished(&self) -> bool {
+        self.simulation.is_finished()
+    }
+
+    /// Returns the particles that are currently alive.
+    pub fn particles(&self) -> &[Particle] {
+        &self.simulation.particles
+    }
+
+    /// Removes all of the emitter's particles.
+    pub fn clear(&mut self) {
+        self.simulation.particles.clear();
+        self.simulation.pending_burst = 0;
+    }
+
+    /// Sets the seed that is used to randomize the particles.
+    ///
+    /// By default, each emitter is given a different seed. Setting the seed manually
+    /// allows an effect to play out the same way each time.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.simulation.rng = Rng::with_seed(seed);
+    }
+
+    /// Advances the simulation, based on the amount of time that has passed since the
+    /// last update.
+    pub fn update(&mut self, ctx: &Context) {
+        self.update_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the simulation by the specified amount of time.
+    pub fn update_by(&mut self, duration: Duration) {
+        self.simulation.update_by(duration);
+    }
+}
+
+/// The state of a [`ParticleEmitter`], other than its texture.
+#[derive(Debug, Clone)]
+struct Simulation {
+    config: EmitterConfig,
+    position: Vec2<f32>,
+    particles: Vec<Particle>,
+    emitting: bool,
+    pending_burst: u32,
+    spawn_timer: f32,
+    rng: Rng,
+}
+
+impl Simulation {
+    fn new(config: EmitterConfig) -> Simulation {
+        let pending_burst = config.burst;
+
+        Simulation {
+            config,
+            position: Vec2::zero(),
+            particles: Vec::new(),
+            emitting: true,
+            pending_burst,
+            spawn_timer: 0.0,
+            rng: Rng::new(),
+        }
+    }
+
+    fn start(&mut self) {
+        self.emitting = true;
+        self.pending_burst += self.config.burst;
+    }
+
+    fn stop(&mut self) {
+        self.emitting = false;
+        self.spawn_timer = 0.0;
+    }
+
+    fn is_finished(&self) -> bool {
+        !self.emitting && self.pending_burst == 0 && self.particles.is_empty()
+    }
+
+    fn update_by(&mut self, duration: Duration) {
+        let delta = duration.as_secs_f32();
+
+        let gravity = self.config.gravity;
+
+        for particle in &mut self.particles {
+            particle.age += delta;
+            particle.velocity += gravity * delta;
+            particle.position += particle.velocity * delta;
+            particle.rotation += particle.angular_velocity * delta;
+        }
+
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        let mut to_spawn = std::mem::take(&mut self.pending_burst) as usize;
+
+        if self.emitting && self.config.spawn_rate > 0.0 {
+            self.spawn_timer += self.config.spawn_rate * delta;
+
+            let count = self.spawn_timer.floor();
+            self.spawn_timer -= count;
+
+            to_spawn += count as usize;
+        }
+
+        let available = self
+            .config
+            .max_particles
+            .saturating_sub(self.particles.len());
+
+        for _ in 0..to_spawn.min(available) {
+            self.spawn();
+        }
+    }
+
+    fn spawn(&mut self) {
+        let config = &self.config;
+        let rng = &mut self.rng;
+
+        let angle = config.direction + rng.range(-config.spread, config.spread);
+        let speed = rng.sample(config.speed);
+
+        self.particles.push(Particle {
+            position: self.position,
+            velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+            rotation: rng.sample(config.rotation),
+            angular_velocity: rng.sample(config.angular_velocity),
+            age: 0.0,
+            lifetime: rng.sample(config.lifetime),
+        });
+    }
+}
+
+impl Drawable for ParticleEmitter {
+    /// Draws the emitter's particles.
+    ///
+    /// The particles are positioned in the same co-ordinate space as the emitter, and
+    /// each particle is drawn centered on its position. The `DrawParams` are applied
+    /// on top of this, transforming all of the particles as a group.
+    fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        if self.simulation.particles.is_empty() {
+            return;
+        }
+
+        let params = params.into();
+
+        let texture_width = self.texture.width() as f32;
+        let texture_height = self.texture.height() as f32;
+
+        let source = self
+            .simulation
+            .config
+            .source
+            .unwrap_or_else(|| Rectangle::new(0.0, 0.0, texture_width, texture_height));
+
+        let u1 = source.x / texture_width;
+        let v1 = source.y / texture_height;
+        let u2 = (source.x + source.width) / texture_width;
+        let v2 = (source.y + source.height) / texture_height;
+
+        let (sin, cos) = params.rotation.sin_cos();
+
+        let transform = |point: Vec2<f32>| {
+            let point = (point - params.origin) * params.scale;
+
+            params.position
+                + Vec2::new(cos * point.x - sin * point.y, sin * point.x + cos * point.y)
+        };
+
+        graphics::set_texture(ctx, &self.texture);
+
+        for particle in &self.simulation.particles {
+            let progress = particle.progress();
+
+            let scale = self.simulation.config.scale.sample(progress);
+            let color = self.simulation.config.color.sample(progress) * params.color;
+
+            let half_width = source.width * scale / 2.0;
+            let half_height = source.height * scale / 2.0;
+
+            let (particle_sin, particle_cos) = particle.rotation.sin_cos();
+
+            let corner = |x: f32, y: f32| {
+                transform(
+                    particle.position
+                        + Vec2::new(
+                            particle_cos * x - particle_sin * y,
+                            particle_sin * x + particle_cos * y,
+                        ),
+                )
+            };
+
+            graphics::push_quad_vertices(
+                ctx,
+                &[
+                    Vertex::new(corner(-half_width, -half_height), Vec2::new(u1, v1), color),
+                    Vertex::new(corner(-half_width, half_height), Vec2::new(u1, v2), color),
+                    Vertex::new(corner(half_width, half_height), Vec2::new(u2, v2), color),
+                    Vertex::new(corner(half_width, -half_height), Vec2::new(u2, v1), color),
+                ],
+            );
+        }
+    }
+}
+
+/// A small, fast pseudo-random number generator (xorshift64*), used so that particles
+/// can be randomized without pulling in an extra dependency.
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+/// Used to give each emitter a different default seed.
+static NEXT_SEED: AtomicU64 = AtomicU64::new(0);
+
+impl Rng {
+    fn new() -> Rng {
+        Rng::with_seed(NEXT_SEED.fetch_add(1, atomic::Ordering::Relaxed))
+    }
+
+    fn with_seed(seed: u64) -> Rng {
+        // The seed is scrambled via SplitMix64, so that similar seeds give very
+        // different sequences (and so that the state is never zero).
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Rng {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random number between `0.0` (inclusive) and `1.0` (exclusive).
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn sample(&mut self, range: ValueRange) -> f32 {
+        self.range(range.min, range.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_sampling() {
+        let curve = Curve::from_keys(vec![(1.0, 0.0), (0.0, 1.0), (0.5, 3.0)]);
+
+        assert_eq!(curve.sample(-1.0), 1.0);
+        assert_eq!(curve.sample(0.25), 2.0);
+        assert_eq!(curve.sample(0.75), 1.5);
+        assert_eq!(curve.sample(2.0), 0.0);
+
+        let mut colors = Curve::new(Color::WHITE);
+        colors.add_key(1.0, Color::rgba(0.0, 0.0, 0.0, 0.0));
+
+        assert_eq!(colors.sample(0.5), Color::rgba(0.5, 0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn curve_conversion() {
+        let curve = Curve::try_from(vec![(1.0, 0.0), (0.0, 1.0)]).unwrap();
+
+        assert_eq!(curve.keys(), [(0.0, 1.0), (1.0, 0.0)]);
+        assert!(Curve::<f32>::try_from(Vec::new()).is_err());
+
+        assert_eq!(Vec::from(curve), [(0.0, 1.0), (1.0, 0.0)]);
+    }
+
+    fn simulation(config: EmitterConfig) -> Simulation {
+        let mut simulation = Simulation::new(config);
+        simulation.rng = Rng::with_seed(1);
+        simulation
+    }
+
+    #[test]
+    fn spawn_rate() {
+        let mut simulation = simulation(EmitterConfig {
+            spawn_rate: 10.0,
+            ..EmitterConfig::default()
+        });
+
+        simulation.update_by(Duration::from_millis(250));
+        assert_eq!(simulation.particles.len(), 2);
+
+        // The leftover fraction of a particle should carry over to the next update.
+        simulation.update_by(Duration::from_millis(250));
+        assert_eq!(simulation.particles.len(), 5);
+
+        simulation.stop();
+        simulation.update_by(Duration::from_millis(250));
+        assert_eq!(simulation.particles.len(), 5);
+    }
+
+    #[test]
+    fn burst() {
+        let mut simulation = simulation(EmitterConfig {
+            spawn_rate: 0.0,
+            burst: 5,
+            ..EmitterConfig::default()
+        });
+
+        simulation.update_by(Duration::from_secs(0));
+        assert_eq!(simulation.particles.len(), 5);
+
+        simulation.update_by(Duration::from_secs(0));
+        assert_eq!(simulation.particles.len(), 5);
+
+        simulation.start();
+        simulation.update_by(Duration::from_secs(0));
+        assert_eq!(simulation.particles.len(), 10);
+    }
+
+    #[test]
+    fn max_particles() {
+        let mut simulation = simulation(EmitterConfig {
+            spawn_rate: 100.0,
+            burst: 10,
+            max_particles: 4,
+            lifetime: ValueRange::constant(10.0),
+            ..EmitterConfig::default()
+        });
+
+        simulation.update_by(Duration::from_secs(0));
+        assert_eq!(simulation.particles.len(), 4);
+
+        simulation.update_by(Duration::from_secs(1));
+        assert_eq!(simulation.particles.len(), 4);
+    }
+
+    #[test]
+    fn particles_die() {
+        let mut simulation = simulation(EmitterConfig {
+            spawn_rate: 0.0,
+            burst: 3,
+            lifetime: ValueRange::constant(1.0),
+            ..EmitterConfig::default()
+        });
+
+        simulation.stop();
+        simulation.update_by(Duration::from_secs(0));
+        assert_eq!(simulation.particles.len(), 3);
+        assert!(!simulation.is_finished());
+
+        simulation.update_by(Duration::from_millis(500));
+        assert_eq!(simulation.particles.len(), 3);
+        assert_eq!(simulation.particles[0].progress(), 0.5);
+
+        simulation.update_by(Duration::from_millis(500));
+        assert!(simulation.particles.is_empty());
+        assert!(simulation.is_finished());
+    }
+
+    #[test]
+    fn seeded_simulations_match() {
+        let config = EmitterConfig {
+            speed: ValueRange::new(10.0, 100.0),
+            lifetime: ValueRange::new(1.0, 2.0),
+            ..EmitterConfig::default()
+        };
+
+        let mut a = simulation(config.clone());
+        let mut b = simulation(config);
+
+        for _ in 0..10 {
+            a.update_by(Duration::from_millis(250));
+            b.update_by(Duration::from_millis(250));
+        }
+
+        assert!(!a.particles.is_empty());
+        assert_eq!(a.particles, b.particles);
+    }
+
+    #[test]
+    fn rng_is_deterministic_and_in_range() {
+        let mut a = Rng::with_seed(42);
+        let mut b = Rng::with_seed(42);
+
+        for _ in 0..1000 {
+            let value = a.range(-2.0, 3.0);
+
+            assert_eq!(value, b.range(-2.0, 3.0));
+            assert!((-2.0..3.0).contains(&value));
+        }
+
+        assert_eq!(a.sample(ValueRange::constant(5.0)), 5.0);
+    }
+}
@@ -0,0 +1,604 @@
+//! Functions and types relating to post-processing effects.
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::error::Result;
+use crate::graphics::scaling::ScreenScaler;
+use crate::graphics::{
+    self, ActiveShader, BlendMode, Canvas, Color, DrawParams, Shader, UniformValue,
+    DEFAULT_VERTEX_SHADER,
+};
+use crate::math::{Mat4, Vec2};
+use crate::window;
+use crate::Context;
+
+const BLUR_SHADER: &str = include_str!("../resources/post_process/blur.frag");
+const BLOOM_EXTRACT_SHADER: &str = include_str!("../resources/post_process/bloom_extract.frag");
+const BLOOM_COMBINE_SHADER: &str = include_str!("../resources/post_process/bloom_combine.frag");
+const COLOR_GRADE_SHADER: &str = include_str!("../resources/post_process/color_grade.frag");
+const SCANLINES_SHADER: &str = include_str!("../resources/post_process/scanlines.frag");
+
+/// A chain of post-processing effects, which are applied to a scene after it has been drawn.
+///
+/// The scene should be drawn between calls to [`begin`](Self::begin) and
+/// [`finish`](Self::finish) (or [`finish_to`](Self::finish_to)). Each [`Effect`] will then
+/// be applied in order, with the final pass being drawn to the screen (or to a canvas).
+///
+/// Internally, the pipeline owns three canvases of the same size - one which holds the
+/// input to the current effect, and two which are 'ping-ponged' between by the effect's
+/// passes. The size can be set to match the window via
+/// [`with_window_size`](Self::with_window_size), or to match a [`ScreenScaler`] via
+/// [`for_scaler`](Self::for_scaler).
+///
+/// # Uniforms
+///
+/// In addition to the [uniforms that are provided to every shader](Shader#uniforms), the
+/// shader for each pass will be provided with:
+///
+/// * `u_resolution` - A `vec2` containing the size of the pipeline's canvases, in pixels.
+/// * `u_input` - A `sampler2D` containing the input to the current effect (i.e. the output
+///   of the previous effect, or the original scene for the first effect). For the first pass
+///   of an effect, this is the same as `u_texture`.
+///
+/// Any uniforms that have been set on the pass or effect will also be applied, every time
+/// the pipeline is run.
+///
+/// # Performance
+///
+/// Each pass requires the whole screen to be redrawn, so effects with lots of passes may be
+/// expensive, especially at high resolutions.
+///
+/// # Examples
+///
+/// The [`post_process`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/post_process.rs)
+/// example demonstrates how to apply effects to a scene.
+#[derive(Debug)]
+pub struct PostProcess {
+    input: Canvas,
+    ping: Canvas,
+    pong: Canvas,
+    effects: Vec<Effect>,
+}
+
+impl PostProcess {
+    /// Creates a new post-processing pipeline, with canvases of the specified size.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32) -> Result<PostProcess> {
+        Ok(PostProcess {
+            input: Canvas::new(ctx, width, height)?,
+            ping: Canvas::new(ctx, width, height)?,
+            pong: Canvas::new(ctx, width, height)?,
+            effects: Vec::new(),
+        })
+    }
+
+    /// Creates a new post-processing pipeline, with canvases that are the same size as
+    /// the window.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn with_window_size(ctx: &mut Context) -> Result<PostProcess> {
+        let (width, height) = window::get_size(ctx);
+
+        PostProcess::new(ctx, width, height)
+    }
+
+    /// Creates a new post-processing pipeline, with canvases that are the same size as
+    /// the inner canvas of a [`ScreenScaler`].
+    ///
+    /// The output of the pipeline can then be drawn to the scaler via
+    /// [`finish_to`](Self::finish_to).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn for_scaler(ctx: &mut Context, scaler: &ScreenScaler) -> Result<PostProcess> {
+        let (width, height) = scaler.canvas().size();
+
+        PostProcess::new(ctx, width, height)
+    }
+
+    /// Returns the width of the pipeline's canvases.
+    pub fn width(&self) -> i32 {
+        self.input.width()
+    }
+
+    /// Returns the height of the pipeline's canvases.
+    pub fn height(&self) -> i32 {
+        self.input.height()
+    }
+
+    /// Returns the size of the pipeline's canvases.
+    pub fn size(&self) -> (i32, i32) {
+        self.input.size()
+    }
+
+    /// Resizes the pipeline's canvases. This is useful for keeping the pipeline in sync with
+    /// the size of the window, in response to a [`Event::Resized`](crate::Event::Resized).
+    ///
+    /// If the size has not changed, this does nothing. Otherwise, the canvases will be
+    /// recreated, and their contents will be lost.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn set_size(&mut self, ctx: &mut Context, width: i32, height: i32) -> Result {
+        if self.size() != (width, height) {
+            self.input = Canvas::new(ctx, width, height)?;
+            self.ping = Canvas::new(ctx, width, height)?;
+            self.pong = Canvas::new(ctx, width, height)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the canvas that the scene should be drawn to.
+    ///
+    /// [`begin`](Self::begin) will switch to this canvas automatically.
+    pub fn canvas(&self) -> &Canvas {
+        &self.input
+    }
+
+    /// Adds an effect to the end of the chain.
+    pub fn add_effect(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+
+    /// Returns the effects in the chain, in the order they will be applied.
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    /// Returns a mutable reference to the effects in the chain.
+    pub fn effects_mut(&mut self) -> &mut [Effect] {
+        &mut self.effects
+    }
+
+    /// Removes the effect at the specified index from the chain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove_effect(&mut self, index: usize) -> Effect {
+        self.effects.remove(index)
+    }
+
+    /// Removes all of the effects from the chain.
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    /// Switches rendering to the pipeline's input canvas, so that the scene can be drawn.
+    ///
+    /// The canvas is not cleared automatically.
+    pub fn begin(&self, ctx: &mut Context) {
+        graphics::set_canvas(ctx, &self.input);
+    }
+
+    /// Applies the effects to the scene, drawing the final result to the window.
+    ///
+    /// Afterwards, rendering will be redirected back to the window, and the previously
+    /// active shader will be restored.
+    pub fn finish(&self, ctx: &mut Context) {
+        self.run(ctx, None);
+    }
+
+    /// Applies the effects to the scene, drawing the final result to the specified canvas
+    /// (for example, the canvas of a [`ScreenScaler`]).
+    ///
+    /// Afterwards, rendering will be redirected back to the window, and the previously
+    /// active shader will be restored.
+    pub fn finish_to(&self, ctx: &mut Context, canvas: &Canvas) {
+        self.run(ctx, Some(canvas));
+    }
+
+    fn run(&self, ctx: &mut Context, target: Option<&Canvas>) {
+        let blend_mode = graphics::get_blend_mode(ctx);
+        let transform = graphics::get_transform_matrix(ctx);
+        let scissor = graphics::get_scissor(ctx);
+
+        let previous_shader = match &ctx.graphics.shader {
+            ActiveShader::Default => ActiveShader::Default,
+            ActiveShader::User(shader) => ActiveShader::User(shader.clone()),
+        };
+
+        graphics::set_transform_matrix(ctx, Mat4::identity());
+        graphics::reset_scissor(ctx);
+
+        let resolution = Vec2::new(self.width() as f32, self.height() as f32);
+
+        let canvases = [&self.input, &self.ping, &self.pong];
+
+        let pass_count: usize = self
+            .effects
+            .iter()
+            .filter(|effect| effect.enabled)
+            .map(|effect| effect.passes.len())
+            .sum();
+
+        let mut pass_index = 0;
+        let mut source = 0;
+
+        for effect in self.effects.iter().filter(|effect| effect.enabled) {
+            // The input to the effect is kept separate from the canvases that its passes
+            // are ping-ponged between, so that it can be sampled by any of the passes.
+            let effect_input = source;
+
+            for pass in &effect.passes {
+                pass_index += 1;
+
+                let destination = if pass_index == pass_count {
+                    None
+                } else {
+                    (0..canvases.len()).find(|&i| i != source && i != effect_input)
+                };
+
+                match destination.map(|i| canvases[i]).or(target) {
+                    Some(canvas) => graphics::set_canvas(ctx, canvas),
+                    None => graphics::reset_canvas(ctx),
+                }
+
+                graphics::set_shader(ctx, &pass.shader);
+
+                if pass.shader.has_uniform("u_resolution") {
+                    pass.shader.set_uniform(ctx, "u_resolution", resolution);
+                }
+
+                if pass.shader.has_uniform("u_input") {
+                    pass.shader
+                        .set_uniform(ctx, "u_input", canvases[effect_input].texture());
+                }
+
+                effect.uniforms.apply(ctx, &pass.shader);
+                pass.uniforms.apply(ctx, &pass.shader);
+
+                // Intermediate passes overwrite their target entirely, but the final pass
+                // respects the user's blend mode.
+                match destination {
+                    Some(_) => graphics::set_blend_mode(ctx, BlendMode::Replace),
+                    None => graphics::set_blend_mode(ctx, blend_mode),
+                }
+
+                self.draw_canvas(ctx, canvases[source]);
+
+                if let Some(destination) = destination {
+                    source = destination;
+                }
+            }
+        }
+
+        if pass_count == 0 {
+            match target {
+                Some(canvas) => graphics::set_canvas(ctx, canvas),
+                None => graphics::reset_canvas(ctx),
+            }
+
+            self.draw_canvas(ctx, &self.input);
+        }
+
+        graphics::set_shader_ex(ctx, previous_shader);
+
+        if target.is_some() {
+            graphics::reset_canvas(ctx);
+        }
+
+        graphics::set_blend_mode(ctx, blend_mode);
+        graphics::set_transform_matrix(ctx, transform);
+
+        if let Some(scissor) = scissor {
+            graphics::set_scissor(ctx, scissor);
+        }
+    }
+
+    fn draw_canvas(&self, ctx: &mut Context, canvas: &Canvas) {
+        graphics::set_texture(ctx, canvas.texture());
+
+        graphics::push_quad(
+            ctx,
+            0.0,
+            0.0,
+            self.width() as f32,
+            self.height() as f32,
+            0.0,
+            0.0,
+            1.0,
+            1.0,
+            &DrawParams::new(),
+        );
+    }
+}
+
+/// A post-processing effect, made up of one or more shader passes.
+///
+/// Uniforms that are set on an effect will be applied to all of its passes (before the
+/// uniforms that are set on the passes themselves).
+///
+/// # Built-in Effects
+///
+/// A few commonly used effects are provided out of the box. The uniforms that they use are
+/// listed in their documentation, and can be changed via [`set_uniform`](Self::set_uniform).
+#[derive(Debug)]
+pub struct Effect {
+    passes: Vec<EffectPass>,
+    uniforms: UniformList,
+    enabled: bool,
+}
+
+impl Effect {
+    /// Creates a new effect with a single pass, using the specified shader.
+    pub fn new(shader: Shader) -> Effect {
+        Effect::from_passes(vec![EffectPass::new(shader)])
+    }
+
+    /// Creates a new effect from a list of passes, which will be applied in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no passes are provided.
+    pub fn from_passes(passes: Vec<EffectPass>) -> Effect {
+        assert!(!passes.is_empty(), "effect must have at least one pass");
+
+        Effect {
+            passes,
+            uniforms: UniformList::default(),
+            enabled: true,
+        }
+    }
+
+    /// Returns the passes that make up the effect.
+    pub fn passes(&self) -> &[EffectPass] {
+        &self.passes
+    }
+
+    /// Returns a mutable reference to the passes that make up the effect.
+    pub fn passes_mut(&mut self) -> &mut [EffectPass] {
+        &mut self.passes
+    }
+
+    /// Sets the value of a uniform for all of the effect's passes.
+    ///
+    /// The value will be applied each time the effect is run.
+    pub fn set_uniform<V>(&mut self, name: &str, value: V)
+    where
+        V: UniformValue + 'static,
+    {
+        self.uniforms.set(name, value);
+    }
+
+    /// Returns whether the effect is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets whether the effect is enabled. Disabled effects will be skipped when the
+    /// pipeline is run.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Creates a two-pass Gaussian blur effect.
+    ///
+    /// `radius` controls the distance between the samples, in pixels. Values above `2.0`
+    /// will start to show artifacts - for a stronger blur, add multiple blur effects.
+    ///
+    /// The radius can be changed via the `u_radius` uniform.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn gaussian_blur(ctx: &mut Context, radius: f32) -> Result<Effect> {
+        let mut effect = Effect::from_passes(blur_passes(ctx)?);
+        effect.set_uniform("u_radius", radius);
+
+        Ok(effect)
+    }
+
+    /// Creates a bloom effect, which makes bright parts of the image glow.
+    ///
+    /// * `threshold` is the brightness (from `0.0` to `1.0`) above which pixels will glow.
+    /// * `intensity` controls how strongly the glow is added back to the image.
+    ///
+    /// These can be changed via the `u_threshold` and `u_intensity` uniforms. The radius of
+    /// the blur that is applied to the glow can also be changed via the `u_radius` uniform
+    /// (defaulting to `1.5`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn bloom(ctx: &mut Context, threshold: f32, intensity: f32) -> Result<Effect> {
+        let mut passes = Vec::with_capacity(4);
+
+        passes.push(EffectPass::new(Shader::from_string(
+            ctx,
+            DEFAULT_VERTEX_SHADER,
+            BLOOM_EXTRACT_SHADER,
+        )?));
+
+        passes.extend(blur_passes(ctx)?);
+
+        passes.push(EffectPass::new(Shader::from_string(
+            ctx,
+            DEFAULT_VERTEX_SHADER,
+            BLOOM_COMBINE_SHADER,
+        )?));
+
+        let mut effect = Effect::from_passes(passes);
+
+        effect.set_uniform("u_threshold", threshold);
+        effect.set_uniform("u_intensity", intensity);
+        effect.set_uniform("u_radius", 1.5f32);
+
+        Ok(effect)
+    }
+
+    /// Creates a color grading effect.
+    ///
+    /// The settings can be changed via the `u_brightness`, `u_contrast`, `u_saturation`
+    /// and `u_tint` uniforms.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn color_grade(ctx: &mut Context, settings: ColorGrade) -> Result<Effect> {
+        let mut effect = Effect::new(Shader::from_string(
+            ctx,
+            DEFAULT_VERTEX_SHADER,
+            COLOR_GRADE_SHADER,
+        )?);
+
+        effect.set_uniform("u_brightness", settings.brightness);
+        effect.set_uniform("u_contrast", settings.contrast);
+        effect.set_uniform("u_saturation", settings.saturation);
+        effect.set_uniform("u_tint", settings.tint);
+
+        Ok(effect)
+    }
+
+    /// Creates a scanline effect, which darkens every other row of pixels, like an old CRT
+    /// display.
+    ///
+    /// * `line_height` is the height of each line, in pixels.
+    /// * `intensity` controls how much the lines are darkened, from `0.0` to `1.0`.
+    ///
+    /// These can be changed via the `u_line_height` and `u_intensity` uniforms.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    ///   if the underlying graphics API encounters an error.
+    pub fn scanlines(ctx: &mut Context, line_height: f32, intensity: f32) -> Result<Effect> {
+        let mut effect = Effect::new(Shader::from_string(
+            ctx,
+            DEFAULT_VERTEX_SHADER,
+            SCANLINES_SHADER,
+        )?);
+
+        effect.set_uniform("u_line_height", line_height);
+        effect.set_uniform("u_intensity", intensity);
+
+        Ok(effect)
+    }
+}
+
+fn blur_passes(ctx: &mut Context) -> Result<Vec<EffectPass>> {
+    let shader = Shader::from_string(ctx, DEFAULT_VERTEX_SHADER, BLUR_SHADER)?;
+
+    let mut horizontal = EffectPass::new(shader.clone());
+    horizontal.set_uniform("u_direction", Vec2::new(1.0f32, 0.0));
+
+    let mut vertical = EffectPass::new(shader);
+    vertical.set_uniform("u_direction", Vec2::new(0.0f32, 1.0));
+
+    Ok(vec![horizontal, vertical])
+}
+
+/// The settings for a [color grading effect](Effect::color_grade).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorGrade {
+    /// An amount to add to each color channel. Defaults to `0.0`.
+    pub brightness: f32,
+
+    /// How much to scale the difference between each color channel and `0.5`.
+    /// Defaults to `1.0`.
+    pub contrast: f32,
+
+    /// How much to scale the saturation of the image, where `0.0` is greyscale.
+    /// Defaults to `1.0`.
+    pub saturation: f32,
+
+    /// A color to multiply the image by. Defaults to [`Color::WHITE`].
+    pub tint: Color,
+}
+
+impl Default for ColorGrade {
+    fn default() -> ColorGrade {
+        ColorGrade {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            tint: Color::WHITE,
+        }
+    }
+}
+
+/// A single shader pass within an [`Effect`].
+///
+/// Each pass is drawn to the whole screen, using the output of the previous pass as its
+/// texture.
+///
+/// # Performance
+///
+/// As a [`Shader`] is reference-counted, the same shader can be shared between multiple
+/// passes, with different uniforms (e.g. for the horizontal and vertical passes of a blur).
+#[derive(Debug)]
+pub struct EffectPass {
+    shader: Shader,
+    uniforms: UniformList,
+}
+
+impl EffectPass {
+    /// Creates a new pass, using the specified shader.
+    pub fn new(shader: Shader) -> EffectPass {
+        EffectPass {
+            shader,
+            uniforms: UniformList::default(),
+        }
+    }
+
+    /// Returns a reference to the pass's shader.
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    /// Sets the value of a uniform for this pass.
+    ///
+    /// The value will be applied each time the pass is run.
+    pub fn set_uniform<V>(&mut self, name: &str, value: V)
+    where
+        V: UniformValue + 'static,
+    {
+        self.uniforms.set(name, value);
+    }
+}
+
+/// A list of uniform values, which are stored so that they can be applied each time a
+/// pass is run.
+#[derive(Default)]
+struct UniformList(Vec<(String, Box<dyn UniformValue>)>);
+
+impl UniformList {
+    fn set<V>(&mut self, name: &str, value: V)
+    where
+        V: UniformValue + 'static,
+    {
+        match self.0.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = Box::new(value),
+            None => self.0.push((name.to_owned(), Box::new(value))),
+        }
+    }
+
+    fn apply(&self, ctx: &mut Context, shader: &Shader) {
+        for (name, value) in &self.0 {
+            value.set_uniform(ctx, shader, name);
+        }
+    }
+}
+
+impl Debug for UniformList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{Add, AddAssign, Div};
+use std::ops::{Add, AddAssign, Div, Sub};
 
 use num_traits::One;
 
@@ -102,6 +102,34 @@ where
             && self.y + self.height > other.y
     }
 
+    /// Returns the area where `self` and the `other` rectangle overlap, or [`None`] if
+    /// they do not intersect.
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>>
+    where
+        T: Add<Output = T> + Sub<Output = T> + PartialOrd,
+    {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let x = if self.x > other.x { self.x } else { other.x };
+        let y = if self.y > other.y { self.y } else { other.y };
+
+        let right = if self.right() < other.right() {
+            self.right()
+        } else {
+            other.right()
+        };
+
+        let bottom = if self.bottom() < other.bottom() {
+            self.bottom()
+        } else {
+            other.bottom()
+        };
+
+        Some(Rectangle::new(x, y, right - x, bottom - y))
+    }
+
     /// Returns `true` if the `other` rectangle is fully contained within `self`.
     pub fn contains(&self, other: &Rectangle<T>) -> bool
     where
@@ -253,6 +281,25 @@ mod tests {
         assert!(!base.intersects(&adjacent));
     }
 
+    #[test]
+    fn intersection() {
+        let base = Rectangle::new(2.0, 2.0, 4.0, 4.0);
+        let fully_contained = Rectangle::new(2.5, 2.5, 2.0, 2.0);
+        let overlapping = Rectangle::new(3.0, 3.0, 4.0, 4.0);
+        let seperate = Rectangle::new(20.0, 20.0, 4.0, 4.0);
+        let adjacent = Rectangle::new(6.0, 2.0, 4.0, 4.0);
+
+        assert_eq!(Some(base), base.intersection(&base));
+        assert_eq!(Some(fully_contained), base.intersection(&fully_contained));
+        assert_eq!(
+            Some(Rectangle::new(3.0, 3.0, 3.0, 3.0)),
+            base.intersection(&overlapping)
+        );
+
+        assert_eq!(None, base.intersection(&seperate));
+        assert_eq!(None, base.intersection(&adjacent));
+    }
+
     #[test]
     fn contains() {
         let base = Rectangle::new(2.0, 2.0, 4.0, 4.0);
@@ -1,18 +1,24 @@
 //! Functions and types relating to shader programs.
 
+mod preprocessor;
+
 use std::cell::{Cell, RefCell};
-use std::path::Path;
-use std::rc::Rc;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::SystemTime;
 
 use hashbrown::HashMap;
 
-use crate::error::Result;
+use crate::error::{Result, TetraError};
 use crate::fs;
-use crate::graphics::{Color, Texture};
+use crate::graphics::{self, Color, Texture, UniformBuffer};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 use crate::platform::{GraphicsDevice, RawProgram};
 use crate::Context;
 
+use self::preprocessor::Preprocessor;
+
 /// The default vertex shader.
 ///
 /// The source code for this shader is available in [`src/resources/shader.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/shader.vert).
@@ -29,16 +35,87 @@ pub(crate) struct Sampler {
     pub(crate) unit: u32,
 }
 
+/// Where the code for one stage of a shader comes from.
+#[derive(Debug, Clone)]
+enum StageSource {
+    Default,
+    File(PathBuf),
+    String(String),
+}
+
+/// The sources that a shader was created from, so that it can be reloaded later.
+#[derive(Debug, Clone)]
+pub(crate) struct ShaderSource {
+    vertex: StageSource,
+    fragment: StageSource,
+    defines: Vec<(String, String)>,
+}
+
+impl ShaderSource {
+    /// Preprocesses and compiles the shader, returning the program along with every file
+    /// that was read in order to build it.
+    fn compile(&self, device: &mut GraphicsDevice) -> Result<(RawProgram, Vec<PathBuf>)> {
+        let mut preprocessor = Preprocessor::new(&self.defines);
+        let mut load = |path: &Path| fs::read_to_string(path);
+
+        let vertex_shader = match &self.vertex {
+            StageSource::Default => preprocessor.process_string(
+                DEFAULT_VERTEX_SHADER,
+                "<default vertex shader>",
+                &mut load,
+            )?,
+            StageSource::File(path) => preprocessor.process_file(path, &mut load)?,
+            StageSource::String(source) => {
+                preprocessor.process_string(source, "<vertex shader>", &mut load)?
+            }
+        };
+
+        let fragment_shader = match &self.fragment {
+            StageSource::Default => preprocessor.process_string(
+                DEFAULT_FRAGMENT_SHADER,
+                "<default fragment shader>",
+                &mut load,
+            )?,
+            StageSource::File(path) => preprocessor.process_file(path, &mut load)?,
+            StageSource::String(source) => {
+                preprocessor.process_string(source, "<fragment shader>", &mut load)?
+            }
+        };
+
+        let handle = device
+            .new_program(&vertex_shader, &fragment_shader)
+            .map_err(|e| match e {
+                TetraError::InvalidShader(log) => {
+                    TetraError::InvalidShader(preprocessor.remap_log(&log))
+                }
+                e => e,
+            })?;
+
+        Ok((handle, preprocessor.into_dependencies()))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ShaderSharedData {
-    pub(crate) handle: RawProgram,
+    // The program can be swapped out when the shader is reloaded.
+    pub(crate) handle: RefCell<RawProgram>,
+    pub(crate) uniforms: RefCell<Vec<UniformInfo>>,
+    pub(crate) source: Option<ShaderSource>,
+    pub(crate) dependencies: RefCell<Vec<PathBuf>>,
     pub(crate) samplers: RefCell<HashMap<String, Sampler>>,
     pub(crate) next_unit: Cell<u32>,
+    pub(crate) uniform_blocks: RefCell<HashMap<String, UniformBlock>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct UniformBlock {
+    pub(crate) buffer: UniformBuffer,
+    pub(crate) binding: u32,
 }
 
 impl PartialEq for ShaderSharedData {
     fn eq(&self, other: &ShaderSharedData) -> bool {
-        self.handle.eq(&other.handle)
+        self.handle.borrow().eq(&other.handle.borrow())
     }
 }
 
@@ -74,7 +151,23 @@ impl PartialEq for ShaderSharedData {
 ///   pass through the [`DrawParams::color`](super::DrawParams::color) for a [`Mesh`](super::Mesh), and will
 ///   otherwise be set to [`Color::WHITE`].
 ///
-/// You can also set data into your own uniform variables via the `set_uniform` method.
+/// You can also set data into your own uniform variables via the `set_uniform` method, and
+/// list the uniforms that are active in the shader via the `uniforms` method.
+///
+/// ## Preprocessing
+///
+/// Shaders that are loaded from files (or created via a [`ShaderBuilder`]) can pull in code
+/// from other files via `#include "path"` directives. Paths are resolved relative to the file
+/// containing the directive, or relative to the current working directory for shaders that
+/// were provided as strings. Included files can include other files, but a file cannot
+/// include itself.
+///
+/// A [`ShaderBuilder`] can also be used to add `#define`s to the start of the code (directly
+/// after the `#version` directive, if there is one).
+///
+/// If a preprocessed shader fails to compile, the line numbers in the
+/// [`TetraError::InvalidShader`] message will refer to the original files, rather than to
+/// the combined code.
 ///
 /// # Performance
 ///
@@ -102,19 +195,22 @@ impl Shader {
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
-    /// underlying graphics API encounters an error.
+    ///   underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the files could not be loaded.
+    ///   if the files (or any files that they include) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    ///   shader could not be preprocessed or compiled.
     pub fn new<P>(ctx: &mut Context, vertex_path: P, fragment_path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
+        Shader::with_source(
             &mut ctx.device,
-            &fs::read_to_string(vertex_path)?,
-            &fs::read_to_string(fragment_path)?,
+            ShaderSource {
+                vertex: StageSource::File(vertex_path.as_ref().to_owned()),
+                fragment: StageSource::File(fragment_path.as_ref().to_owned()),
+                defines: Vec::new(),
+            },
         )
     }
 
@@ -125,19 +221,22 @@ impl Shader {
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
-    /// underlying graphics API encounters an error.
+    ///   underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the file could not be loaded.
+    ///   if the file (or any files that it includes) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    ///   shader could not be preprocessed or compiled.
     pub fn from_vertex_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
+        Shader::with_source(
             &mut ctx.device,
-            &fs::read_to_string(path)?,
-            DEFAULT_FRAGMENT_SHADER,
+            ShaderSource {
+                vertex: StageSource::File(path.as_ref().to_owned()),
+                fragment: StageSource::Default,
+                defines: Vec::new(),
+            },
         )
     }
 
@@ -148,19 +247,22 @@ impl Shader {
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
-    /// underlying graphics API encounters an error.
+    ///   underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the file could not be loaded.
+    ///   if the file (or any files that it includes) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    ///   shader could not be preprocessed or compiled.
     pub fn from_fragment_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
+        Shader::with_source(
             &mut ctx.device,
-            DEFAULT_VERTEX_SHADER,
-            &fs::read_to_string(path)?,
+            ShaderSource {
+                vertex: StageSource::Default,
+                fragment: StageSource::File(path.as_ref().to_owned()),
+                defines: Vec::new(),
+            },
         )
     }
 
@@ -215,16 +317,118 @@ impl Shader {
     ) -> Result<Shader> {
         let handle = device.new_program(vertex_shader, fragment_shader)?;
 
-        Ok(Shader {
+        Ok(Shader::from_handle(device, handle, None, Vec::new()))
+    }
+
+    fn with_source(device: &mut GraphicsDevice, source: ShaderSource) -> Result<Shader> {
+        let (handle, dependencies) = source.compile(device)?;
+
+        Ok(Shader::from_handle(
+            device,
+            handle,
+            Some(source),
+            dependencies,
+        ))
+    }
+
+    fn from_handle(
+        device: &mut GraphicsDevice,
+        handle: RawProgram,
+        source: Option<ShaderSource>,
+        dependencies: Vec<PathBuf>,
+    ) -> Shader {
+        let uniforms = device.get_active_uniforms(&handle);
+
+        Shader {
             data: Rc::new(ShaderSharedData {
-                handle,
+                handle: RefCell::new(handle),
+                uniforms: RefCell::new(uniforms),
+                source,
+                dependencies: RefCell::new(dependencies),
                 samplers: RefCell::new(HashMap::new()),
                 next_unit: Cell::new(1),
+                uniform_blocks: RefCell::new(HashMap::new()),
             }),
-        })
+        }
+    }
+
+    /// Reloads the shader from the files that it was created from, recompiling it in place.
+    ///
+    /// As the underlying data is shared, all clones of this shader will also be updated.
+    ///
+    /// Shaders that were created via [`from_string`](Self::from_string) (or the other
+    /// `from_*_string` constructors) have nothing to reload from, so calling this method
+    /// on them will have no effect. To reload shaders automatically when their files
+    /// change, use a [`ShaderWatcher`].
+    ///
+    /// Textures and uniform buffers that have been set as uniforms will be kept, but all
+    /// other uniforms will be reset to their default values, and should be set again.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    ///   if the files could not be loaded.
+    /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    ///   shader could not be compiled. In this case, the shader will keep using the previous program.
+    pub fn reload(&self, ctx: &mut Context) -> Result {
+        let source = match &self.data.source {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+
+        let (handle, dependencies) = source.compile(&mut ctx.device)?;
+
+        // Anything that was batched using the old program needs to be drawn before it
+        // gets replaced.
+        graphics::flush(ctx);
+
+        self.data
+            .uniforms
+            .replace(ctx.device.get_active_uniforms(&handle));
+
+        self.data.handle.replace(handle);
+        self.data.dependencies.replace(dependencies);
+
+        // Sampler uniforms have to be assigned to their texture units again, as that
+        // is stored in the program itself.
+        for (name, sampler) in self.data.samplers.borrow().iter() {
+            (sampler.unit as i32).set_uniform(ctx, self, name);
+        }
+
+        // The same goes for the binding points of uniform blocks.
+        for (name, block) in self.data.uniform_blocks.borrow().iter() {
+            ctx.device
+                .set_uniform_block_binding(&self.data.handle.borrow(), name, block.binding);
+        }
+
+        Ok(())
+    }
+
+    /// Returns information about the active uniforms in the shader.
+    ///
+    /// GLSL compilers will usually remove uniforms that do not affect the output of the
+    /// shader, so uniforms that are declared but not used may not be listed.
+    ///
+    /// Arrays are listed once, using their name without an index (e.g. `u_lights` rather
+    /// than `u_lights[0]`). Members of uniform blocks are not listed, as they are set via
+    /// a [`UniformBuffer`].
+    pub fn uniforms(&self) -> Vec<UniformInfo> {
+        self.data.uniforms.borrow().clone()
+    }
+
+    /// Returns whether the shader has an active uniform with the specified name.
+    ///
+    /// See [`uniforms`](Self::uniforms) for details of which uniforms are considered
+    /// active.
+    pub fn has_uniform(&self, name: &str) -> bool {
+        self.find_uniform(name).is_some()
     }
 
     /// Sets the value of the specifed uniform parameter.
+    ///
+    /// If the shader has no active uniform with the specified name, or the value is of the
+    /// wrong type, this will silently do nothing. To check for these mistakes, use
+    /// [`try_set_uniform`](Self::try_set_uniform) instead.
     pub fn set_uniform<V>(&self, ctx: &mut Context, name: &str, value: V)
     where
         V: UniformValue,
@@ -232,6 +436,80 @@ impl Shader {
         value.set_uniform(ctx, self, name)
     }
 
+    /// Sets the value of the specified uniform parameter, checking that the shader has an
+    /// active uniform with that name, and that the value is of a compatible type.
+    ///
+    /// This is a stricter version of [`set_uniform`](Self::set_uniform), which can be
+    /// useful for catching typos and type mismatches during development.
+    ///
+    /// Uniforms with types that Tetra does not recognize (i.e. [`UniformType::Other`])
+    /// are not type-checked.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidUniform`](crate::TetraError::InvalidUniform) will be returned
+    ///   if the shader has no active uniform with the specified name, if the value is not
+    ///   compatible with the uniform's type, or if more values were provided than the uniform
+    ///   can hold. In this case, the uniform will not be set.
+    pub fn try_set_uniform<V>(&self, ctx: &mut Context, name: &str, value: V) -> Result
+    where
+        V: UniformValue,
+    {
+        value.validate(ctx, self, name)?;
+        value.set_uniform(ctx, self, name);
+
+        Ok(())
+    }
+
+    /// Checks that the shader has an active uniform with the specified name, which can
+    /// hold `count` values of the `provided` type.
+    pub(crate) fn check_uniform(&self, name: &str, provided: UniformType, count: usize) -> Result {
+        let (uniform_type, available) = self.find_uniform(name).ok_or_else(|| {
+            TetraError::InvalidUniform(format!("the shader has no active uniform named '{}'", name))
+        })?;
+
+        if !uniform_type.accepts(provided) {
+            return Err(TetraError::InvalidUniform(format!(
+                "uniform '{}' is a {}, but a {} was provided",
+                name, uniform_type, provided
+            )));
+        }
+
+        if count > available {
+            return Err(TetraError::InvalidUniform(format!(
+                "uniform '{}' can hold {} values, but {} were provided",
+                name, available, count
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the type of the uniform with the specified name, and the number of values
+    /// that can be set starting from that uniform.
+    fn find_uniform(&self, name: &str) -> Option<(UniformType, usize)> {
+        // Individual array elements (e.g. `u_lights[1]`) are looked up via their array.
+        let (base_name, index) = match name.find('[') {
+            Some(start) if name.ends_with(']') => (
+                &name[..start],
+                name[start + 1..name.len() - 1].parse::<usize>().ok(),
+            ),
+            _ => (name, None),
+        };
+
+        self.data.uniforms.borrow().iter().find_map(|uniform| {
+            if uniform.name == name {
+                Some((uniform.uniform_type, uniform.size))
+            } else if uniform.name == base_name {
+                let index = index.filter(|&index| index < uniform.size)?;
+
+                Some((uniform.uniform_type, uniform.size - index))
+            } else {
+                None
+            }
+        })
+    }
+
     pub(crate) fn set_default_uniforms(
         &self,
         device: &mut GraphicsDevice,
@@ -244,18 +522,427 @@ impl Shader {
             device.bind_texture(Some(&sampler.texture.data.handle), sampler.unit)?;
         }
 
-        let projection_location = device.get_uniform_location(&self.data.handle, "u_projection");
+        for block in self.data.uniform_blocks.borrow().values() {
+            device.bind_uniform_buffer(&block.buffer.handle, block.binding);
+        }
+
+        let handle = self.data.handle.borrow();
 
-        device.set_uniform_mat4(&self.data.handle, projection_location.as_ref(), projection);
+        let projection_location = device.get_uniform_location(&handle, "u_projection");
 
-        let diffuse_location = device.get_uniform_location(&self.data.handle, "u_diffuse");
+        device.set_uniform_mat4(&handle, projection_location.as_ref(), projection);
 
-        device.set_uniform_vec4(&self.data.handle, diffuse_location.as_ref(), diffuse.into());
+        let diffuse_location = device.get_uniform_location(&handle, "u_diffuse");
+
+        device.set_uniform_vec4(&handle, diffuse_location.as_ref(), diffuse.into());
 
         Ok(())
     }
 }
 
+/// A builder for shaders, allowing for `#define`s to be added to the shader's code, and for
+/// files and strings to be combined.
+///
+/// Any stage that is not set will use the default shader for that stage.
+///
+/// See the [`Shader`] docs for more information on how shaders are preprocessed.
+#[derive(Debug, Clone)]
+pub struct ShaderBuilder {
+    source: ShaderSource,
+}
+
+impl ShaderBuilder {
+    /// Creates a new `ShaderBuilder`, which will use the default vertex and fragment shaders.
+    pub fn new() -> ShaderBuilder {
+        ShaderBuilder::default()
+    }
+
+    /// Sets the vertex shader to be loaded from the given file.
+    pub fn vertex_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.source.vertex = StageSource::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the vertex shader to the given string.
+    pub fn vertex_string<S>(&mut self, shader: S) -> &mut ShaderBuilder
+    where
+        S: Into<String>,
+    {
+        self.source.vertex = StageSource::String(shader.into());
+        self
+    }
+
+    /// Sets the fragment shader to be loaded from the given file.
+    pub fn fragment_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.source.fragment = StageSource::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the fragment shader to the given string.
+    pub fn fragment_string<S>(&mut self, shader: S) -> &mut ShaderBuilder
+    where
+        S: Into<String>,
+    {
+        self.source.fragment = StageSource::String(shader.into());
+        self
+    }
+
+    /// Adds a `#define` to both stages of the shader.
+    ///
+    /// If the name has already been defined, its value will be replaced.
+    pub fn define<N, V>(&mut self, name: N, value: V) -> &mut ShaderBuilder
+    where
+        N: Into<String>,
+        V: ToString,
+    {
+        let name = name.into();
+        let value = value.to_string();
+
+        match self.source.defines.iter_mut().find(|(n, _)| *n == name) {
+            Some(define) => define.1 = value,
+            None => self.source.defines.push((name, value)),
+        }
+
+        self
+    }
+
+    /// Builds the shader.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
+    ///   underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    ///   if any of the files (including those pulled in via `#include`) could not be loaded.
+    /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    ///   shader could not be preprocessed or compiled.
+    pub fn build(&self, ctx: &mut Context) -> Result<Shader> {
+        Shader::with_source(&mut ctx.device, self.source.clone())
+    }
+}
+
+impl Default for ShaderBuilder {
+    fn default() -> ShaderBuilder {
+        ShaderBuilder {
+            source: ShaderSource {
+                vertex: StageSource::Default,
+                fragment: StageSource::Default,
+                defines: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Watches the files that shaders were loaded from, and reloads the shaders when the
+/// files change.
+///
+/// This is intended to be used during development, so that changes to a shader can be
+/// seen without restarting your game. Each shader is reloaded via [`Shader::reload`], so
+/// all clones of the shader will be updated, and a shader that fails to compile will keep
+/// using its previous program.
+///
+/// The watcher does not keep shaders alive - once all clones of a shader have been
+/// dropped, it will stop being watched.
+///
+/// # Performance
+///
+/// Each call to [`update`](Self::update) checks the modification time of every watched
+/// file, which requires a call to the file system per file. You may want to only call it
+/// periodically, or to only create a watcher in debug builds.
+///
+/// # Examples
+///
+/// The [`shaders`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/shaders.rs)
+/// example demonstrates how to reload a shader when its file changes.
+#[derive(Debug, Default)]
+pub struct ShaderWatcher {
+    shaders: Vec<WatchedShader>,
+}
+
+#[derive(Debug)]
+struct WatchedShader {
+    data: Weak<ShaderSharedData>,
+    files: Vec<WatchedFile>,
+}
+
+#[derive(Debug)]
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl ShaderWatcher {
+    /// Creates a new watcher, with no shaders being watched.
+    pub fn new() -> ShaderWatcher {
+        ShaderWatcher::default()
+    }
+
+    /// Starts watching the files that a shader was loaded from, including any files
+    /// that were pulled in via `#include`.
+    ///
+    /// Shaders that were created from strings without any includes have no files to
+    /// watch, so they will be ignored. Watching the same shader more than once has no
+    /// effect.
+    pub fn watch(&mut self, shader: &Shader) {
+        if shader.data.source.is_none() || self.is_watching(shader) {
+            return;
+        }
+
+        let files = watched_files(&shader.data);
+
+        if files.is_empty() {
+            return;
+        }
+
+        self.shaders.push(WatchedShader {
+            data: Rc::downgrade(&shader.data),
+            files,
+        });
+    }
+
+    /// Stops watching a shader.
+    pub fn unwatch(&mut self, shader: &Shader) {
+        let data = Rc::downgrade(&shader.data);
+
+        self.shaders
+            .retain(|watched| !Weak::ptr_eq(&watched.data, &data));
+    }
+
+    /// Returns whether a shader is being watched.
+    pub fn is_watching(&self, shader: &Shader) -> bool {
+        let data = Rc::downgrade(&shader.data);
+
+        self.shaders
+            .iter()
+            .any(|watched| Weak::ptr_eq(&watched.data, &data))
+    }
+
+    /// Checks whether any of the watched files have changed, and reloads the shaders
+    /// that were loaded from them.
+    ///
+    /// Returns `true` if any shaders were reloaded successfully. As reloading a shader
+    /// resets its uniforms (other than textures), you may want to set them again when
+    /// this happens.
+    ///
+    /// # Errors
+    ///
+    /// If any of the shaders fail to reload, the first error will be returned, after all
+    /// of the other changed shaders have been reloaded. The failed shaders will keep using
+    /// their previous programs, and will be reloaded again the next time their files change.
+    ///
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    ///   if the files could not be loaded.
+    /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if a
+    ///   shader could not be compiled.
+    pub fn update(&mut self, ctx: &mut Context) -> Result<bool> {
+        // Shaders that have been dropped can't be reloaded, so there's no need to keep
+        // checking their files.
+        self.shaders
+            .retain(|watched| watched.data.strong_count() > 0);
+
+        let mut reloaded = false;
+        let mut error = None;
+
+        for watched in &mut self.shaders {
+            let mut changed = false;
+
+            for file in &mut watched.files {
+                let modified = modified_time(&file.path);
+
+                if modified != file.modified {
+                    file.modified = modified;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                continue;
+            }
+
+            if let Some(data) = watched.data.upgrade() {
+                match (Shader {
+                    data: Rc::clone(&data),
+                })
+                .reload(ctx)
+                {
+                    Ok(()) => {
+                        // The shader's includes may have changed, so the list of files
+                        // needs to be refreshed.
+                        watched.files = watched_files(&data);
+                        reloaded = true;
+                    }
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(reloaded),
+        }
+    }
+}
+
+fn watched_files(data: &ShaderSharedData) -> Vec<WatchedFile> {
+    data.dependencies
+        .borrow()
+        .iter()
+        .map(|path| WatchedFile {
+            path: path.clone(),
+            modified: modified_time(path),
+        })
+        .collect()
+}
+
+/// Returns the time that a file was last modified, or [`None`] if it could not be
+/// determined (e.g. if the file is in the middle of being saved).
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Information about an active uniform in a [`Shader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniformInfo {
+    /// The name of the uniform. For arrays, this does not include an index.
+    pub name: String,
+
+    /// The GLSL type of the uniform. For arrays, this is the type of each element.
+    pub uniform_type: UniformType,
+
+    /// The number of elements in the uniform, if it is an array. For other uniforms,
+    /// this will be `1`.
+    pub size: usize,
+}
+
+/// The GLSL types that a uniform can have.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UniformType {
+    /// A `float`.
+    Float,
+
+    /// A `vec2`.
+    Vec2,
+
+    /// A `vec3`.
+    Vec3,
+
+    /// A `vec4`.
+    Vec4,
+
+    /// An `int`.
+    Int,
+
+    /// An `ivec2`.
+    IVec2,
+
+    /// An `ivec3`.
+    IVec3,
+
+    /// An `ivec4`.
+    IVec4,
+
+    /// A `uint`.
+    UInt,
+
+    /// A `uvec2`.
+    UVec2,
+
+    /// A `uvec3`.
+    UVec3,
+
+    /// A `uvec4`.
+    UVec4,
+
+    /// A `bool`.
+    Bool,
+
+    /// A `bvec2`.
+    BVec2,
+
+    /// A `bvec3`.
+    BVec3,
+
+    /// A `bvec4`.
+    BVec4,
+
+    /// A `mat2`.
+    Mat2,
+
+    /// A `mat3`.
+    Mat3,
+
+    /// A `mat4`.
+    Mat4,
+
+    /// A `sampler2D`.
+    Sampler2D,
+
+    /// A type that Tetra does not currently recognize, identified by its OpenGL enum value.
+    Other(u32),
+}
+
+impl UniformType {
+    /// Returns whether a value of the `provided` type can be used to set a uniform of
+    /// this type.
+    fn accepts(self, provided: UniformType) -> bool {
+        use UniformType::*;
+
+        match (self, provided) {
+            _ if self == provided => true,
+
+            // OpenGL allows booleans to be set via any of the scalar/vector setters,
+            // and samplers to be set via their texture unit.
+            (Bool, Int) | (Bool, UInt) | (Bool, Float) => true,
+            (BVec2, Vec2) | (BVec3, Vec3) | (BVec4, Vec4) => true,
+            (Sampler2D, Int) => true,
+
+            (Other(_), _) => true,
+
+            _ => false,
+        }
+    }
+}
+
+impl Display for UniformType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UniformType::Float => "float",
+            UniformType::Vec2 => "vec2",
+            UniformType::Vec3 => "vec3",
+            UniformType::Vec4 => "vec4",
+            UniformType::Int => "int",
+            UniformType::IVec2 => "ivec2",
+            UniformType::IVec3 => "ivec3",
+            UniformType::IVec4 => "ivec4",
+            UniformType::UInt => "uint",
+            UniformType::UVec2 => "uvec2",
+            UniformType::UVec3 => "uvec3",
+            UniformType::UVec4 => "uvec4",
+            UniformType::Bool => "bool",
+            UniformType::BVec2 => "bvec2",
+            UniformType::BVec3 => "bvec3",
+            UniformType::BVec4 => "bvec4",
+            UniformType::Mat2 => "mat2",
+            UniformType::Mat3 => "mat3",
+            UniformType::Mat4 => "mat4",
+            UniformType::Sampler2D => "sampler2D",
+            UniformType::Other(value) => return write!(f, "unknown type (0x{:04X})", value),
+        };
+
+        f.write_str(name)
+    }
+}
+
 /// Implemented for types that can be passed as a uniform value to a shader.
 ///
 /// As the implementation of this trait currently interacts directly with the platform layer,
@@ -263,10 +950,13 @@ impl Shader {
 pub trait UniformValue {
     #[doc(hidden)]
     fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str);
+
+    #[doc(hidden)]
+    fn validate(&self, ctx: &Context, shader: &Shader, name: &str) -> Result;
 }
 
 macro_rules! simple_uniforms {
-    ($($t:ty => $f:ident),* $(,)?) => {
+    ($($t:ty => $f:ident, $u:ident),* $(,)?) => {
         $(
             impl UniformValue for $t {
                 #[doc(hidden)]
@@ -276,8 +966,14 @@ macro_rules! simple_uniforms {
                     shader: &Shader,
                     name: &str,
                 ) {
-                    let location = ctx.device.get_uniform_location(&shader.data.handle, name);
-                    ctx.device.$f(&shader.data.handle, location.as_ref(), *self);
+                    let handle = shader.data.handle.borrow();
+                    let location = ctx.device.get_uniform_location(&handle, name);
+                    ctx.device.$f(&handle, location.as_ref(), *self);
+                }
+
+                #[doc(hidden)]
+                fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.check_uniform(name, UniformType::$u, 1)
                 }
             }
         )*
@@ -285,15 +981,15 @@ macro_rules! simple_uniforms {
 }
 
 simple_uniforms! {
-    i32 => set_uniform_i32,
-    u32 => set_uniform_u32,
-    f32 => set_uniform_f32,
-    Vec2<f32> => set_uniform_vec2,
-    Vec3<f32> => set_uniform_vec3,
-    Vec4<f32> => set_uniform_vec4,
-    Mat2<f32> => set_uniform_mat2,
-    Mat3<f32> => set_uniform_mat3,
-    Mat4<f32> => set_uniform_mat4,
+    i32 => set_uniform_i32, Int,
+    u32 => set_uniform_u32, UInt,
+    f32 => set_uniform_f32, Float,
+    Vec2<f32> => set_uniform_vec2, Vec2,
+    Vec3<f32> => set_uniform_vec3, Vec3,
+    Vec4<f32> => set_uniform_vec4, Vec4,
+    Mat2<f32> => set_uniform_mat2, Mat2,
+    Mat3<f32> => set_uniform_mat3, Mat3,
+    Mat4<f32> => set_uniform_mat4, Mat4,
 }
 
 impl UniformValue for Color {
@@ -302,6 +998,11 @@ impl UniformValue for Color {
         let vec4: Vec4<f32> = (*self).into();
         vec4.set_uniform(ctx, shader, name);
     }
+
+    #[doc(hidden)]
+    fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+        shader.check_uniform(name, UniformType::Vec4, 1)
+    }
 }
 
 impl UniformValue for Texture {
@@ -330,11 +1031,179 @@ impl UniformValue for Texture {
             shader.data.next_unit.set(next_unit + 1);
         }
     }
+
+    #[doc(hidden)]
+    fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+        shader.check_uniform(name, UniformType::Sampler2D, 1)
+    }
+}
+
+/// Arrays of values can be set by passing a slice. The slice can be shorter than the array
+/// in the shader, in which case the remaining elements will not be changed. To start setting
+/// values from a later element, pass the name of that element (e.g. `u_lights[2]`).
+macro_rules! slice_uniforms {
+    ($($t:ty => $f:ident, $u:ident),* $(,)?) => {
+        $(
+            impl UniformValue for [$t] {
+                #[doc(hidden)]
+                fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+                    if self.is_empty() {
+                        return;
+                    }
+
+                    let handle = shader.data.handle.borrow();
+                    let location = ctx.device.get_uniform_location(&handle, name);
+                    ctx.device.$f(&handle, location.as_ref(), self);
+                }
+
+                #[doc(hidden)]
+                fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.check_uniform(name, UniformType::$u, self.len())
+                }
+            }
+        )*
+    };
+}
+
+slice_uniforms! {
+    i32 => set_uniform_i32_slice, Int,
+    u32 => set_uniform_u32_slice, UInt,
+}
+
+macro_rules! vector_slice_uniforms {
+    ($($t:ty => $components:expr, $u:ident),* $(,)?) => {
+        $(
+            impl UniformValue for [$t] {
+                #[doc(hidden)]
+                fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+                    if self.is_empty() {
+                        return;
+                    }
+
+                    let mut values = Vec::with_capacity(self.len() * $components);
+
+                    for value in self {
+                        values.extend_from_slice(value.as_slice());
+                    }
+
+                    let handle = shader.data.handle.borrow();
+                    let location = ctx.device.get_uniform_location(&handle, name);
+
+                    ctx.device
+                        .set_uniform_f32_slice(&handle, location.as_ref(), $components, &values);
+                }
+
+                #[doc(hidden)]
+                fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.check_uniform(name, UniformType::$u, self.len())
+                }
+            }
+        )*
+    };
+}
+
+vector_slice_uniforms! {
+    Vec2<f32> => 2, Vec2,
+    Vec3<f32> => 3, Vec3,
+    Vec4<f32> => 4, Vec4,
+}
+
+macro_rules! matrix_slice_uniforms {
+    ($($t:ty => $dimensions:expr, $u:ident),* $(,)?) => {
+        $(
+            impl UniformValue for [$t] {
+                #[doc(hidden)]
+                fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+                    let transpose = match self.first() {
+                        Some(first) => first.gl_should_transpose(),
+                        None => return,
+                    };
+
+                    let mut values = Vec::with_capacity(self.len() * $dimensions * $dimensions);
+
+                    for value in self {
+                        values.extend_from_slice(value.as_col_slice());
+                    }
+
+                    let handle = shader.data.handle.borrow();
+                    let location = ctx.device.get_uniform_location(&handle, name);
+
+                    ctx.device.set_uniform_matrix_slice(
+                        &handle,
+                        location.as_ref(),
+                        $dimensions,
+                        transpose,
+                        &values,
+                    );
+                }
+
+                #[doc(hidden)]
+                fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.check_uniform(name, UniformType::$u, self.len())
+                }
+            }
+        )*
+    };
+}
+
+matrix_slice_uniforms! {
+    Mat2<f32> => 2, Mat2,
+    Mat3<f32> => 3, Mat3,
+    Mat4<f32> => 4, Mat4,
+}
+
+impl UniformValue for [f32] {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        if self.is_empty() {
+            return;
+        }
+
+        let handle = shader.data.handle.borrow();
+        let location = ctx.device.get_uniform_location(&handle, name);
+
+        ctx.device
+            .set_uniform_f32_slice(&handle, location.as_ref(), 1, self);
+    }
+
+    #[doc(hidden)]
+    fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+        shader.check_uniform(name, UniformType::Float, self.len())
+    }
+}
+
+impl UniformValue for [Color] {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        let vectors: Vec<Vec4<f32>> = self.iter().map(|&color| color.into()).collect();
+
+        vectors.set_uniform(ctx, shader, name);
+    }
+
+    #[doc(hidden)]
+    fn validate(&self, _ctx: &Context, shader: &Shader, name: &str) -> Result {
+        shader.check_uniform(name, UniformType::Vec4, self.len())
+    }
+}
+
+impl<T> UniformValue for Vec<T>
+where
+    [T]: UniformValue,
+{
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        self.as_slice().set_uniform(ctx, shader, name);
+    }
+
+    #[doc(hidden)]
+    fn validate(&self, ctx: &Context, shader: &Shader, name: &str) -> Result {
+        self.as_slice().validate(ctx, shader, name)
+    }
 }
 
 impl<'a, T> UniformValue for &'a T
 where
-    T: UniformValue,
+    T: UniformValue + ?Sized,
 {
     #[doc(hidden)]
     fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
@@ -342,4 +1211,37 @@ where
             (**self).set_uniform(ctx, shader, name);
         }
     }
+
+    #[doc(hidden)]
+    fn validate(&self, ctx: &Context, shader: &Shader, name: &str) -> Result {
+        (**self).validate(ctx, shader, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UniformType;
+
+    #[test]
+    fn uniform_type_compatibility() {
+        assert!(UniformType::Float.accepts(UniformType::Float));
+        assert!(UniformType::Bool.accepts(UniformType::Int));
+        assert!(UniformType::Sampler2D.accepts(UniformType::Int));
+        assert!(UniformType::Sampler2D.accepts(UniformType::Sampler2D));
+        assert!(UniformType::Other(0x8B60).accepts(UniformType::Int));
+
+        assert!(!UniformType::Float.accepts(UniformType::Int));
+        assert!(!UniformType::Vec4.accepts(UniformType::Vec3));
+        assert!(!UniformType::Mat4.accepts(UniformType::Sampler2D));
+    }
+
+    #[test]
+    fn uniform_type_display() {
+        assert_eq!(UniformType::Vec3.to_string(), "vec3");
+        assert_eq!(UniformType::Sampler2D.to_string(), "sampler2D");
+        assert_eq!(
+            UniformType::Other(0x8B60).to_string(),
+            "unknown type (0x8B60)"
+        );
+    }
 }
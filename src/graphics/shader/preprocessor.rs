@@ -0,0 +1,453 @@
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Result, TetraError};
+
+/// Expands `#include` directives and inserts `#define`s into GLSL source code.
+///
+/// Each file that makes up the output is given its own source string number, via
+/// `#line` directives, so that the line numbers in the driver's error messages line up
+/// with the original files. The numbers are shared between all of the shader stages
+/// that are processed by the same preprocessor, so that errors which occur while linking
+/// can also be mapped back to the right file.
+pub(crate) struct Preprocessor<'a> {
+    defines: &'a [(String, String)],
+
+    /// The name of each source string, indexed by its number.
+    names: Vec<String>,
+
+    /// Every file that was read while processing the shader.
+    dependencies: Vec<PathBuf>,
+}
+
+impl<'a> Preprocessor<'a> {
+    pub(crate) fn new(defines: &'a [(String, String)]) -> Preprocessor<'a> {
+        Preprocessor {
+            defines,
+            names: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Processes a shader stage that was loaded from a file.
+    pub(crate) fn process_file<F>(&mut self, path: &Path, load: &mut F) -> Result<String>
+    where
+        F: FnMut(&Path) -> Result<String>,
+    {
+        let source = load(path)?;
+
+        self.add_dependency(path);
+        self.process(&source, path.display().to_string(), path, load)
+    }
+
+    /// Processes a shader stage that was provided as a string.
+    ///
+    /// Includes will be resolved relative to the current working directory.
+    pub(crate) fn process_string<F>(
+        &mut self,
+        source: &str,
+        name: &str,
+        load: &mut F,
+    ) -> Result<String>
+    where
+        F: FnMut(&Path) -> Result<String>,
+    {
+        self.process(source, name.to_owned(), Path::new(""), load)
+    }
+
+    /// Rewrites the source string numbers in a compiler or linker log to the names of
+    /// the files they refer to.
+    pub(crate) fn remap_log(&self, log: &str) -> String {
+        let mut output = String::with_capacity(log.len());
+
+        for (i, line) in log.lines().enumerate() {
+            if i > 0 {
+                output.push('\n');
+            }
+
+            match find_source_number(line) {
+                Some((start, end, number)) if number < self.names.len() => {
+                    output.push_str(&line[..start]);
+                    output.push_str(&self.names[number]);
+                    output.push_str(&line[end..]);
+                }
+                _ => output.push_str(line),
+            }
+        }
+
+        output
+    }
+
+    /// Returns every file that was read while processing the shader.
+    pub(crate) fn into_dependencies(self) -> Vec<PathBuf> {
+        self.dependencies
+    }
+
+    fn process<F>(
+        &mut self,
+        source: &str,
+        name: String,
+        path: &Path,
+        load: &mut F,
+    ) -> Result<String>
+    where
+        F: FnMut(&Path) -> Result<String>,
+    {
+        let mut output = String::with_capacity(source.len());
+
+        let (version_line, legacy_line_numbers) = find_version(source);
+
+        let mut lines = source.lines().enumerate().peekable();
+
+        // The `#version` directive has to come before anything else, so the defines
+        // are inserted directly after it.
+        if let Some(version_line) = version_line {
+            for (i, line) in lines.by_ref() {
+                output.push_str(line);
+                output.push('\n');
+
+                if i == version_line {
+                    break;
+                }
+            }
+        }
+
+        for (name, value) in self.defines {
+            writeln!(output, "#define {} {}", name, value).unwrap();
+        }
+
+        let mut stack = vec![path.to_owned()];
+
+        let first_line = lines.peek().map(|(i, _)| *i).unwrap_or(0);
+
+        self.expand(
+            &mut output,
+            name,
+            path,
+            first_line,
+            lines,
+            legacy_line_numbers,
+            &mut stack,
+            load,
+        )?;
+
+        Ok(output)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn expand<'s, I, F>(
+        &mut self,
+        output: &mut String,
+        name: String,
+        path: &Path,
+        first_line: usize,
+        lines: I,
+        legacy_line_numbers: bool,
+        stack: &mut Vec<PathBuf>,
+        load: &mut F,
+    ) -> Result
+    where
+        I: Iterator<Item = (usize, &'s str)>,
+        F: FnMut(&Path) -> Result<String>,
+    {
+        let number = self.names.len();
+        self.names.push(name);
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+        write_line_directive(output, first_line + 1, number, legacy_line_numbers);
+
+        for (i, line) in lines {
+            let include = match parse_directive(line, "include") {
+                Some(include) => include,
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                    continue;
+                }
+            };
+
+            let include_path = parse_include_path(include).ok_or_else(|| {
+                TetraError::InvalidShader(format!(
+                    "{}:{}: expected a quoted path after #include",
+                    self.names[number],
+                    i + 1
+                ))
+            })?;
+
+            let include_path = normalize(&directory.join(include_path));
+
+            if stack.contains(&include_path) {
+                return Err(TetraError::InvalidShader(format!(
+                    "{}:{}: '{}' includes itself",
+                    self.names[number],
+                    i + 1,
+                    include_path.display()
+                )));
+            }
+
+            let contents = load(&include_path)?;
+
+            self.add_dependency(&include_path);
+
+            stack.push(include_path.clone());
+
+            self.expand(
+                output,
+                include_path.display().to_string(),
+                &include_path,
+                0,
+                contents.lines().enumerate(),
+                legacy_line_numbers,
+                stack,
+                load,
+            )?;
+
+            stack.pop();
+
+            write_line_directive(output, i + 2, number, legacy_line_numbers);
+        }
+
+        Ok(())
+    }
+
+    fn add_dependency(&mut self, path: &Path) {
+        if !self.dependencies.iter().any(|p| p == path) {
+            self.dependencies.push(path.to_owned());
+        }
+    }
+}
+
+/// Writes a `#line` directive, so that the next line of the output is reported as the
+/// specified line of the specified source string.
+fn write_line_directive(output: &mut String, line: usize, number: usize, legacy: bool) {
+    // Before GLSL 3.30, `#line` set the number of the directive itself, rather than the
+    // line that follows it.
+    let line = if legacy { line - 1 } else { line };
+
+    writeln!(output, "#line {} {}", line, number).unwrap();
+}
+
+/// Returns the index of the line containing the `#version` directive (if there is one),
+/// and whether the version uses the pre-3.30 behaviour for `#line`.
+fn find_version(source: &str) -> (Option<usize>, bool) {
+    for (i, line) in source.lines().enumerate() {
+        if let Some(version) = parse_directive(line, "version") {
+            let mut parts = version.split_whitespace();
+
+            let number = parts
+                .next()
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(110);
+
+            let es = parts.next() == Some("es");
+
+            return (Some(i), number < 300 || (number < 330 && !es));
+        }
+    }
+
+    // Shaders without a `#version` directive are treated as GLSL 1.10.
+    (None, true)
+}
+
+/// If the line is the specified preprocessor directive, returns the rest of the line.
+fn parse_directive<'s>(line: &'s str, directive: &str) -> Option<&'s str> {
+    let line = line.trim_start();
+
+    if !line.starts_with('#') {
+        return None;
+    }
+
+    let line = line[1..].trim_start();
+
+    if !line.starts_with(directive) {
+        return None;
+    }
+
+    let rest = &line[directive.len()..];
+
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) || rest.starts_with('"') {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+fn parse_include_path(include: &str) -> Option<&str> {
+    if include.len() >= 2 && include.starts_with('"') && include.ends_with('"') {
+        Some(&include[1..include.len() - 1])
+    } else {
+        None
+    }
+}
+
+/// Removes `.` and `..` components from a path where possible, so that the same file
+/// is always referred to by the same path.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Finds the source string number at the start of a line of a compiler log, returning
+/// its start and end positions along with the number itself.
+///
+/// Drivers format their locations differently (e.g. `0:12(5):` on Mesa, `0(12) :` on
+/// NVIDIA, `ERROR: 0:12:` on ANGLE), but the source string number always comes first
+/// (other than the severity), followed by the line number.
+fn find_source_number(line: &str) -> Option<(usize, usize, usize)> {
+    let mut start = line.len() - line.trim_start().len();
+
+    if let Some(colon) = line[start..].find(": ") {
+        let severity = &line[start..start + colon];
+
+        if !severity.is_empty() && severity.bytes().all(|b| b.is_ascii_alphabetic()) {
+            start += colon + 2;
+        }
+    }
+
+    let bytes = line.as_bytes();
+
+    let end = start
+        + bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+
+    if end == start {
+        return None;
+    }
+
+    match (bytes.get(end), bytes.get(end + 1)) {
+        (Some(b':'), Some(next)) | (Some(b'('), Some(next)) if next.is_ascii_digit() => line
+            [start..end]
+            .parse()
+            .ok()
+            .map(|number| (start, end, number)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(path: &Path) -> Result<String> {
+        let source = match path.to_str().unwrap() {
+            "shaders/common.glsl" => "float common() {\n    return 1.0;\n}",
+            "shaders/lib/noise.glsl" => "#include \"../common.glsl\"\nfloat noise() {}",
+            "shaders/loop.glsl" => "#include \"loop.glsl\"",
+            _ => panic!("unexpected path: {}", path.display()),
+        };
+
+        Ok(source.to_owned())
+    }
+
+    #[test]
+    fn includes_are_expanded() {
+        let mut preprocessor = Preprocessor::new(&[]);
+
+        let output = preprocessor
+            .process(
+                "#version 330\n#include \"lib/noise.glsl\"\nvoid main() {}",
+                "main.frag".into(),
+                Path::new("shaders/main.frag"),
+                &mut load,
+            )
+            .unwrap();
+
+        assert_eq!(
+            output,
+            "#version 330\n\
+             #line 2 0\n\
+             #line 1 1\n\
+             #line 1 2\n\
+             float common() {\n    return 1.0;\n}\n\
+             #line 2 1\n\
+             float noise() {}\n\
+             #line 3 0\n\
+             void main() {}\n"
+        );
+
+        assert_eq!(
+            preprocessor.into_dependencies(),
+            vec![
+                PathBuf::from("shaders/lib/noise.glsl"),
+                PathBuf::from("shaders/common.glsl"),
+            ]
+        );
+    }
+
+    #[test]
+    fn defines_follow_version() {
+        let defines = vec![("MAX_LIGHTS".to_owned(), "16".to_owned())];
+        let mut preprocessor = Preprocessor::new(&defines);
+
+        let output = preprocessor
+            .process_string(
+                "// Comment\n#version 150\nvoid main() {}",
+                "shader",
+                &mut load,
+            )
+            .unwrap();
+
+        // GLSL 1.50 uses the older `#line` behaviour.
+        assert_eq!(
+            output,
+            "// Comment\n#version 150\n#define MAX_LIGHTS 16\n#line 2 0\nvoid main() {}\n"
+        );
+    }
+
+    #[test]
+    fn circular_includes_are_rejected() {
+        let mut preprocessor = Preprocessor::new(&[]);
+
+        let result = preprocessor.process(
+            "#include \"loop.glsl\"",
+            "main.frag".into(),
+            Path::new("shaders/main.frag"),
+            &mut load,
+        );
+
+        assert!(matches!(result, Err(TetraError::InvalidShader(_))));
+    }
+
+    #[test]
+    fn logs_are_remapped() {
+        let mut preprocessor = Preprocessor::new(&[]);
+        preprocessor.names = vec!["main.frag".into(), "noise.glsl".into()];
+
+        assert_eq!(
+            preprocessor.remap_log("0:12(5): error: `foo' undeclared"),
+            "main.frag:12(5): error: `foo' undeclared"
+        );
+
+        assert_eq!(
+            preprocessor.remap_log("1(7) : error C1008: undefined variable"),
+            "noise.glsl(7) : error C1008: undefined variable"
+        );
+
+        assert_eq!(
+            preprocessor.remap_log("ERROR: 1:3: 'x' : undeclared identifier\nERROR: 5:1: other"),
+            "ERROR: noise.glsl:3: 'x' : undeclared identifier\nERROR: 5:1: other"
+        );
+
+        assert_eq!(
+            preprocessor.remap_log("error: linking failed 0:1"),
+            "error: linking failed 0:1"
+        );
+    }
+}
@@ -0,0 +1,527 @@
+//! Functions and types relating to sprite sheets exported from external tools.
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+use crate::graphics::animation::{Animation, AnimationDirection};
+use crate::graphics::{Rectangle, Texture, TextureRegion};
+use crate::math::Vec2;
+use crate::Context;
+
+/// A sprite sheet, loaded from the JSON data exported by
+/// [Aseprite](https://www.aseprite.org/) or [TexturePacker](https://www.codeandweb.com/texturepacker).
+///
+/// Both the 'hash' and 'array' variants of the JSON format are supported. Each frame in the
+/// sheet is stored as a [`TextureRegion`], along with its name and (if the sheet was exported
+/// from Aseprite) its duration. Aseprite's frame tags are also loaded, and can be turned into
+/// [`Animation`]s via [`animation`](Self::animation).
+///
+/// Trimmed frames are supported, but rotated frames are not - make sure to disable rotation
+/// when exporting from TexturePacker.
+///
+/// This type requires the `sprite_sheet_json` feature to be enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{Drawable, SpriteSheet};
+/// # use tetra::math::Vec2;
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let sheet = SpriteSheet::new(ctx, "./player.json")?;
+///
+/// let mut run = sheet.animation("run").expect("sheet should have a 'run' tag");
+///
+/// run.advance(ctx);
+/// run.draw(ctx, Vec2::new(16.0, 16.0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    texture: Texture,
+    frames: Vec<SpriteFrame>,
+    frame_indices: HashMap<String, usize>,
+    tags: Vec<FrameTag>,
+}
+
+impl SpriteSheet {
+    /// The frame length that will be used for frames that do not specify a duration.
+    ///
+    /// This matches the default frame duration in Aseprite.
+    pub const DEFAULT_FRAME_LENGTH: Duration = Duration::from_millis(100);
+
+    /// Loads a sprite sheet from the given JSON file.
+    ///
+    /// The texture will be loaded from the path specified in the file's metadata,
+    /// relative to the directory that the JSON file is in.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the JSON file or the
+    ///   texture could not be loaded.
+    /// * [`TetraError::InvalidSpriteSheet`] will be returned if the JSON data is invalid, or
+    ///   does not specify a texture.
+    /// * [`TetraError::InvalidTexture`] will be returned if the texture data is invalid.
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn new<P>(ctx: &mut Context, path: P) -> Result<SpriteSheet>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        let data = parse(&json)?;
+
+        let image = data.meta.image.as_deref().ok_or_else(|| {
+            TetraError::InvalidSpriteSheet(format!("{} does not specify an image", path.display()))
+        })?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let texture = Texture::new(ctx, directory.join(image))?;
+
+        SpriteSheet::from_data(texture, data)
+    }
+
+    /// Creates a sprite sheet from a string of JSON data and an already-loaded texture.
+    ///
+    /// The image path in the JSON's metadata will be ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSpriteSheet`] will be returned if the JSON data is invalid.
+    pub fn from_json(texture: Texture, json: &str) -> Result<SpriteSheet> {
+        SpriteSheet::from_data(texture, parse(json)?)
+    }
+
+    fn from_data(texture: Texture, data: SheetData) -> Result<SpriteSheet> {
+        let mut frames = Vec::with_capacity(data.frames.len());
+        let mut frame_indices = HashMap::with_capacity(data.frames.len());
+
+        for (name, frame) in data.frames {
+            if frame.rotated {
+                return Err(TetraError::InvalidSpriteSheet(format!(
+                    "frame '{}' is rotated, which is not supported",
+                    name
+                )));
+            }
+
+            let bounds = frame.frame.to_rectangle();
+
+            let region = if frame.trimmed {
+                TextureRegion::trimmed(
+                    texture.clone(),
+                    bounds,
+                    Vec2::new(frame.sprite_source_size.x, frame.sprite_source_size.y),
+                    Vec2::new(frame.source_size.w, frame.source_size.h),
+                )
+            } else {
+                TextureRegion::new(texture.clone(), bounds)
+            };
+
+            frame_indices.insert(name.clone(), frames.len());
+
+            frames.push(SpriteFrame {
+                name,
+                region,
+                duration: frame.duration.map(Duration::from_millis),
+            });
+        }
+
+        let mut tags = Vec::with_capacity(data.meta.frame_tags.len());
+
+        for tag in data.meta.frame_tags {
+            if tag.from > tag.to || tag.to >= frames.len() {
+                return Err(TetraError::InvalidSpriteSheet(format!(
+                    "frame tag '{}' refers to frames that do not exist",
+                    tag.name
+                )));
+            }
+
+            tags.push(FrameTag {
+                name: tag.name,
+                from: tag.from,
+                to: tag.to,
+                direction: tag.direction,
+            });
+        }
+
+        Ok(SpriteSheet {
+            texture,
+            frames,
+            frame_indices,
+            tags,
+        })
+    }
+
+    /// Returns a reference to the sprite sheet's texture.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Returns the frames in the sprite sheet, in the order they were exported.
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    /// Returns the frame with the specified name, or `None` if there is no frame with
+    /// that name.
+    pub fn frame(&self, name: &str) -> Option<&SpriteFrame> {
+        self.frame_indices
+            .get(name)
+            .map(|&index| &self.frames[index])
+    }
+
+    /// Returns the frame tags in the sprite sheet.
+    pub fn tags(&self) -> &[FrameTag] {
+        &self.tags
+    }
+
+    /// Returns the frame tag with the specified name, or `None` if there is no tag with
+    /// that name.
+    pub fn tag(&self, name: &str) -> Option<&FrameTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// Creates a looping [`Animation`] from the frame tag with the specified name, or
+    /// returns `None` if there is no tag with that name.
+    ///
+    /// The animation will use the tag's direction, and each frame's duration (or
+    /// [`DEFAULT_FRAME_LENGTH`](Self::DEFAULT_FRAME_LENGTH), if the frame does not have
+    /// a duration).
+    pub fn animation(&self, tag: &str) -> Option<Animation> {
+        let tag = self.tag(tag)?;
+
+        let frames = &self.frames[tag.from..=tag.to];
+
+        let regions = frames.iter().map(|frame| frame.region.clone()).collect();
+
+        let frame_lengths = frames
+            .iter()
+            .map(|frame| frame.duration.unwrap_or(Self::DEFAULT_FRAME_LENGTH))
+            .collect();
+
+        let mut animation = Animation::from_regions_with_lengths(regions, frame_lengths);
+
+        animation.set_direction(match tag.direction {
+            TagDirection::Forward => AnimationDirection::Forward,
+            TagDirection::Reverse => AnimationDirection::Reverse,
+            TagDirection::PingPong => AnimationDirection::PingPong,
+            TagDirection::PingPongReverse => AnimationDirection::PingPongReverse,
+        });
+
+        Some(animation)
+    }
+}
+
+/// A single frame within a [`SpriteSheet`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteFrame {
+    /// The name of the frame (usually the filename of the original image).
+    pub name: String,
+
+    /// The region of the sprite sheet's texture that contains the frame.
+    pub region: TextureRegion,
+
+    /// How long the frame should be displayed for, if specified by the sprite sheet.
+    pub duration: Option<Duration>,
+}
+
+/// A named range of frames within a [`SpriteSheet`].
+///
+/// These correspond to the tags that can be added to a sprite in Aseprite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameTag {
+    /// The name of the tag.
+    pub name: String,
+
+    /// The index of the first frame in the tag.
+    pub from: usize,
+
+    /// The index of the last frame in the tag (inclusive).
+    pub to: usize,
+
+    /// The order in which the tag's frames should be played.
+    pub direction: TagDirection,
+}
+
+/// The order in which the frames of a [`FrameTag`] should be played.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum TagDirection {
+    /// The frames are played from first to last.
+    #[serde(rename = "forward")]
+    Forward,
+
+    /// The frames are played from last to first.
+    #[serde(rename = "reverse")]
+    Reverse,
+
+    /// The frames are played from first to last, and then back again.
+    #[serde(rename = "pingpong")]
+    PingPong,
+
+    /// The frames are played from last to first, and then back again.
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+#[derive(Debug, Deserialize)]
+struct SheetData {
+    frames: FrameList,
+
+    #[serde(default)]
+    meta: MetaData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetaData {
+    image: Option<String>,
+
+    #[serde(default)]
+    frame_tags: Vec<TagData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameData {
+    #[serde(default)]
+    filename: Option<String>,
+
+    frame: RectData,
+
+    #[serde(default)]
+    rotated: bool,
+
+    #[serde(default)]
+    trimmed: bool,
+
+    sprite_source_size: RectData,
+    source_size: SizeData,
+    duration: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagData {
+    name: String,
+    from: usize,
+    to: usize,
+
+    #[serde(default = "default_direction")]
+    direction: TagDirection,
+}
+
+fn default_direction() -> TagDirection {
+    TagDirection::Forward
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+struct RectData {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl RectData {
+    fn to_rectangle(self) -> Rectangle {
+        Rectangle::new(self.x, self.y, self.w, self.h)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+struct SizeData {
+    w: f32,
+    h: f32,
+}
+
+/// The frames of a sprite sheet, paired with their names.
+///
+/// The 'hash' format stores the frames as a JSON object, but frame tags refer to frames
+/// by index, so the order of the keys has to be preserved.
+#[derive(Debug)]
+struct FrameList(Vec<(String, FrameData)>);
+
+impl FrameList {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl IntoIterator for FrameList {
+    type Item = (String, FrameData);
+    type IntoIter = std::vec::IntoIter<(String, FrameData)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'de> Deserialize<'de> for FrameList {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<FrameList, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FrameListVisitor;
+
+        impl<'de> Visitor<'de> for FrameListVisitor {
+            type Value = FrameList;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an array or map of frames")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<FrameList, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut frames = Vec::new();
+
+                while let Some(frame) = seq.next_element::<FrameData>()? {
+                    let name = frame
+                        .filename
+                        .clone()
+                        .ok_or_else(|| de::Error::missing_field("filename"))?;
+
+                    frames.push((name, frame));
+                }
+
+                Ok(FrameList(frames))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<FrameList, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut frames = Vec::new();
+
+                while let Some(entry) = map.next_entry::<String, FrameData>()? {
+                    frames.push(entry);
+                }
+
+                Ok(FrameList(frames))
+            }
+        }
+
+        deserializer.deserialize_any(FrameListVisitor)
+    }
+}
+
+fn parse(json: &str) -> Result<SheetData> {
+    serde_json::from_str(json).map_err(|e| {
+        TetraError::InvalidSpriteSheet(format!("could not parse sprite sheet data: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = r#"{
+        "frames": {
+            "walk 1.aseprite": {
+                "frame": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "sourceSize": { "w": 16, "h": 16 },
+                "duration": 150
+            },
+            "walk 0.aseprite": {
+                "frame": { "x": 16, "y": 0, "w": 12, "h": 14 },
+                "rotated": false,
+                "trimmed": true,
+                "spriteSourceSize": { "x": 2, "y": 1, "w": 12, "h": 14 },
+                "sourceSize": { "w": 16, "h": 16 },
+                "duration": 100
+            }
+        },
+        "meta": {
+            "image": "walk.png",
+            "frameTags": [
+                { "name": "walk", "from": 0, "to": 1, "direction": "pingpong" }
+            ]
+        }
+    }"#;
+
+    const ARRAY: &str = r#"{
+        "frames": [
+            {
+                "filename": "idle.png",
+                "frame": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "sourceSize": { "w": 16, "h": 16 }
+            }
+        ],
+        "meta": { "image": "sheet.png" }
+    }"#;
+
+    #[test]
+    fn parse_hash() {
+        let data = parse(HASH).unwrap();
+
+        assert_eq!(data.meta.image.as_deref(), Some("walk.png"));
+
+        // The order of the keys should be preserved, rather than sorted.
+        let names: Vec<&str> = data.frames.0.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["walk 1.aseprite", "walk 0.aseprite"]);
+
+        let (_, trimmed) = &data.frames.0[1];
+        assert!(trimmed.trimmed);
+        assert_eq!(trimmed.sprite_source_size.x, 2.0);
+        assert_eq!(trimmed.duration, Some(100));
+
+        assert_eq!(data.meta.frame_tags[0].direction, TagDirection::PingPong);
+    }
+
+    #[test]
+    fn parse_array() {
+        let data = parse(ARRAY).unwrap();
+
+        assert_eq!(data.frames.len(), 1);
+        assert_eq!(data.frames.0[0].0, "idle.png");
+        assert_eq!(data.frames.0[0].1.duration, None);
+        assert!(data.meta.frame_tags.is_empty());
+    }
+
+    #[test]
+    fn parse_tag_directions() {
+        let json = r#"{
+            "frames": [],
+            "meta": {
+                "frameTags": [
+                    { "name": "a", "from": 0, "to": 0, "direction": "forward" },
+                    { "name": "b", "from": 0, "to": 0, "direction": "reverse" },
+                    { "name": "c", "from": 0, "to": 0, "direction": "pingpong_reverse" }
+                ]
+            }
+        }"#;
+
+        let data = parse(json).unwrap();
+        let directions: Vec<TagDirection> =
+            data.meta.frame_tags.iter().map(|t| t.direction).collect();
+
+        assert_eq!(
+            directions,
+            [
+                TagDirection::Forward,
+                TagDirection::Reverse,
+                TagDirection::PingPongReverse
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("{}").is_err());
+        assert!(parse(r#"{ "frames": [{ "frame": {} }] }"#).is_err());
+    }
+}
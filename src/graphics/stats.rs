@@ -0,0 +1,59 @@
+//! Functions and types relating to rendering statistics.
+
+/// Statistics about the rendering work that has been done during the current frame.
+///
+/// These can be retrieved via [`graphics::get_stats`](super::get_stats). The per-frame
+/// counters are reset every time the screen is [presented](super::present), while the
+/// memory usage fields reflect the totals at the time that the stats were retrieved.
+///
+/// # Batching
+///
+/// The `*_flushes` fields count how many times the sprite batch was sent to the graphics
+/// hardware, grouped by the reason that it had to be sent. If you are seeing more draw calls
+/// than you expect, these can help you work out what is breaking up your batches - for example,
+/// a high number of texture flushes suggests that you should try to group draws that use the
+/// same texture together, or combine your images into a single texture.
+///
+/// Flushes that occur while the batch is empty are not counted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// The number of draw calls that have been sent to the graphics hardware.
+    ///
+    /// This includes both flushes of the sprite batch and [`Mesh`](super::Mesh) draws.
+    pub draw_calls: usize,
+
+    /// The number of vertices that have been sent to the graphics hardware.
+    ///
+    /// Each sprite in the batch counts as four vertices. For indexed meshes, every vertex
+    /// in the mesh's vertex buffer is counted, as any of them can be referenced by the indices.
+    pub vertices: usize,
+
+    /// The number of times the sprite batch was flushed because a different texture
+    /// was drawn.
+    pub texture_flushes: usize,
+
+    /// The number of times the sprite batch was flushed because the shader was changed.
+    pub shader_flushes: usize,
+
+    /// The number of times the sprite batch was flushed because the canvas was changed.
+    pub canvas_flushes: usize,
+
+    /// The number of times the sprite batch was flushed because it was full.
+    ///
+    /// If this is high, you may want to increase the maximum batch size via
+    /// [`ContextBuilder::batch_size`](crate::ContextBuilder::batch_size).
+    pub batch_full_flushes: usize,
+
+    /// The number of times the sprite batch was flushed for any other reason, such as
+    /// other render state being changed, a [`Mesh`](super::Mesh) being drawn, or
+    /// [`graphics::flush`](super::flush) being called manually.
+    pub other_flushes: usize,
+
+    /// The approximate amount of GPU memory currently being used by textures, canvases and
+    /// stencil buffers, in bytes.
+    pub texture_memory: usize,
+
+    /// The approximate amount of GPU memory currently being used by vertex and index
+    /// buffers (including the sprite batch), in bytes.
+    pub buffer_memory: usize,
+}
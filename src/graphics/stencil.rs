@@ -0,0 +1,161 @@
+//! Functions and types relating to the stencil buffer.
+
+/// The state of the stencil buffer, determining how it is read from and written to
+/// while rendering.
+///
+/// The stencil buffer stores an 8-bit value for each pixel of the render target. This can be
+/// used to mask out parts of the screen - for example, you can draw a shape into the stencil
+/// buffer, and then only draw sprites where the shape was (or wasn't) drawn.
+///
+/// Note that the stencil buffer is only available if it has been enabled for the current render
+/// target, via [`ContextBuilder::stencil_buffer`](crate::ContextBuilder::stencil_buffer) for the
+/// window, or [`CanvasBuilder::stencil_buffer`](super::CanvasBuilder::stencil_buffer) for a canvas.
+///
+/// The [`write`](Self::write), [`read`](Self::read) and [`disabled`](Self::disabled) constructors
+/// cover the most common use cases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StencilState {
+    /// Whether or not the stencil test is enabled.
+    ///
+    /// If this is `false`, the other fields will be ignored.
+    pub enabled: bool,
+
+    /// The action that will be performed on the stencil buffer when a pixel passes
+    /// the stencil test.
+    pub action: StencilAction,
+
+    /// The test that the existing stencil value must pass for a pixel to be drawn.
+    ///
+    /// The reference value is compared against the value in the stencil buffer -
+    /// for example, `StencilTest::GreaterThan` will only draw a pixel if the reference
+    /// value is greater than the stored value.
+    pub test: StencilTest,
+
+    /// The value that is used for the stencil test and for [`StencilAction::Replace`].
+    pub reference_value: u8,
+
+    /// A mask that determines which bits of the stencil buffer can be written to.
+    pub write_mask: u8,
+
+    /// A mask that is applied to both the reference value and the stored value
+    /// before they are compared.
+    pub read_mask: u8,
+}
+
+impl StencilState {
+    /// Creates a stencil state that disables the stencil test. Anything drawn while this
+    /// state is active will neither read from nor write to the stencil buffer.
+    ///
+    /// This is the default state.
+    pub fn disabled() -> StencilState {
+        StencilState {
+            enabled: false,
+            action: StencilAction::Keep,
+            test: StencilTest::Always,
+            reference_value: 0,
+            write_mask: 0x00,
+            read_mask: 0xFF,
+        }
+    }
+
+    /// Creates a stencil state that writes to the stencil buffer, without doing any testing.
+    ///
+    /// Anything drawn while this state is active will apply the specified action to the
+    /// stencil buffer. For example, `StencilState::write(StencilAction::Replace, 1)` will
+    /// set the stencil value to `1` wherever something is drawn.
+    ///
+    /// If you do not want the drawn shapes to also be visible, you can use
+    /// [`graphics::set_color_mask`](super::set_color_mask) to temporarily disable
+    /// color output.
+    pub fn write(action: StencilAction, reference_value: u8) -> StencilState {
+        StencilState {
+            enabled: true,
+            action,
+            test: StencilTest::Always,
+            reference_value,
+            write_mask: 0xFF,
+            read_mask: 0xFF,
+        }
+    }
+
+    /// Creates a stencil state that tests against the stencil buffer, without writing to it.
+    ///
+    /// Anything drawn while this state is active will only be visible where the stencil test
+    /// passes. For example, `StencilState::read(StencilTest::EqualTo, 1)` will only draw
+    /// where the stencil value is equal to `1`.
+    pub fn read(test: StencilTest, reference_value: u8) -> StencilState {
+        StencilState {
+            enabled: true,
+            action: StencilAction::Keep,
+            test,
+            reference_value,
+            write_mask: 0x00,
+            read_mask: 0xFF,
+        }
+    }
+}
+
+impl Default for StencilState {
+    fn default() -> StencilState {
+        StencilState::disabled()
+    }
+}
+
+/// Tests that can be performed against the stencil buffer.
+///
+/// The test compares the reference value of the [`StencilState`] (on the left) against the
+/// value currently stored in the stencil buffer (on the right).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StencilTest {
+    /// The test never passes.
+    Never,
+
+    /// The test passes if the reference value is less than the stored value.
+    LessThan,
+
+    /// The test passes if the reference value is less than or equal to the stored value.
+    LessThanOrEqualTo,
+
+    /// The test passes if the reference value is equal to the stored value.
+    EqualTo,
+
+    /// The test passes if the reference value is not equal to the stored value.
+    NotEqualTo,
+
+    /// The test passes if the reference value is greater than the stored value.
+    GreaterThan,
+
+    /// The test passes if the reference value is greater than or equal to the stored value.
+    GreaterThanOrEqualTo,
+
+    /// The test always passes.
+    Always,
+}
+
+/// Actions that can be applied to the stencil buffer when a pixel passes the stencil test.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StencilAction {
+    /// The stored value is left unchanged.
+    Keep,
+
+    /// The stored value is set to zero.
+    Zero,
+
+    /// The stored value is replaced with the reference value.
+    Replace,
+
+    /// The stored value is incremented, stopping at the maximum value.
+    Increment,
+
+    /// The stored value is incremented, wrapping back to zero if it exceeds the maximum value.
+    IncrementWrap,
+
+    /// The stored value is decremented, stopping at zero.
+    Decrement,
+
+    /// The stored value is decremented, wrapping to the maximum value if it goes below zero.
+    DecrementWrap,
+
+    /// The bits of the stored value are inverted.
+    Invert,
+}
@@ -5,7 +5,6 @@
 #![cfg_attr(not(feature = "font_ttf"), allow(unused))]
 
 mod cache;
-mod packer;
 #[cfg(feature = "font_ttf")]
 mod vector;
 
@@ -3,7 +3,7 @@ use hashbrown::HashMap;
 use unicode_normalization::UnicodeNormalization;
 
 use crate::error::Result;
-use crate::graphics::text::packer::ShelfPacker;
+use crate::graphics::packer::ShelfPacker;
 use crate::graphics::{Rectangle, Texture};
 use crate::math::Vec2;
 use crate::platform::GraphicsDevice;
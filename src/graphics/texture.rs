@@ -4,9 +4,8 @@ use std::cell::Cell;
 use std::path::Path;
 use std::rc::Rc;
 
-use crate::error::{Result, TetraError};
-use crate::fs;
-use crate::graphics::{self, DrawParams, Drawable};
+use crate::error::Result;
+use crate::graphics::{self, Color, DrawParams, Drawable, ImageData};
 use crate::platform::{GraphicsDevice, RawTexture};
 use crate::Context;
 
@@ -14,11 +13,12 @@ use crate::Context;
 pub(crate) struct TextureSharedData {
     pub(crate) handle: RawTexture,
     filter_mode: Cell<FilterMode>,
+    wrap_mode: Cell<WrapMode>,
 }
 
 impl PartialEq for TextureSharedData {
     fn eq(&self, other: &TextureSharedData) -> bool {
-        // filter_mode should always match what's set on the GPU,
+        // filter_mode and wrap_mode should always match what's set on the GPU,
         // so we can ignore it for equality checks.
 
         self.handle.eq(&other.handle)
@@ -77,15 +77,8 @@ impl Texture {
     where
         P: AsRef<Path>,
     {
-        let image = fs::read_to_image(path)?.to_rgba8();
-        let (width, height) = image.dimensions();
-
-        Texture::from_rgba(
-            ctx,
-            width as i32,
-            height as i32,
-            image.into_raw().as_slice(),
-        )
+        let image = ImageData::new(path)?;
+        Texture::from_image_data(ctx, &image)
     }
 
     /// Creates a new texture from a slice of data, encoded in one of Tetra's supported
@@ -104,18 +97,8 @@ impl Texture {
     /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
     /// * [`TetraError::InvalidTexture`] will be returned if the texture data was invalid.
     pub fn from_file_data(ctx: &mut Context, data: &[u8]) -> Result<Texture> {
-        let image = image::load_from_memory(data)
-            .map_err(TetraError::InvalidTexture)?
-            .to_rgba8();
-
-        let (width, height) = image.dimensions();
-
-        Texture::from_rgba(
-            ctx,
-            width as i32,
-            height as i32,
-            image.into_raw().as_slice(),
-        )
+        let image = ImageData::from_file_data(data)?;
+        Texture::from_image_data(ctx, &image)
     }
 
     /// Creates a new texture from a slice of RGBA pixel data.
@@ -137,17 +120,37 @@ impl Texture {
             height,
             data,
             ctx.graphics.default_filter_mode,
+            false,
         )
     }
 
+    /// Creates a new texture from an [`ImageData`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn from_image_data(ctx: &mut Context, image: &ImageData) -> Result<Texture> {
+        let (width, height) = image.size();
+        Texture::from_rgba(ctx, width, height, image.as_bytes())
+    }
+
+    /// Creates a new texture builder, which can be used to create a texture with
+    /// additional settings (e.g. mipmaps).
+    ///
+    /// This is a shortcut for calling [`TextureBuilder::new`].
+    pub fn builder() -> TextureBuilder {
+        TextureBuilder::new()
+    }
+
     pub(crate) fn with_device(
         device: &mut GraphicsDevice,
         width: i32,
         height: i32,
         data: &[u8],
         filter_mode: FilterMode,
+        mipmaps: bool,
     ) -> Result<Texture> {
-        let handle = device.new_texture(width, height)?;
+        let handle = device.new_texture(width, height, mipmaps)?;
 
         device.set_texture_data(&handle, &data, 0, 0, width, height)?;
         device.set_texture_filter_mode(&handle, filter_mode);
@@ -155,7 +158,8 @@ impl Texture {
         Ok(Texture {
             data: Rc::new(TextureSharedData {
                 handle,
-                filter_mode: Cell::new(FilterMode::Linear),
+                filter_mode: Cell::new(filter_mode),
+                wrap_mode: Cell::new(WrapMode::ClampToEdge),
             }),
         })
     }
@@ -165,14 +169,16 @@ impl Texture {
         width: i32,
         height: i32,
         filter_mode: FilterMode,
+        mipmaps: bool,
     ) -> Result<Texture> {
-        let handle = device.new_texture(width, height)?;
+        let handle = device.new_texture(width, height, mipmaps)?;
         device.set_texture_filter_mode(&handle, filter_mode);
 
         Ok(Texture {
             data: Rc::new(TextureSharedData {
                 handle,
                 filter_mode: Cell::new(filter_mode),
+                wrap_mode: Cell::new(WrapMode::ClampToEdge),
             }),
         })
     }
@@ -205,6 +211,51 @@ impl Texture {
         self.data.filter_mode.set(filter_mode);
     }
 
+    /// Returns whether or not the texture has mipmaps.
+    pub fn has_mipmaps(&self) -> bool {
+        self.data.handle.mipmaps()
+    }
+
+    /// Sets whether or not the texture should have mipmaps.
+    ///
+    /// Mipmaps are pre-shrunk copies of a texture, which the GPU can use when the texture is
+    /// being drawn at a smaller size than its actual dimensions. This avoids the flickering
+    /// and shimmering that can occur when a large texture is scaled down (e.g. when zooming
+    /// a [`Camera`](super::Camera) out).
+    ///
+    /// When mipmaps are enabled, they will be generated immediately, and then regenerated
+    /// whenever the texture's data is changed (e.g. via [`set_data`](Self::set_data)).
+    /// If you know that a texture will need mipmaps when you load it, use
+    /// [`TextureBuilder::mipmaps`] instead, so that they are only generated once.
+    ///
+    /// Note that mipmaps will only be used if the texture's filter mode is set to one of the
+    /// mipmapped [`FilterMode`]s, such as [`FilterMode::LinearMipmapLinear`].
+    ///
+    /// # Performance
+    ///
+    /// Mipmaps increase the amount of GPU memory used by the texture by roughly a third, and
+    /// regenerating them takes time. Avoid enabling them on textures that are updated frequently.
+    pub fn set_mipmaps(&mut self, ctx: &mut Context, mipmaps: bool) {
+        ctx.device.set_texture_mipmaps(&self.data.handle, mipmaps);
+    }
+
+    /// Returns the wrap mode being used by the texture.
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.data.wrap_mode.get()
+    }
+
+    /// Sets the wrap mode that should be used by the texture.
+    ///
+    /// This determines what will be drawn when the texture is sampled outside of its
+    /// bounds - for example, when drawing with a [`DrawParams::clip`] rectangle that is
+    /// larger than the texture.
+    pub fn set_wrap_mode(&mut self, ctx: &mut Context, wrap_mode: WrapMode) {
+        ctx.device
+            .set_texture_wrap_mode(&self.data.handle, wrap_mode);
+
+        self.data.wrap_mode.set(wrap_mode);
+    }
+
     /// Writes RGBA pixel data to a specified region of the texture.
     ///
     /// This method requires you to provide enough data to fill the target rectangle.
@@ -236,6 +287,23 @@ impl Texture {
             .set_texture_data(&self.data.handle, &data, x, y, width, height)
     }
 
+    /// Reads the texture's pixel data back from the GPU.
+    ///
+    /// The data is returned in RGBA format, with the rows ordered from top to bottom.
+    ///
+    /// This will trigger a [`flush`](graphics::flush) to the graphics hardware, to ensure
+    /// that any pending drawing operations are taken into account.
+    ///
+    /// # Performance
+    ///
+    /// This is a relatively expensive operation, as the CPU has to wait for the GPU
+    /// to finish any work that affects the texture. Try to avoid calling it every frame.
+    pub fn get_data(&self, ctx: &mut Context) -> Vec<u8> {
+        graphics::flush(ctx);
+
+        ctx.device.get_texture_data(&self.data.handle)
+    }
+
     /// Overwrites the entire texture with new RGBA pixel data.
     ///
     /// This method requires you to provide enough data to fill the texture.
@@ -255,6 +323,119 @@ impl Texture {
     }
 }
 
+/// A builder for creating a [`Texture`] with additional settings.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{FilterMode, Texture};
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let texture = Texture::builder()
+///     .mipmaps(true)
+///     .filter_mode(FilterMode::LinearMipmapLinear)
+///     .build(ctx, "./examples/resources/player.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TextureBuilder {
+    filter_mode: Option<FilterMode>,
+    mipmaps: bool,
+}
+
+impl TextureBuilder {
+    /// Creates a new `TextureBuilder`, with the default settings.
+    pub fn new() -> TextureBuilder {
+        TextureBuilder::default()
+    }
+
+    /// Sets the filter mode that the texture should use.
+    ///
+    /// Defaults to the value of [`graphics::get_default_filter_mode`].
+    pub fn filter_mode(&mut self, filter_mode: FilterMode) -> &mut TextureBuilder {
+        self.filter_mode = Some(filter_mode);
+        self
+    }
+
+    /// Sets whether or not the texture should have mipmaps.
+    ///
+    /// The mipmaps will be generated once, after the texture's data has been uploaded.
+    /// See [`Texture::set_mipmaps`] for more details.
+    ///
+    /// Defaults to `false`.
+    pub fn mipmaps(&mut self, mipmaps: bool) -> &mut TextureBuilder {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Builds the texture from the given file.
+    ///
+    /// The format will be determined based on the file extension.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+    /// * [`TetraError::InvalidTexture`] will be returned if the texture data was invalid.
+    pub fn build<P>(&self, ctx: &mut Context, path: P) -> Result<Texture>
+    where
+        P: AsRef<Path>,
+    {
+        let image = ImageData::new(path)?;
+        self.build_from_image_data(ctx, &image)
+    }
+
+    /// Builds the texture from a slice of data, encoded in one of Tetra's supported
+    /// file formats (except for TGA).
+    ///
+    /// See [`Texture::from_file_data`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    /// * [`TetraError::InvalidTexture`] will be returned if the texture data was invalid.
+    pub fn build_from_file_data(&self, ctx: &mut Context, data: &[u8]) -> Result<Texture> {
+        let image = ImageData::from_file_data(data)?;
+        self.build_from_image_data(ctx, &image)
+    }
+
+    /// Builds the texture from a slice of RGBA pixel data.
+    ///
+    /// See [`Texture::from_rgba`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
+    ///   the texture.
+    pub fn build_from_rgba(
+        &self,
+        ctx: &mut Context,
+        width: i32,
+        height: i32,
+        data: &[u8],
+    ) -> Result<Texture> {
+        Texture::with_device(
+            &mut ctx.device,
+            width,
+            height,
+            data,
+            self.filter_mode.unwrap_or(ctx.graphics.default_filter_mode),
+            self.mipmaps,
+        )
+    }
+
+    /// Builds the texture from an [`ImageData`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn build_from_image_data(&self, ctx: &mut Context, image: &ImageData) -> Result<Texture> {
+        let (width, height) = image.size();
+        self.build_from_rgba(ctx, width, height, image.as_bytes())
+    }
+}
+
 impl Drawable for Texture {
     fn draw<P>(&self, ctx: &mut Context, params: P)
     where
@@ -288,6 +469,10 @@ impl Drawable for Texture {
 /// Filtering algorithms that can be used when scaling an image.
 ///
 /// Tetra currently defaults to using `Nearest` for all newly created textures.
+///
+/// The mipmapped variants will only have an effect when an image is being scaled down, and
+/// only if the texture has [mipmaps enabled](Texture::set_mipmaps) - otherwise, they behave
+/// the same as `Nearest` or `Linear`.
 #[derive(Debug, Clone, Copy)]
 pub enum FilterMode {
     /// Nearest-neighbor interpolation. This preserves hard edges and details, but may look pixelated.
@@ -297,4 +482,56 @@ pub enum FilterMode {
 
     /// Linear interpolation. This smooths images when scaling them up or down.
     Linear,
+
+    /// Nearest-neighbor interpolation, using the closest mipmap level when scaling down.
+    NearestMipmapNearest,
+
+    /// Nearest-neighbor interpolation, blending between the two closest mipmap levels
+    /// when scaling down.
+    NearestMipmapLinear,
+
+    /// Linear interpolation, using the closest mipmap level when scaling down.
+    LinearMipmapNearest,
+
+    /// Linear interpolation, blending between the two closest mipmap levels when scaling
+    /// down. This is often referred to as trilinear filtering.
+    ///
+    /// This gives the smoothest results when textures are drawn at a variety of sizes.
+    LinearMipmapLinear,
+}
+
+/// Algorithms that can be used when a texture is sampled outside of its bounds.
+///
+/// This allows a texture to be tiled across a large area in a single draw, which is useful
+/// for things like scrolling backgrounds and repeating fills. To do this, set the texture's
+/// wrap mode to `Repeat`, and then draw it with a [`DrawParams::clip`] rectangle that is larger
+/// than the texture:
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{DrawParams, Drawable, Rectangle, Texture, WrapMode};
+/// # fn run(ctx: &mut Context, texture: &mut Texture, scroll: f32) {
+/// texture.set_wrap_mode(ctx, WrapMode::Repeat);
+///
+/// texture.draw(
+///     ctx,
+///     DrawParams::new().clip(Rectangle::new(scroll, 0.0, 1280.0, 720.0)),
+/// );
+/// # }
+/// ```
+///
+/// Tetra currently defaults to using `ClampToEdge` for all newly created textures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrapMode {
+    /// The edge pixels of the texture will be stretched out to fill the space.
+    ClampToEdge,
+
+    /// The texture will be repeated.
+    Repeat,
+
+    /// The texture will be repeated, with every other repetition being mirrored.
+    MirroredRepeat,
+
+    /// The space outside of the texture will be filled with the specified color.
+    ClampToBorder(Color),
 }
@@ -0,0 +1,296 @@
+//! Functions and types relating to texture regions.
+
+use crate::graphics::{self, DrawParams, Drawable, Rectangle, Texture};
+use crate::math::Vec2;
+use crate::Context;
+
+/// A region of a texture, which can be drawn as if it were a texture in its own right.
+///
+/// This is useful for working with sprite sheets and [`TextureAtlas`](super::TextureAtlas)es,
+/// as it allows you to pass around a single image from the sheet without having to keep track
+/// of its bounds separately.
+///
+/// # Trimming
+///
+/// Some texture packing tools (including [`TextureAtlasBuilder::trim`](super::TextureAtlasBuilder::trim))
+/// can remove the transparent space from around each image, to save space in the texture. A trimmed
+/// region stores the size of the original image, and the offset at which the trimmed bounds should
+/// be drawn, so that it will be positioned identically to the untrimmed image.
+///
+/// When drawing a region, the [`origin`](DrawParams::origin) and [`clip`](DrawParams::clip)
+/// parameters are relative to the original, untrimmed image.
+///
+/// # Performance
+///
+/// Cloning a `TextureRegion` is a very cheap operation, as the underlying texture is
+/// [reference-counted](https://doc.rust-lang.org/std/rc/struct.Rc.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureRegion {
+    texture: Texture,
+    bounds: Rectangle,
+    offset: Vec2<f32>,
+    original_size: Vec2<f32>,
+}
+
+impl TextureRegion {
+    /// Creates a new region, covering the specified bounds of a texture.
+    pub fn new(texture: Texture, bounds: Rectangle) -> TextureRegion {
+        TextureRegion {
+            texture,
+            bounds,
+            offset: Vec2::zero(),
+            original_size: Vec2::new(bounds.width, bounds.height),
+        }
+    }
+
+    /// Creates a new region for an image that has been trimmed.
+    ///
+    /// * `bounds` is the area of the texture that contains the trimmed image.
+    /// * `offset` is the position of the trimmed image within the original image.
+    /// * `original_size` is the size of the original image, before it was trimmed.
+    pub fn trimmed(
+        texture: Texture,
+        bounds: Rectangle,
+        offset: Vec2<f32>,
+        original_size: Vec2<f32>,
+    ) -> TextureRegion {
+        TextureRegion {
+            texture,
+            bounds,
+            offset,
+            original_size,
+        }
+    }
+
+    /// Returns a reference to the texture that the region is a part of.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Sets the texture that the region is a part of.
+    ///
+    /// This will not change the bounds of the region.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    /// Returns the bounds of the region within the texture.
+    pub fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    /// Returns the position of the region's bounds within the original image.
+    ///
+    /// This will be zero, unless the region has been trimmed.
+    pub fn offset(&self) -> Vec2<f32> {
+        self.offset
+    }
+
+    /// Returns the size of the original image.
+    ///
+    /// This will be the same as the size of the bounds, unless the region has been trimmed.
+    pub fn original_size(&self) -> Vec2<f32> {
+        self.original_size
+    }
+
+    /// Returns the width of the original image.
+    pub fn width(&self) -> f32 {
+        self.original_size.x
+    }
+
+    /// Returns the height of the original image.
+    pub fn height(&self) -> f32 {
+        self.original_size.y
+    }
+
+    /// Returns whether or not the region has been trimmed.
+    pub fn is_trimmed(&self) -> bool {
+        self.offset != Vec2::zero()
+            || self.original_size != Vec2::new(self.bounds.width, self.bounds.height)
+    }
+}
+
+impl From<Texture> for TextureRegion {
+    fn from(texture: Texture) -> TextureRegion {
+        let (width, height) = texture.size();
+
+        TextureRegion::new(
+            texture,
+            Rectangle::new(0.0, 0.0, width as f32, height as f32),
+        )
+    }
+}
+
+impl Drawable for TextureRegion {
+    fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+
+        let texture_size = Vec2::new(self.texture.width() as f32, self.texture.height() as f32);
+
+        // If the clip rectangle only covers the trimmed-away space, there's nothing to draw.
+        let quad = match region_quad(
+            self.bounds,
+            self.offset,
+            self.original_size,
+            params.clip,
+            texture_size,
+        ) {
+            Some(quad) => quad,
+            None => return,
+        };
+
+        graphics::set_texture(ctx, &self.texture);
+        graphics::push_quad(
+            ctx, quad.x1, quad.y1, quad.x2, quad.y2, quad.u1, quad.v1, quad.u2, quad.v2, &params,
+        );
+    }
+}
+
+/// The position and texture co-ordinates of a region, once it has been untrimmed and clipped.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RegionQuad {
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    u1: f32,
+    v1: f32,
+    u2: f32,
+    v2: f32,
+}
+
+fn region_quad(
+    bounds: Rectangle,
+    offset: Vec2<f32>,
+    original_size: Vec2<f32>,
+    clip: Option<Rectangle>,
+    texture_size: Vec2<f32>,
+) -> Option<RegionQuad> {
+    let clip = clip.unwrap_or_else(|| Rectangle::new(0.0, 0.0, original_size.x, original_size.y));
+    let trimmed = Rectangle::new(offset.x, offset.y, bounds.width, bounds.height);
+
+    let visible = clip.intersection(&trimmed)?;
+
+    let x1 = visible.x - clip.x;
+    let y1 = visible.y - clip.y;
+
+    let u = bounds.x + visible.x - offset.x;
+    let v = bounds.y + visible.y - offset.y;
+
+    Some(RegionQuad {
+        x1,
+        y1,
+        x2: x1 + visible.width,
+        y2: y1 + visible.height,
+        u1: u / texture_size.x,
+        v1: v / texture_size.y,
+        u2: (u + visible.width) / texture_size.x,
+        v2: (v + visible.height) / texture_size.y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTURE_SIZE: Vec2<f32> = Vec2::new(100.0, 100.0);
+
+    #[test]
+    fn untrimmed_region() {
+        let bounds = Rectangle::new(10.0, 20.0, 4.0, 6.0);
+
+        let quad = region_quad(
+            bounds,
+            Vec2::zero(),
+            Vec2::new(4.0, 6.0),
+            None,
+            TEXTURE_SIZE,
+        );
+
+        assert_eq!(
+            quad,
+            Some(RegionQuad {
+                x1: 0.0,
+                y1: 0.0,
+                x2: 4.0,
+                y2: 6.0,
+                u1: 10.0 / 100.0,
+                v1: 20.0 / 100.0,
+                u2: 14.0 / 100.0,
+                v2: 26.0 / 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn trimmed_region_is_offset() {
+        let bounds = Rectangle::new(10.0, 20.0, 4.0, 6.0);
+
+        let quad = region_quad(
+            bounds,
+            Vec2::new(2.0, 3.0),
+            Vec2::new(8.0, 10.0),
+            None,
+            TEXTURE_SIZE,
+        );
+
+        assert_eq!(
+            quad,
+            Some(RegionQuad {
+                x1: 2.0,
+                y1: 3.0,
+                x2: 6.0,
+                y2: 9.0,
+                u1: 10.0 / 100.0,
+                v1: 20.0 / 100.0,
+                u2: 14.0 / 100.0,
+                v2: 26.0 / 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn trimmed_region_is_clipped() {
+        let bounds = Rectangle::new(10.0, 20.0, 4.0, 6.0);
+        let offset = Vec2::new(2.0, 3.0);
+        let original_size = Vec2::new(8.0, 10.0);
+
+        // The clip is relative to the original image, so the trimmed image starts
+        // partway through it.
+        let quad = region_quad(
+            bounds,
+            offset,
+            original_size,
+            Some(Rectangle::new(3.0, 0.0, 8.0, 10.0)),
+            TEXTURE_SIZE,
+        );
+
+        assert_eq!(
+            quad,
+            Some(RegionQuad {
+                x1: 0.0,
+                y1: 3.0,
+                x2: 3.0,
+                y2: 9.0,
+                u1: 11.0 / 100.0,
+                v1: 20.0 / 100.0,
+                u2: 14.0 / 100.0,
+                v2: 26.0 / 100.0,
+            })
+        );
+
+        // Clipping to the trimmed-away space leaves nothing to draw.
+        let quad = region_quad(
+            bounds,
+            offset,
+            original_size,
+            Some(Rectangle::new(0.0, 0.0, 2.0, 10.0)),
+            TEXTURE_SIZE,
+        );
+
+        assert_eq!(quad, None);
+    }
+}
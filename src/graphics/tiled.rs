@@ -0,0 +1,1255 @@
+//! Functions and types relating to maps created with [Tiled](https://www.mapeditor.org/).
+//!
+//! This module requires the `tiled` feature to be enabled.
+
+mod json;
+mod xml;
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use hashbrown::HashMap;
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+use crate::graphics::tilemap::{self, Tile, TileFrame, TileMap, Tileset};
+use crate::graphics::{Color, Rectangle, Texture};
+use crate::math::Vec2;
+use crate::Context;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+
+const FLAG_MASK: u32 =
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL;
+
+/// The custom properties attached to a map, layer, tile or object, keyed by name.
+pub type Properties = BTreeMap<String, PropertyValue>;
+
+/// The value of a custom property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    /// A boolean property.
+    Bool(bool),
+
+    /// An integer property.
+    Int(i64),
+
+    /// A floating point property.
+    Float(f64),
+
+    /// A string property.
+    String(String),
+
+    /// A color property.
+    Color(Color),
+
+    /// A file property. The path is stored exactly as it appears in the map file, so it
+    /// will usually be relative to the map.
+    File(String),
+
+    /// A reference to an object, by ID. An ID of `0` means that no object is set.
+    Object(u32),
+
+    /// A property with a custom class, containing its own set of properties.
+    Class(Properties),
+}
+
+/// A map loaded from a [Tiled](https://www.mapeditor.org/) file.
+///
+/// Both the JSON (`.tmj`) and XML (`.tmx`) formats are supported, along with external
+/// tilesets in either format (`.tsj`/`.tsx`). Tile data can be stored as CSV, or as
+/// Base64 (optionally compressed with zlib or gzip).
+///
+/// All of the map's data is exposed via public fields, with textures already loaded,
+/// so it can be drawn however you like. The texture and source rectangle for a tile
+/// can be found via [`tile_source`](Self::tile_source), and used with
+/// [`DrawParams::clip`](super::DrawParams::clip). Alternatively, the map's tile layers can
+/// be converted into a [`TileMap`] via [`to_tile_map`](Self::to_tile_map).
+///
+/// Only orthogonal, finite maps are supported. Tilesets must be based on a single
+/// image - 'collection of images' tilesets are not supported.
+///
+/// This type requires the `tiled` feature to be enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::graphics::{DrawParams, Drawable};
+/// # use tetra::graphics::tiled::{TiledLayerData, TiledMap};
+/// # use tetra::math::Vec2;
+/// # fn run(ctx: &mut Context) -> tetra::Result {
+/// let map = TiledMap::new(ctx, "./level.tmx")?;
+///
+/// for layer in &map.layers {
+///     if let TiledLayerData::Objects(objects) = &layer.data {
+///         for object in objects {
+///             let tile = match object.tile {
+///                 Some(tile) => tile,
+///                 None => continue,
+///             };
+///
+///             if let Some((texture, source)) = map.tile_source(tile.gid) {
+///                 texture.draw(
+///                     ctx,
+///                     DrawParams::new()
+///                         .position(Vec2::new(object.x, object.y - source.height))
+///                         .clip(source),
+///                 );
+///             }
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledMap {
+    /// The width of the map, in tiles.
+    pub width: i32,
+
+    /// The height of the map, in tiles.
+    pub height: i32,
+
+    /// The width of the map's grid cells, in pixels.
+    pub tile_width: i32,
+
+    /// The height of the map's grid cells, in pixels.
+    pub tile_height: i32,
+
+    /// The background color of the map, if one was set.
+    pub background_color: Option<Color>,
+
+    /// The custom properties of the map.
+    pub properties: Properties,
+
+    /// The tilesets used by the map, ordered by their first global tile ID.
+    pub tilesets: Vec<TiledTileset>,
+
+    /// The layers of the map, from bottom to top.
+    ///
+    /// Group layers are flattened into this list - the offset, opacity and visibility
+    /// of each group are combined into those of its children.
+    pub layers: Vec<TiledLayer>,
+}
+
+impl TiledMap {
+    /// Loads a map from the given file.
+    ///
+    /// The format will be determined based on the file extension (`.tmj` or `.json` for
+    /// JSON, `.tmx` or `.xml` for XML). If the extension is not recognized, the format
+    /// will be guessed from the contents of the file.
+    ///
+    /// External tilesets and images will be loaded relative to the file that refers to them.
+    /// If the same image is used more than once, it will only be loaded once.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the map, or any of the
+    ///   tilesets or images it refers to, could not be loaded.
+    /// * [`TetraError::InvalidMap`] will be returned if the map or tileset data is invalid,
+    ///   or uses features that are not supported.
+    /// * [`TetraError::InvalidTexture`] will be returned if any of the image data is invalid.
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn new<P>(ctx: &mut Context, path: P) -> Result<TiledMap>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        let data = match Format::detect(path, &text) {
+            Format::Json => json::parse_map(&text),
+            Format::Xml => xml::parse_map(&text),
+        }
+        .map_err(|e| invalid_map(path, e))?;
+
+        let directory = parent_directory(path);
+        let mut loader = Loader {
+            ctx,
+            textures: HashMap::new(),
+        };
+
+        let mut tilesets = Vec::with_capacity(data.tilesets.len());
+
+        for tileset in data.tilesets {
+            let tileset = match tileset {
+                TilesetSource::Embedded { first_gid, data } => {
+                    loader.load_tileset(first_gid, data, directory, path)?
+                }
+
+                TilesetSource::External { first_gid, source } => {
+                    let tileset_path = directory.join(source);
+                    let text = fs::read_to_string(&tileset_path)?;
+
+                    let data = match Format::detect(&tileset_path, &text) {
+                        Format::Json => json::parse_tileset(&text),
+                        Format::Xml => xml::parse_tileset(&text),
+                    }
+                    .map_err(|e| invalid_map(&tileset_path, e))?;
+
+                    loader.load_tileset(
+                        first_gid,
+                        data,
+                        parent_directory(&tileset_path),
+                        &tileset_path,
+                    )?
+                }
+            };
+
+            tilesets.push(tileset);
+        }
+
+        tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+        let mut layers = Vec::with_capacity(data.layers.len());
+
+        for layer in data.layers {
+            loader.load_layer(layer, &LayerParent::root(), directory, path, &mut layers)?;
+        }
+
+        Ok(TiledMap {
+            width: data.width,
+            height: data.height,
+            tile_width: data.tile_width,
+            tile_height: data.tile_height,
+            background_color: data.background_color,
+            properties: data.properties,
+            tilesets,
+            layers,
+        })
+    }
+
+    /// Returns the tileset that contains the tile with the specified global ID, or
+    /// `None` if no tileset contains it.
+    pub fn tileset_for_gid(&self, gid: u32) -> Option<&TiledTileset> {
+        self.tilesets
+            .iter()
+            .rev()
+            .find(|tileset| tileset.first_gid <= gid)
+            .filter(|tileset| tileset.contains(gid))
+    }
+
+    /// Returns the texture and source rectangle of the tile with the specified global ID,
+    /// or `None` if no tileset contains it.
+    ///
+    /// The rectangle can be passed to [`DrawParams::clip`](super::DrawParams::clip) in
+    /// order to draw the tile.
+    pub fn tile_source(&self, gid: u32) -> Option<(&Texture, Rectangle)> {
+        let tileset = self.tileset_for_gid(gid)?;
+        let bounds = tileset.tile_bounds(gid - tileset.first_gid)?;
+
+        Some((&tileset.texture, bounds))
+    }
+
+    /// Returns the first layer with the specified name, or `None` if there is no layer
+    /// with that name.
+    pub fn layer(&self, name: &str) -> Option<&TiledLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Converts the map's tile layers into a [`TileMap`].
+    ///
+    /// As each [`TileLayer`](super::tilemap::TileLayer) can only use a single tileset, a
+    /// Tiled layer that uses multiple tilesets will be split into one layer per tileset.
+    /// Tile flipping, tile animations and layer visibility will be carried across, but
+    /// object and image layers will be skipped.
+    ///
+    /// [`TileLayer`](super::tilemap::TileLayer)s do not have an offset or opacity, so
+    /// the [`offset`](TiledLayer::offset) and [`opacity`](TiledLayer::opacity) of each
+    /// Tiled layer will not be carried across. If your map uses them, you can draw each
+    /// layer individually and apply them via [`DrawParams`](super::DrawParams).
+    ///
+    /// Tiles that do not belong to any of the map's tilesets, or that lie outside of the
+    /// bounds of the map, will be left empty.
+    pub fn to_tile_map(&self) -> TileMap {
+        let mut tile_map = TileMap::new(
+            self.width,
+            self.height,
+            self.tile_width as f32,
+            self.tile_height as f32,
+        );
+
+        let tilesets: Vec<Tileset> = self.tilesets.iter().map(|t| t.to_tileset()).collect();
+
+        for layer in &self.layers {
+            let tiles = match &layer.data {
+                TiledLayerData::Tiles(tiles) => tiles,
+                _ => continue,
+            };
+
+            for (index, tiled_tileset) in self.tilesets.iter().enumerate() {
+                // Layers are only created for the tilesets that are actually used.
+                let used = tiles
+                    .tiles
+                    .iter()
+                    .flatten()
+                    .any(|tile| tiled_tileset.contains(tile.gid));
+
+                if !used {
+                    continue;
+                }
+
+                let output = tile_map.add_layer(tilesets[index].clone());
+                output.set_visible(layer.visible);
+
+                for (i, tile) in tiles.tiles.iter().enumerate() {
+                    let tile = match tile {
+                        Some(tile) if tiled_tileset.contains(tile.gid) => tile,
+                        _ => continue,
+                    };
+
+                    let x = i as i32 % tiles.width;
+                    let y = i as i32 / tiles.width;
+
+                    // Tiled always makes layers the same size as the map, but a
+                    // hand-edited file might not.
+                    if x >= self.width || y >= self.height {
+                        continue;
+                    }
+
+                    output.set_tile(
+                        x,
+                        y,
+                        Tile {
+                            id: tile.gid - tiled_tileset.first_gid,
+                            flip_x: tile.flip_x,
+                            flip_y: tile.flip_y,
+                            flip_diagonal: tile.flip_diagonal,
+                        },
+                    );
+                }
+            }
+        }
+
+        tile_map
+    }
+}
+
+/// A tileset used by a [`TiledMap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileset {
+    /// The global ID of the tileset's first tile. Each tile in the tileset has a global ID
+    /// of `first_gid + id`.
+    pub first_gid: u32,
+
+    /// The name of the tileset.
+    pub name: String,
+
+    /// The width of the tiles, in pixels.
+    pub tile_width: i32,
+
+    /// The height of the tiles, in pixels.
+    pub tile_height: i32,
+
+    /// The number of pixels between the edge of the texture and the tiles.
+    pub margin: i32,
+
+    /// The number of pixels between each tile.
+    pub spacing: i32,
+
+    /// The number of columns of tiles in the texture.
+    pub columns: i32,
+
+    /// The number of tiles in the tileset.
+    pub tile_count: u32,
+
+    /// The tileset's texture.
+    pub texture: Texture,
+
+    /// The custom properties of the tileset.
+    pub properties: Properties,
+
+    /// The custom properties of individual tiles, keyed by their (local) ID.
+    ///
+    /// Tiles without any properties will not have an entry.
+    pub tile_properties: BTreeMap<u32, Properties>,
+
+    /// The animations of individual tiles, keyed by their (local) ID.
+    pub animations: BTreeMap<u32, Vec<TileFrame>>,
+}
+
+impl TiledTileset {
+    /// Returns the source rectangle of the tile with the specified local ID, or `None`
+    /// if the tileset does not contain the tile.
+    pub fn tile_bounds(&self, id: u32) -> Option<Rectangle> {
+        if id >= self.tile_count || self.columns <= 0 {
+            return None;
+        }
+
+        Some(tilemap::tile_bounds(
+            id,
+            self.columns,
+            self.tile_width,
+            self.tile_height,
+            self.margin,
+            self.spacing,
+        ))
+    }
+
+    /// Returns whether the tileset contains the tile with the specified global ID.
+    pub fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid - self.first_gid < self.tile_count
+    }
+
+    /// Converts the tileset into a [`Tileset`], including its tile animations.
+    pub fn to_tileset(&self) -> Tileset {
+        let mut tileset = Tileset::with_spacing(
+            self.texture.clone(),
+            self.tile_width,
+            self.tile_height,
+            self.margin,
+            self.spacing,
+        );
+
+        for (id, frames) in &self.animations {
+            tileset.set_animation(*id, frames.clone());
+        }
+
+        tileset
+    }
+}
+
+/// A layer within a [`TiledMap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledLayer {
+    /// The name of the layer.
+    pub name: String,
+
+    /// Whether the layer is visible.
+    pub visible: bool,
+
+    /// The opacity of the layer, from `0.0` to `1.0`.
+    pub opacity: f32,
+
+    /// The offset of the layer, in pixels.
+    pub offset: Vec2<f32>,
+
+    /// The custom properties of the layer.
+    pub properties: Properties,
+
+    /// The contents of the layer.
+    pub data: TiledLayerData,
+}
+
+/// The contents of a [`TiledLayer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledLayerData {
+    /// A grid of tiles.
+    Tiles(TiledTileLayer),
+
+    /// A list of objects.
+    Objects(Vec<TiledObject>),
+
+    /// A single image, or `None` if the layer does not have an image set.
+    Image(Option<Texture>),
+}
+
+/// The tiles within a tile layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileLayer {
+    /// The width of the layer, in tiles.
+    pub width: i32,
+
+    /// The height of the layer, in tiles.
+    pub height: i32,
+
+    /// The tiles in the layer, stored row by row from the top left.
+    /// Empty cells are `None`.
+    pub tiles: Vec<Option<TiledTile>>,
+}
+
+impl TiledTileLayer {
+    /// Returns the tile at the specified position, or `None` if the cell is empty or
+    /// outside the bounds of the layer.
+    pub fn get(&self, x: i32, y: i32) -> Option<TiledTile> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.tiles[(y * self.width + x) as usize]
+    }
+}
+
+/// A reference to a tile, as stored in a layer or object.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TiledTile {
+    /// The global ID of the tile, with the flip flags removed.
+    pub gid: u32,
+
+    /// Whether the tile is flipped horizontally.
+    pub flip_x: bool,
+
+    /// Whether the tile is flipped vertically.
+    pub flip_y: bool,
+
+    /// Whether the tile is flipped diagonally. This is applied before the horizontal and
+    /// vertical flips.
+    pub flip_diagonal: bool,
+}
+
+impl TiledTile {
+    /// Decodes a tile from its raw value, which may include flip flags. Returns `None` if
+    /// the value refers to an empty cell.
+    fn from_raw(raw: u32) -> Option<TiledTile> {
+        let gid = raw & !FLAG_MASK;
+
+        if gid == 0 {
+            return None;
+        }
+
+        Some(TiledTile {
+            gid,
+            flip_x: raw & FLIPPED_HORIZONTALLY != 0,
+            flip_y: raw & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: raw & FLIPPED_DIAGONALLY != 0,
+        })
+    }
+}
+
+/// An object within an object layer.
+///
+/// The position of the object is stored exactly as it is in Tiled - note that this means
+/// that the origin of tile objects is at their bottom left, rather than their top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObject {
+    /// The unique ID of the object.
+    pub id: u32,
+
+    /// The name of the object.
+    pub name: String,
+
+    /// The class (formerly 'type') of the object.
+    pub class: String,
+
+    /// The X position of the object, in pixels.
+    pub x: f32,
+
+    /// The Y position of the object, in pixels.
+    pub y: f32,
+
+    /// The width of the object, in pixels.
+    pub width: f32,
+
+    /// The height of the object, in pixels.
+    pub height: f32,
+
+    /// The rotation of the object, in degrees clockwise.
+    pub rotation: f32,
+
+    /// Whether the object is visible.
+    pub visible: bool,
+
+    /// The tile that the object displays, if it is a tile object.
+    pub tile: Option<TiledTile>,
+
+    /// The shape of the object.
+    pub shape: ObjectShape,
+
+    /// The custom properties of the object.
+    pub properties: Properties,
+}
+
+/// The shape of a [`TiledObject`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    /// A rectangle, filling the object's bounds. This is also used for tile objects.
+    Rectangle,
+
+    /// An ellipse, filling the object's bounds.
+    Ellipse,
+
+    /// A single point.
+    Point,
+
+    /// A closed polygon, with points relative to the object's position.
+    Polygon(Vec<Vec2<f32>>),
+
+    /// An open line, with points relative to the object's position.
+    Polyline(Vec<Vec2<f32>>),
+
+    /// A block of text, filling the object's bounds.
+    Text(String),
+}
+
+// The types below are the intermediate representation produced by the JSON and XML
+// parsers. They mirror the files closely, and have not had any assets loaded yet.
+
+#[derive(Debug, PartialEq)]
+struct MapData {
+    width: i32,
+    height: i32,
+    tile_width: i32,
+    tile_height: i32,
+    background_color: Option<Color>,
+    properties: Properties,
+    tilesets: Vec<TilesetSource>,
+    layers: Vec<LayerData>,
+}
+
+#[derive(Debug, PartialEq)]
+enum TilesetSource {
+    Embedded { first_gid: u32, data: TilesetData },
+    External { first_gid: u32, source: String },
+}
+
+#[derive(Debug, PartialEq)]
+struct TilesetData {
+    name: String,
+    tile_width: i32,
+    tile_height: i32,
+    margin: i32,
+    spacing: i32,
+    columns: i32,
+    tile_count: u32,
+    image: Option<String>,
+    properties: Properties,
+    tiles: Vec<TileData>,
+}
+
+#[derive(Debug, PartialEq)]
+struct TileData {
+    id: u32,
+    properties: Properties,
+    animation: Vec<TileFrame>,
+}
+
+#[derive(Debug, PartialEq)]
+struct LayerData {
+    name: String,
+    visible: bool,
+    opacity: f32,
+    offset: Vec2<f32>,
+    properties: Properties,
+    kind: LayerKind,
+}
+
+#[derive(Debug, PartialEq)]
+enum LayerKind {
+    Tiles {
+        width: i32,
+        height: i32,
+        tiles: Vec<u32>,
+    },
+    Objects(Vec<TiledObject>),
+    Image(Option<String>),
+    Group(Vec<LayerData>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Json,
+    Xml,
+}
+
+impl Format {
+    fn detect(path: &Path, text: &str) -> Format {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("tmj") | Some("tsj") | Some("json") => Format::Json,
+            Some("tmx") | Some("tsx") | Some("xml") => Format::Xml,
+            _ if text.trim_start().starts_with('<') => Format::Xml,
+            _ => Format::Json,
+        }
+    }
+}
+
+/// The combined state of the groups that a layer is nested within.
+struct LayerParent {
+    visible: bool,
+    opacity: f32,
+    offset: Vec2<f32>,
+}
+
+impl LayerParent {
+    fn root() -> LayerParent {
+        LayerParent {
+            visible: true,
+            opacity: 1.0,
+            offset: Vec2::zero(),
+        }
+    }
+}
+
+struct Loader<'a> {
+    ctx: &'a mut Context,
+    textures: HashMap<PathBuf, Texture>,
+}
+
+impl Loader<'_> {
+    fn load_texture(&mut self, path: PathBuf) -> Result<Texture> {
+        if let Some(texture) = self.textures.get(&path) {
+            return Ok(texture.clone());
+        }
+
+        let texture = Texture::new(self.ctx, &path)?;
+        self.textures.insert(path, texture.clone());
+
+        Ok(texture)
+    }
+
+    fn load_tileset(
+        &mut self,
+        first_gid: u32,
+        data: TilesetData,
+        directory: &Path,
+        path: &Path,
+    ) -> Result<TiledTileset> {
+        let image = match &data.image {
+            Some(image) => directory.join(image),
+            None => {
+                return Err(invalid_map(
+                    path,
+                    format!(
+                        "tileset '{}' is a collection of images, which is not supported",
+                        data.name
+                    ),
+                ))
+            }
+        };
+
+        if data.tile_width <= 0 || data.tile_height <= 0 {
+            return Err(invalid_map(
+                path,
+                format!("tileset '{}' has an invalid tile size", data.name),
+            ));
+        }
+
+        let texture = self.load_texture(image)?;
+
+        let mut tile_properties = BTreeMap::new();
+        let mut animations = BTreeMap::new();
+
+        for tile in data.tiles {
+            if !tile.properties.is_empty() {
+                tile_properties.insert(tile.id, tile.properties);
+            }
+
+            if !tile.animation.is_empty() {
+                animations.insert(tile.id, tile.animation);
+            }
+        }
+
+        Ok(TiledTileset {
+            first_gid,
+            name: data.name,
+            tile_width: data.tile_width,
+            tile_height: data.tile_height,
+            margin: data.margin.max(0),
+            spacing: data.spacing.max(0),
+            columns: data.columns,
+            tile_count: data.tile_count,
+            texture,
+            properties: data.properties,
+            tile_properties,
+            animations,
+        })
+    }
+
+    fn load_layer(
+        &mut self,
+        layer: LayerData,
+        parent: &LayerParent,
+        directory: &Path,
+        path: &Path,
+        output: &mut Vec<TiledLayer>,
+    ) -> Result {
+        let visible = parent.visible && layer.visible;
+        let opacity = parent.opacity * layer.opacity;
+        let offset = parent.offset + layer.offset;
+
+        let data = match layer.kind {
+            LayerKind::Tiles {
+                width,
+                height,
+                tiles,
+            } => {
+                if width < 0 || height < 0 || tiles.len() != (width * height) as usize {
+                    return Err(invalid_map(
+                        path,
+                        format!(
+                            "layer '{}' should contain {} tiles, but it contains {}",
+                            layer.name,
+                            width.max(0) * height.max(0),
+                            tiles.len()
+                        ),
+                    ));
+                }
+
+                TiledLayerData::Tiles(TiledTileLayer {
+                    width,
+                    height,
+                    tiles: tiles.into_iter().map(TiledTile::from_raw).collect(),
+                })
+            }
+
+            LayerKind::Objects(objects) => TiledLayerData::Objects(objects),
+
+            LayerKind::Image(image) => TiledLayerData::Image(match image {
+                Some(image) => Some(self.load_texture(directory.join(image))?),
+                None => None,
+            }),
+
+            LayerKind::Group(children) => {
+                let parent = LayerParent {
+                    visible,
+                    opacity,
+                    offset,
+                };
+
+                for child in children {
+                    self.load_layer(child, &parent, directory, path, output)?;
+                }
+
+                return Ok(());
+            }
+        };
+
+        output.push(TiledLayer {
+            name: layer.name,
+            visible,
+            opacity,
+            offset,
+            properties: layer.properties,
+            data,
+        });
+
+        Ok(())
+    }
+}
+
+fn invalid_map(path: &Path, reason: String) -> TetraError {
+    TetraError::InvalidMap(format!("could not parse {}: {}", path.display(), reason))
+}
+
+fn parent_directory(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new(""))
+}
+
+/// Decodes a layer's tile data from its CSV or Base64 representation.
+fn decode_tiles(
+    data: &str,
+    encoding: Option<&str>,
+    compression: Option<&str>,
+) -> std::result::Result<Vec<u32>, String> {
+    match encoding {
+        Some("csv") => data
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid tile ID '{}'", value))
+            })
+            .collect(),
+
+        Some("base64") => {
+            let bytes = base64::decode(data.trim())
+                .map_err(|e| format!("invalid Base64 tile data: {}", e))?;
+
+            let bytes = match compression {
+                None | Some("") => bytes,
+                Some("zlib") => decompress(ZlibDecoder::new(&bytes[..]))?,
+                Some("gzip") => decompress(GzDecoder::new(&bytes[..]))?,
+                Some(other) => {
+                    return Err(format!(
+                        "'{}' compression is not supported (use zlib or gzip instead)",
+                        other
+                    ))
+                }
+            };
+
+            if bytes.len() % 4 != 0 {
+                return Err("tile data was not a whole number of tiles".into());
+            }
+
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect())
+        }
+
+        Some(other) => Err(format!("'{}' encoding is not supported", other)),
+
+        None => Err("tile data has no encoding".into()),
+    }
+}
+
+fn decompress<R>(mut reader: R) -> std::result::Result<Vec<u8>, String>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("could not decompress tile data: {}", e))?;
+
+    Ok(bytes)
+}
+
+/// Parses a color in Tiled's `#AARRGGBB` or `#RRGGBB` format.
+///
+/// Tiled writes unset color properties as an empty string - these are treated as
+/// transparent black.
+fn parse_color(value: &str) -> std::result::Result<Color, String> {
+    let hex = value.trim_start_matches('#');
+
+    let color = match hex.len() {
+        0 => Ok(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+        6 => Color::try_hex(hex),
+        8 => Color::try_hex(&format!("{}{}", &hex[2..], &hex[..2])),
+        _ => return Err(format!("invalid color '{}'", value)),
+    };
+
+    color.map_err(|_| format!("invalid color '{}'", value))
+}
+
+/// Converts a property from its type name and string representation.
+///
+/// Both formats store some property types as strings, so this is shared between them.
+fn parse_property(kind: &str, value: &str) -> std::result::Result<PropertyValue, String> {
+    let invalid = || format!("invalid {} property value '{}'", kind, value);
+
+    match kind {
+        "string" => Ok(PropertyValue::String(value.to_owned())),
+        "file" => Ok(PropertyValue::File(value.to_owned())),
+        "color" => parse_color(value).map(PropertyValue::Color),
+        "bool" => value
+            .parse()
+            .map(PropertyValue::Bool)
+            .map_err(|_| invalid()),
+        "int" => value.parse().map(PropertyValue::Int).map_err(|_| invalid()),
+        "float" => value
+            .parse()
+            .map(PropertyValue::Float)
+            .map_err(|_| invalid()),
+        "object" => value
+            .parse()
+            .map(PropertyValue::Object)
+            .map_err(|_| invalid()),
+        _ => Err(format!("unknown property type '{}'", kind)),
+    }
+}
+
+fn tile_frame(tile: u32, duration: u64) -> TileFrame {
+    TileFrame::new(tile, Duration::from_millis(duration))
+}
+
+fn check_map_support(orientation: &str, infinite: bool) -> std::result::Result<(), String> {
+    if orientation != "orthogonal" {
+        return Err(format!(
+            "{} maps are not supported (only orthogonal maps are)",
+            orientation
+        ));
+    }
+
+    if infinite {
+        return Err("infinite maps are not supported".into());
+    }
+
+    Ok(())
+}
+
+fn check_map_size(width: i32, height: i32) -> std::result::Result<(), String> {
+    if width < 0 || height < 0 {
+        return Err(format!("map has an invalid size ({}x{})", width, height));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    const JSON_MAP: &str = r##"{
+        "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "orientation": "orthogonal", "infinite": false,
+        "backgroundcolor": "#80ff0000",
+        "properties": [
+            { "name": "music", "type": "file", "value": "song.ogg" },
+            { "name": "difficulty", "type": "int", "value": 3 }
+        ],
+        "tilesets": [
+            {
+                "firstgid": 1, "name": "tiles", "tilewidth": 16, "tileheight": 16,
+                "margin": 0, "spacing": 0, "columns": 4, "tilecount": 16,
+                "image": "tiles.png",
+                "tiles": [
+                    { "id": 2, "animation": [
+                        { "tileid": 2, "duration": 100 },
+                        { "tileid": 3, "duration": 200 }
+                    ] }
+                ]
+            },
+            { "firstgid": 17, "source": "objects.tsj" }
+        ],
+        "layers": [
+            {
+                "type": "group", "name": "terrain", "offsetx": 4, "offsety": 0,
+                "opacity": 0.5, "visible": true,
+                "layers": [
+                    {
+                        "type": "tilelayer", "name": "ground", "width": 2, "height": 2,
+                        "opacity": 1, "visible": true, "data": [1, 0, 2147483651, 17]
+                    }
+                ]
+            },
+            {
+                "type": "objectgroup", "name": "entities", "opacity": 1, "visible": true,
+                "objects": [
+                    {
+                        "id": 1, "name": "spawn", "type": "player", "x": 8, "y": 24,
+                        "width": 0, "height": 0, "rotation": 0, "visible": true,
+                        "point": true
+                    },
+                    {
+                        "id": 2, "name": "", "type": "", "x": 0, "y": 0,
+                        "width": 0, "height": 0, "rotation": 0, "visible": true,
+                        "polygon": [{ "x": 0, "y": 0 }, { "x": 16, "y": 0 }, { "x": 0, "y": 16 }]
+                    }
+                ]
+            },
+            {
+                "type": "imagelayer", "name": "sky", "opacity": 1, "visible": false,
+                "image": "sky.png"
+            }
+        ]
+    }"##;
+
+    const XML_MAP: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+        <map version="1.9" orientation="orthogonal" renderorder="right-down" width="2"
+             height="2" tilewidth="16" tileheight="16" infinite="0" backgroundcolor="#80ff0000">
+            <properties>
+                <property name="music" type="file" value="song.ogg"/>
+                <property name="difficulty" type="int" value="3"/>
+            </properties>
+            <tileset firstgid="1" name="tiles" tilewidth="16" tileheight="16" tilecount="16" columns="4">
+                <image source="tiles.png" width="64" height="64"/>
+                <tile id="2">
+                    <animation>
+                        <frame tileid="2" duration="100"/>
+                        <frame tileid="3" duration="200"/>
+                    </animation>
+                </tile>
+            </tileset>
+            <tileset firstgid="17" source="objects.tsj"/>
+            <group name="terrain" offsetx="4" opacity="0.5">
+                <layer name="ground" width="2" height="2">
+                    <data encoding="csv">
+                        1,0,
+                        2147483651,17
+                    </data>
+                </layer>
+            </group>
+            <objectgroup name="entities">
+                <object id="1" name="spawn" type="player" x="8" y="24">
+                    <point/>
+                </object>
+                <object id="2" x="0" y="0">
+                    <polygon points="0,0 16,0 0,16"/>
+                </object>
+            </objectgroup>
+            <imagelayer name="sky" visible="0">
+                <image source="sky.png"/>
+            </imagelayer>
+        </map>"##;
+
+    fn expected_map() -> MapData {
+        let mut properties = Properties::new();
+        properties.insert("music".into(), PropertyValue::File("song.ogg".into()));
+        properties.insert("difficulty".into(), PropertyValue::Int(3));
+
+        let object = |id, name: &str, class: &str, x, y, shape| TiledObject {
+            id,
+            name: name.into(),
+            class: class.into(),
+            x,
+            y,
+            width: 0.0,
+            height: 0.0,
+            rotation: 0.0,
+            visible: true,
+            tile: None,
+            shape,
+            properties: Properties::new(),
+        };
+
+        let layer = |name: &str, visible, opacity, offset, kind| LayerData {
+            name: name.into(),
+            visible,
+            opacity,
+            offset,
+            properties: Properties::new(),
+            kind,
+        };
+
+        MapData {
+            width: 2,
+            height: 2,
+            tile_width: 16,
+            tile_height: 16,
+            background_color: Some(Color::rgba8(255, 0, 0, 128)),
+            properties,
+            tilesets: vec![
+                TilesetSource::Embedded {
+                    first_gid: 1,
+                    data: TilesetData {
+                        name: "tiles".into(),
+                        tile_width: 16,
+                        tile_height: 16,
+                        margin: 0,
+                        spacing: 0,
+                        columns: 4,
+                        tile_count: 16,
+                        image: Some("tiles.png".into()),
+                        properties: Properties::new(),
+                        tiles: vec![TileData {
+                            id: 2,
+                            properties: Properties::new(),
+                            animation: vec![tile_frame(2, 100), tile_frame(3, 200)],
+                        }],
+                    },
+                },
+                TilesetSource::External {
+                    first_gid: 17,
+                    source: "objects.tsj".into(),
+                },
+            ],
+            layers: vec![
+                layer(
+                    "terrain",
+                    true,
+                    0.5,
+                    Vec2::new(4.0, 0.0),
+                    LayerKind::Group(vec![layer(
+                        "ground",
+                        true,
+                        1.0,
+                        Vec2::zero(),
+                        LayerKind::Tiles {
+                            width: 2,
+                            height: 2,
+                            tiles: vec![1, 0, 2147483651, 17],
+                        },
+                    )]),
+                ),
+                layer(
+                    "entities",
+                    true,
+                    1.0,
+                    Vec2::zero(),
+                    LayerKind::Objects(vec![
+                        object(1, "spawn", "player", 8.0, 24.0, ObjectShape::Point),
+                        object(
+                            2,
+                            "",
+                            "",
+                            0.0,
+                            0.0,
+                            ObjectShape::Polygon(vec![
+                                Vec2::new(0.0, 0.0),
+                                Vec2::new(16.0, 0.0),
+                                Vec2::new(0.0, 16.0),
+                            ]),
+                        ),
+                    ]),
+                ),
+                layer(
+                    "sky",
+                    false,
+                    1.0,
+                    Vec2::zero(),
+                    LayerKind::Image(Some("sky.png".into())),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn parse_json_map() {
+        assert_eq!(json::parse_map(JSON_MAP), Ok(expected_map()));
+    }
+
+    #[test]
+    fn parse_xml_map() {
+        assert_eq!(xml::parse_map(XML_MAP), Ok(expected_map()));
+    }
+
+    #[test]
+    fn unsupported_maps() {
+        let isometric = XML_MAP.replace("orthogonal", "isometric");
+        let infinite = JSON_MAP.replace("\"infinite\": false", "\"infinite\": true");
+
+        assert!(xml::parse_map(&isometric).is_err());
+        assert!(json::parse_map(&infinite).is_err());
+    }
+
+    #[test]
+    fn negative_map_size() {
+        let xml = XML_MAP.replacen("width=\"2\"", "width=\"-2\"", 1);
+        let json = JSON_MAP.replacen("\"height\": 2", "\"height\": -2", 1);
+
+        assert!(xml::parse_map(&xml).is_err());
+        assert!(json::parse_map(&json).is_err());
+    }
+
+    #[test]
+    fn decode_tile_data() {
+        let tiles = [1u32, 0, 0x8000_0003, 17];
+        let bytes: Vec<u8> = tiles
+            .iter()
+            .flat_map(|t| t.to_le_bytes().to_vec())
+            .collect();
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            decode_tiles(" 1,0,\n2147483651,17\n", Some("csv"), None),
+            Ok(tiles.to_vec())
+        );
+
+        assert_eq!(
+            decode_tiles(&base64::encode(&bytes), Some("base64"), None),
+            Ok(tiles.to_vec())
+        );
+
+        assert_eq!(
+            decode_tiles(&base64::encode(&compressed), Some("base64"), Some("zlib")),
+            Ok(tiles.to_vec())
+        );
+
+        assert!(decode_tiles(&base64::encode(&bytes), Some("base64"), Some("zstd")).is_err());
+    }
+
+    #[test]
+    fn tile_flags() {
+        assert_eq!(TiledTile::from_raw(0), None);
+        assert_eq!(TiledTile::from_raw(FLIPPED_VERTICALLY), None);
+
+        let tile = TiledTile::from_raw(0xA000_0005).unwrap();
+
+        assert_eq!(tile.gid, 5);
+        assert!(tile.flip_x && !tile.flip_y && tile.flip_diagonal);
+        assert_eq!(TiledTile::from_raw(0x1000_0005).unwrap().gid, 5);
+    }
+}
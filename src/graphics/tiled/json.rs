@@ -0,0 +1,457 @@
+//! Parsing for Tiled's JSON formats (`.tmj` and `.tsj`).
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    check_map_size, check_map_support, decode_tiles, parse_color, parse_property, tile_frame,
+    LayerData, LayerKind, MapData, ObjectShape, Properties, PropertyValue, TileData, TiledObject,
+    TiledTile, TilesetData, TilesetSource,
+};
+use crate::math::Vec2;
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+pub(super) fn parse_map(json: &str) -> ParseResult<MapData> {
+    let map: JsonMap = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    check_map_support(&map.orientation, map.infinite)?;
+    check_map_size(map.width, map.height)?;
+
+    let mut tilesets = Vec::with_capacity(map.tilesets.len());
+
+    for tileset in map.tilesets {
+        tilesets.push(match tileset.source {
+            Some(source) => TilesetSource::External {
+                first_gid: tileset.first_gid,
+                source,
+            },
+            None => TilesetSource::Embedded {
+                first_gid: tileset.first_gid,
+                data: convert_tileset(
+                    serde_json::from_value(tileset.rest).map_err(|e| e.to_string())?,
+                )?,
+            },
+        });
+    }
+
+    Ok(MapData {
+        width: map.width,
+        height: map.height,
+        tile_width: map.tile_width,
+        tile_height: map.tile_height,
+        background_color: map
+            .background_color
+            .as_deref()
+            .map(parse_color)
+            .transpose()?,
+        properties: convert_properties(map.properties)?,
+        tilesets,
+        layers: map
+            .layers
+            .into_iter()
+            .map(convert_layer)
+            .collect::<ParseResult<_>>()?,
+    })
+}
+
+pub(super) fn parse_tileset(json: &str) -> ParseResult<TilesetData> {
+    convert_tileset(serde_json::from_str(json).map_err(|e| e.to_string())?)
+}
+
+fn convert_tileset(tileset: JsonTileset) -> ParseResult<TilesetData> {
+    let mut tiles = Vec::with_capacity(tileset.tiles.len());
+
+    for tile in tileset.tiles {
+        tiles.push(TileData {
+            id: tile.id,
+            properties: convert_properties(tile.properties)?,
+            animation: tile
+                .animation
+                .into_iter()
+                .map(|frame| tile_frame(frame.tile_id, frame.duration))
+                .collect(),
+        });
+    }
+
+    Ok(TilesetData {
+        name: tileset.name,
+        tile_width: tileset.tile_width,
+        tile_height: tileset.tile_height,
+        margin: tileset.margin,
+        spacing: tileset.spacing,
+        columns: tileset.columns,
+        tile_count: tileset.tile_count,
+        image: tileset.image,
+        properties: convert_properties(tileset.properties)?,
+        tiles,
+    })
+}
+
+fn convert_layer(layer: JsonLayer) -> ParseResult<LayerData> {
+    let kind = match layer.kind.as_str() {
+        "tilelayer" => {
+            let tiles = match &layer.data {
+                Some(Value::Array(values)) => values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_u64()
+                            .map(|value| value as u32)
+                            .ok_or_else(|| format!("invalid tile ID '{}'", value))
+                    })
+                    .collect::<ParseResult<_>>()?,
+
+                Some(Value::String(data)) => decode_tiles(
+                    data,
+                    Some(layer.encoding.as_deref().unwrap_or("base64")),
+                    layer.compression.as_deref(),
+                )?,
+
+                _ => return Err(format!("layer '{}' has no tile data", layer.name)),
+            };
+
+            LayerKind::Tiles {
+                width: layer.width,
+                height: layer.height,
+                tiles,
+            }
+        }
+
+        "objectgroup" => LayerKind::Objects(
+            layer
+                .objects
+                .into_iter()
+                .map(convert_object)
+                .collect::<ParseResult<_>>()?,
+        ),
+
+        "imagelayer" => LayerKind::Image(layer.image.filter(|image| !image.is_empty())),
+
+        "group" => LayerKind::Group(
+            layer
+                .layers
+                .into_iter()
+                .map(convert_layer)
+                .collect::<ParseResult<_>>()?,
+        ),
+
+        other => return Err(format!("unknown layer type '{}'", other)),
+    };
+
+    Ok(LayerData {
+        name: layer.name,
+        visible: layer.visible,
+        opacity: layer.opacity,
+        offset: Vec2::new(layer.offset_x, layer.offset_y),
+        properties: convert_properties(layer.properties)?,
+        kind,
+    })
+}
+
+fn convert_object(object: JsonObject) -> ParseResult<TiledObject> {
+    let points = |points: Vec<JsonPoint>| points.into_iter().map(|p| Vec2::new(p.x, p.y)).collect();
+
+    let shape = if let Some(polygon) = object.polygon {
+        ObjectShape::Polygon(points(polygon))
+    } else if let Some(polyline) = object.polyline {
+        ObjectShape::Polyline(points(polyline))
+    } else if let Some(text) = object.text {
+        ObjectShape::Text(text.text)
+    } else if object.ellipse {
+        ObjectShape::Ellipse
+    } else if object.point {
+        ObjectShape::Point
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    Ok(TiledObject {
+        id: object.id,
+        name: object.name,
+        class: object
+            .class
+            .filter(|class| !class.is_empty())
+            .or(object.kind)
+            .unwrap_or_default(),
+        x: object.x,
+        y: object.y,
+        width: object.width,
+        height: object.height,
+        rotation: object.rotation,
+        visible: object.visible,
+        tile: object.gid.and_then(TiledTile::from_raw),
+        shape,
+        properties: convert_properties(object.properties)?,
+    })
+}
+
+fn convert_properties(properties: Vec<JsonProperty>) -> ParseResult<Properties> {
+    properties
+        .into_iter()
+        .map(|JsonProperty { name, kind, value }| {
+            let value = convert_property(&kind, value)
+                .map_err(|e| format!("property '{}': {}", name, e))?;
+
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn convert_property(kind: &str, value: Value) -> ParseResult<PropertyValue> {
+    match (kind, value) {
+        ("bool", Value::Bool(value)) => Ok(PropertyValue::Bool(value)),
+        ("int", Value::Number(value)) if value.is_i64() => {
+            Ok(PropertyValue::Int(value.as_i64().unwrap_or_default()))
+        }
+        ("float", Value::Number(value)) => {
+            Ok(PropertyValue::Float(value.as_f64().unwrap_or_default()))
+        }
+        ("object", Value::Number(value)) if value.is_u64() => Ok(PropertyValue::Object(
+            value.as_u64().unwrap_or_default() as u32,
+        )),
+        ("class", Value::Object(members)) => members
+            .into_iter()
+            .map(|(name, value)| Ok((name, convert_member(value)?)))
+            .collect::<ParseResult<_>>()
+            .map(PropertyValue::Class),
+        (kind, Value::String(value)) => parse_property(kind, &value),
+        (kind, value) => Err(format!("invalid {} property value '{}'", kind, value)),
+    }
+}
+
+/// Converts a member of a class property. Unlike top level properties, these are not
+/// stored alongside their type, so it has to be inferred from the JSON value.
+fn convert_member(value: Value) -> ParseResult<PropertyValue> {
+    match value {
+        Value::Bool(value) => Ok(PropertyValue::Bool(value)),
+        Value::Number(value) => Ok(match value.as_i64() {
+            Some(value) => PropertyValue::Int(value),
+            None => PropertyValue::Float(value.as_f64().unwrap_or_default()),
+        }),
+        Value::String(value) => Ok(PropertyValue::String(value)),
+        Value::Object(_) => convert_property("class", value),
+        value => Err(format!("invalid class member '{}'", value)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonMap {
+    width: i32,
+    height: i32,
+
+    #[serde(rename = "tilewidth")]
+    tile_width: i32,
+
+    #[serde(rename = "tileheight")]
+    tile_height: i32,
+
+    #[serde(default = "default_orientation")]
+    orientation: String,
+
+    #[serde(default)]
+    infinite: bool,
+
+    #[serde(rename = "backgroundcolor")]
+    background_color: Option<String>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+
+    #[serde(default)]
+    tilesets: Vec<JsonTilesetEntry>,
+
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+fn default_orientation() -> String {
+    "orthogonal".into()
+}
+
+/// A tileset in a map's tileset list, which is either a reference to an external
+/// file, or an embedded tileset.
+#[derive(Debug, Deserialize)]
+struct JsonTilesetEntry {
+    #[serde(rename = "firstgid")]
+    first_gid: u32,
+
+    source: Option<String>,
+
+    #[serde(flatten)]
+    rest: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    name: String,
+
+    #[serde(rename = "tilewidth")]
+    tile_width: i32,
+
+    #[serde(rename = "tileheight")]
+    tile_height: i32,
+
+    #[serde(default)]
+    margin: i32,
+
+    #[serde(default)]
+    spacing: i32,
+
+    columns: i32,
+
+    #[serde(rename = "tilecount")]
+    tile_count: u32,
+
+    image: Option<String>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+
+    #[serde(default)]
+    tiles: Vec<JsonTile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTile {
+    id: u32,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+
+    #[serde(default)]
+    animation: Vec<JsonFrame>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFrame {
+    #[serde(rename = "tileid")]
+    tile_id: u32,
+
+    duration: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+
+    #[serde(default)]
+    name: String,
+
+    #[serde(default = "default_visible")]
+    visible: bool,
+
+    #[serde(default = "default_opacity")]
+    opacity: f32,
+
+    #[serde(default, rename = "offsetx")]
+    offset_x: f32,
+
+    #[serde(default, rename = "offsety")]
+    offset_y: f32,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+
+    #[serde(default)]
+    width: i32,
+
+    #[serde(default)]
+    height: i32,
+
+    data: Option<Value>,
+    encoding: Option<String>,
+    compression: Option<String>,
+
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+
+    image: Option<String>,
+
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    id: u32,
+
+    #[serde(default)]
+    name: String,
+
+    // Tiled 1.9 renamed 'type' to 'class', and then switched back in 1.10.
+    #[serde(rename = "type")]
+    kind: Option<String>,
+
+    class: Option<String>,
+
+    #[serde(default)]
+    x: f32,
+
+    #[serde(default)]
+    y: f32,
+
+    #[serde(default)]
+    width: f32,
+
+    #[serde(default)]
+    height: f32,
+
+    #[serde(default)]
+    rotation: f32,
+
+    #[serde(default = "default_visible")]
+    visible: bool,
+
+    gid: Option<u32>,
+
+    #[serde(default)]
+    ellipse: bool,
+
+    #[serde(default)]
+    point: bool,
+
+    polygon: Option<Vec<JsonPoint>>,
+    polyline: Option<Vec<JsonPoint>>,
+    text: Option<JsonText>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonText {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonProperty {
+    name: String,
+
+    #[serde(rename = "type", default = "default_property_type")]
+    kind: String,
+
+    value: Value,
+}
+
+fn default_property_type() -> String {
+    "string".into()
+}
@@ -0,0 +1,333 @@
+//! Parsing for Tiled's XML formats (`.tmx` and `.tsx`).
+
+use std::str::FromStr;
+
+use roxmltree::{Document, Node};
+
+use super::{
+    check_map_size, check_map_support, decode_tiles, parse_color, parse_property, tile_frame,
+    LayerData, LayerKind, MapData, ObjectShape, Properties, PropertyValue, TileData, TiledObject,
+    TiledTile, TilesetData, TilesetSource,
+};
+use crate::math::Vec2;
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+pub(super) fn parse_map(xml: &str) -> ParseResult<MapData> {
+    let document = Document::parse(xml).map_err(|e| e.to_string())?;
+    let map = root_element(&document, "map")?;
+
+    check_map_support(
+        map.attribute("orientation").unwrap_or("orthogonal"),
+        flag(map, "infinite", false)?,
+    )?;
+
+    let width = required(map, "width")?;
+    let height = required(map, "height")?;
+
+    check_map_size(width, height)?;
+
+    let mut tilesets = Vec::new();
+
+    for tileset in children(map, "tileset") {
+        let first_gid = required(tileset, "firstgid")?;
+
+        tilesets.push(match tileset.attribute("source") {
+            Some(source) => TilesetSource::External {
+                first_gid,
+                source: source.to_owned(),
+            },
+            None => TilesetSource::Embedded {
+                first_gid,
+                data: convert_tileset(tileset)?,
+            },
+        });
+    }
+
+    Ok(MapData {
+        width,
+        height,
+        tile_width: required(map, "tilewidth")?,
+        tile_height: required(map, "tileheight")?,
+        background_color: map
+            .attribute("backgroundcolor")
+            .map(parse_color)
+            .transpose()?,
+        properties: convert_properties(map)?,
+        tilesets,
+        layers: convert_layers(map)?,
+    })
+}
+
+pub(super) fn parse_tileset(xml: &str) -> ParseResult<TilesetData> {
+    let document = Document::parse(xml).map_err(|e| e.to_string())?;
+
+    convert_tileset(root_element(&document, "tileset")?)
+}
+
+fn convert_tileset(tileset: Node<'_, '_>) -> ParseResult<TilesetData> {
+    let mut tiles = Vec::new();
+
+    for tile in children(tileset, "tile") {
+        let mut animation = Vec::new();
+
+        if let Some(frames) = child(tile, "animation") {
+            for frame in children(frames, "frame") {
+                animation.push(tile_frame(
+                    required(frame, "tileid")?,
+                    required(frame, "duration")?,
+                ));
+            }
+        }
+
+        tiles.push(TileData {
+            id: required(tile, "id")?,
+            properties: convert_properties(tile)?,
+            animation,
+        });
+    }
+
+    Ok(TilesetData {
+        name: tileset.attribute("name").unwrap_or_default().to_owned(),
+        tile_width: required(tileset, "tilewidth")?,
+        tile_height: required(tileset, "tileheight")?,
+        margin: optional(tileset, "margin")?.unwrap_or(0),
+        spacing: optional(tileset, "spacing")?.unwrap_or(0),
+        columns: required(tileset, "columns")?,
+        tile_count: required(tileset, "tilecount")?,
+        image: child(tileset, "image")
+            .and_then(|image| image.attribute("source"))
+            .map(str::to_owned),
+        properties: convert_properties(tileset)?,
+        tiles,
+    })
+}
+
+fn convert_layers(parent: Node<'_, '_>) -> ParseResult<Vec<LayerData>> {
+    parent
+        .children()
+        .filter(|node| {
+            node.is_element()
+                && matches!(
+                    node.tag_name().name(),
+                    "layer" | "objectgroup" | "imagelayer" | "group"
+                )
+        })
+        .map(convert_layer)
+        .collect()
+}
+
+fn convert_layer(layer: Node<'_, '_>) -> ParseResult<LayerData> {
+    let name = layer.attribute("name").unwrap_or_default();
+
+    let kind = match layer.tag_name().name() {
+        "layer" => {
+            let data =
+                child(layer, "data").ok_or_else(|| format!("layer '{}' has no tile data", name))?;
+
+            let tiles = match data.attribute("encoding") {
+                // Tiles without an encoding are stored as individual elements.
+                None => children(data, "tile")
+                    .map(|tile| Ok(optional(tile, "gid")?.unwrap_or(0)))
+                    .collect::<ParseResult<_>>()?,
+
+                encoding => decode_tiles(
+                    data.text().unwrap_or_default(),
+                    encoding,
+                    data.attribute("compression"),
+                )?,
+            };
+
+            LayerKind::Tiles {
+                width: required(layer, "width")?,
+                height: required(layer, "height")?,
+                tiles,
+            }
+        }
+
+        "objectgroup" => LayerKind::Objects(
+            children(layer, "object")
+                .map(convert_object)
+                .collect::<ParseResult<_>>()?,
+        ),
+
+        "imagelayer" => LayerKind::Image(
+            child(layer, "image")
+                .and_then(|image| image.attribute("source"))
+                .filter(|source| !source.is_empty())
+                .map(str::to_owned),
+        ),
+
+        _ => LayerKind::Group(convert_layers(layer)?),
+    };
+
+    Ok(LayerData {
+        name: name.to_owned(),
+        visible: flag(layer, "visible", true)?,
+        opacity: optional(layer, "opacity")?.unwrap_or(1.0),
+        offset: Vec2::new(
+            optional(layer, "offsetx")?.unwrap_or(0.0),
+            optional(layer, "offsety")?.unwrap_or(0.0),
+        ),
+        properties: convert_properties(layer)?,
+        kind,
+    })
+}
+
+fn convert_object(object: Node<'_, '_>) -> ParseResult<TiledObject> {
+    let shape = if let Some(polygon) = child(object, "polygon") {
+        ObjectShape::Polygon(convert_points(polygon)?)
+    } else if let Some(polyline) = child(object, "polyline") {
+        ObjectShape::Polyline(convert_points(polyline)?)
+    } else if let Some(text) = child(object, "text") {
+        ObjectShape::Text(text.text().unwrap_or_default().to_owned())
+    } else if child(object, "ellipse").is_some() {
+        ObjectShape::Ellipse
+    } else if child(object, "point").is_some() {
+        ObjectShape::Point
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    Ok(TiledObject {
+        id: optional(object, "id")?.unwrap_or(0),
+        name: object.attribute("name").unwrap_or_default().to_owned(),
+        // Tiled 1.9 renamed 'type' to 'class', and then switched back in 1.10.
+        class: object
+            .attribute("class")
+            .or_else(|| object.attribute("type"))
+            .unwrap_or_default()
+            .to_owned(),
+        x: optional(object, "x")?.unwrap_or(0.0),
+        y: optional(object, "y")?.unwrap_or(0.0),
+        width: optional(object, "width")?.unwrap_or(0.0),
+        height: optional(object, "height")?.unwrap_or(0.0),
+        rotation: optional(object, "rotation")?.unwrap_or(0.0),
+        visible: flag(object, "visible", true)?,
+        tile: optional(object, "gid")?.and_then(TiledTile::from_raw),
+        shape,
+        properties: convert_properties(object)?,
+    })
+}
+
+fn convert_points(node: Node<'_, '_>) -> ParseResult<Vec<Vec2<f32>>> {
+    let points = node.attribute("points").unwrap_or_default();
+
+    points
+        .split_whitespace()
+        .map(|point| {
+            let invalid = || format!("invalid point '{}'", point);
+
+            let mut coords = point.split(',').map(|coord| coord.parse::<f32>());
+
+            match (coords.next(), coords.next(), coords.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => Ok(Vec2::new(x, y)),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+/// Converts the properties of an element. Elements without a `<properties>` child
+/// have no properties.
+fn convert_properties(parent: Node<'_, '_>) -> ParseResult<Properties> {
+    let properties = match child(parent, "properties") {
+        Some(properties) => properties,
+        None => return Ok(Properties::new()),
+    };
+
+    children(properties, "property")
+        .map(|property| {
+            let name = property
+                .attribute("name")
+                .ok_or("property has no name")?
+                .to_owned();
+
+            let kind = property.attribute("type").unwrap_or("string");
+
+            let value = if kind == "class" {
+                convert_properties(property).map(PropertyValue::Class)
+            } else {
+                // Multi-line strings are stored as text, rather than in an attribute.
+                let value = property
+                    .attribute("value")
+                    .or_else(|| property.text())
+                    .unwrap_or_default();
+
+                parse_property(kind, value)
+            }
+            .map_err(|e| format!("property '{}': {}", name, e))?;
+
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn root_element<'a, 'input>(
+    document: &'a Document<'input>,
+    name: &str,
+) -> ParseResult<Node<'a, 'input>> {
+    let root = document.root_element();
+
+    if root.has_tag_name(name) {
+        Ok(root)
+    } else {
+        Err(format!(
+            "expected a <{}> element, found <{}>",
+            name,
+            root.tag_name().name()
+        ))
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn optional<T>(node: Node<'_, '_>, name: &str) -> ParseResult<Option<T>>
+where
+    T: FromStr,
+{
+    node.attribute(name)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                format!(
+                    "invalid {} '{}' on <{}>",
+                    name,
+                    value,
+                    node.tag_name().name()
+                )
+            })
+        })
+        .transpose()
+}
+
+fn required<T>(node: Node<'_, '_>, name: &str) -> ParseResult<T>
+where
+    T: FromStr,
+{
+    optional(node, name)?.ok_or_else(|| format!("<{}> is missing {}", node.tag_name().name(), name))
+}
+
+/// Parses a boolean attribute, which Tiled stores as `0` or `1`.
+fn flag(node: Node<'_, '_>, name: &str, default: bool) -> ParseResult<bool> {
+    match node.attribute(name) {
+        None => Ok(default),
+        Some("1") | Some("true") => Ok(true),
+        Some("0") | Some("false") => Ok(false),
+        Some(value) => Err(format!(
+            "invalid {} '{}' on <{}>",
+            name,
+            value,
+            node.tag_name().name()
+        )),
+    }
+}
@@ -0,0 +1,954 @@
+//! Functions and types relating to tile maps.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::graphics::{
+    BufferUsage, Camera, Color, DrawParams, Drawable, IndexBuffer, Mesh, Rectangle, Texture,
+    Vertex, VertexBuffer,
+};
+use crate::math::Vec2;
+use crate::time;
+use crate::Context;
+
+/// A texture containing a grid of tiles, which can be used to draw a [`TileLayer`].
+///
+/// Tiles are identified by their index within the grid, starting from zero at the
+/// top left and counting left to right, top to bottom.
+///
+/// # Animated Tiles
+///
+/// A tile can be animated by calling [`set_animation`](Self::set_animation) with a list
+/// of frames. Wherever the animated tile is placed in a [`TileLayer`], the frames will be
+/// displayed in its place, in sync with every other instance of the tile.
+///
+/// # Performance
+///
+/// Cloning a `Tileset` is fairly cheap, as the underlying texture is
+/// [reference-counted](https://doc.rust-lang.org/std/rc/struct.Rc.html). The tile animations
+/// will be copied, however.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tileset {
+    texture: Texture,
+    tile_width: i32,
+    tile_height: i32,
+    margin: i32,
+    spacing: i32,
+    columns: i32,
+    tile_count: u32,
+    animations: BTreeMap<u32, Vec<TileFrame>>,
+}
+
+impl Tileset {
+    /// Creates a new tileset from a texture, with tiles of the specified size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tile width or height is not positive.
+    pub fn new(texture: Texture, tile_width: i32, tile_height: i32) -> Tileset {
+        Tileset::with_spacing(texture, tile_width, tile_height, 0, 0)
+    }
+
+    /// Creates a new tileset from a texture, with tiles of the specified size.
+    ///
+    /// * `margin` is the number of pixels between the edge of the texture and the tiles.
+    /// * `spacing` is the number of pixels between each tile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tile width or height is not positive, or if the margin or spacing
+    /// is negative.
+    pub fn with_spacing(
+        texture: Texture,
+        tile_width: i32,
+        tile_height: i32,
+        margin: i32,
+        spacing: i32,
+    ) -> Tileset {
+        assert!(
+            tile_width > 0 && tile_height > 0,
+            "tile size must be positive"
+        );
+
+        assert!(
+            margin >= 0 && spacing >= 0,
+            "tileset margin and spacing cannot be negative"
+        );
+
+        let (width, height) = texture.size();
+
+        let columns = tiles_that_fit(width, tile_width, margin, spacing);
+        let rows = tiles_that_fit(height, tile_height, margin, spacing);
+
+        Tileset {
+            texture,
+            tile_width,
+            tile_height,
+            margin,
+            spacing,
+            columns,
+            tile_count: (columns * rows) as u32,
+            animations: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a reference to the tileset's texture.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Returns the width of each tile.
+    pub fn tile_width(&self) -> i32 {
+        self.tile_width
+    }
+
+    /// Returns the height of each tile.
+    pub fn tile_height(&self) -> i32 {
+        self.tile_height
+    }
+
+    /// Returns the number of columns of tiles in the tileset.
+    pub fn columns(&self) -> i32 {
+        self.columns
+    }
+
+    /// Returns the number of tiles in the tileset.
+    pub fn tile_count(&self) -> u32 {
+        self.tile_count
+    }
+
+    /// Returns the bounds of the specified tile within the texture, or `None` if the
+    /// tileset does not contain a tile with that ID.
+    pub fn tile_bounds(&self, id: u32) -> Option<Rectangle> {
+        if id >= self.tile_count {
+            return None;
+        }
+
+        Some(tile_bounds(
+            id,
+            self.columns,
+            self.tile_width,
+            self.tile_height,
+            self.margin,
+            self.spacing,
+        ))
+    }
+
+    /// Returns the frames of the specified tile's animation, or `None` if the tile is
+    /// not animated.
+    pub fn animation(&self, id: u32) -> Option<&[TileFrame]> {
+        self.animations.get(&id).map(Vec::as_slice)
+    }
+
+    /// Sets the frames that should be displayed in place of the specified tile.
+    ///
+    /// If the list of frames is empty, the tile's animation will be removed.
+    pub fn set_animation(&mut self, id: u32, frames: Vec<TileFrame>) {
+        if frames.is_empty() {
+            self.animations.remove(&id);
+        } else {
+            self.animations.insert(id, frames);
+        }
+    }
+
+    /// Removes the animation from the specified tile.
+    pub fn remove_animation(&mut self, id: u32) {
+        self.animations.remove(&id);
+    }
+
+    /// Returns the ID of the tile that should be displayed in place of the specified tile,
+    /// after the given amount of time has passed.
+    fn resolve_tile(&self, id: u32, time: Duration) -> u32 {
+        match self.animations.get(&id) {
+            Some(frames) => current_frame(frames, time).unwrap_or(id),
+            None => id,
+        }
+    }
+
+    /// Returns the currently displayed frame of each animation.
+    fn animation_state(&self, time: Duration) -> Vec<u32> {
+        self.animations
+            .values()
+            .map(|frames| current_frame(frames, time).unwrap_or(0))
+            .collect()
+    }
+}
+
+/// A single frame of an animated tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TileFrame {
+    /// The ID of the tile that should be displayed.
+    pub tile: u32,
+
+    /// How long the frame should be displayed for.
+    pub duration: Duration,
+}
+
+impl TileFrame {
+    /// Creates a new frame.
+    pub fn new(tile: u32, duration: Duration) -> TileFrame {
+        TileFrame { tile, duration }
+    }
+}
+
+/// A tile within a [`TileLayer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Tile {
+    /// The ID of the tile within the layer's [`Tileset`].
+    pub id: u32,
+
+    /// Whether the tile should be flipped horizontally.
+    pub flip_x: bool,
+
+    /// Whether the tile should be flipped vertically.
+    pub flip_y: bool,
+
+    /// Whether the tile should be flipped diagonally (i.e. its X and Y axes should be
+    /// swapped). This is applied before the horizontal and vertical flips, which
+    /// allows tiles to be rotated in 90 degree increments.
+    ///
+    /// For example, a diagonal flip followed by a horizontal flip will rotate the tile 90
+    /// degrees clockwise. This matches the behaviour of [Tiled](https://www.mapeditor.org/).
+    pub flip_diagonal: bool,
+}
+
+impl Tile {
+    /// Creates a new unflipped tile.
+    pub fn new(id: u32) -> Tile {
+        Tile {
+            id,
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+        }
+    }
+}
+
+impl From<u32> for Tile {
+    fn from(id: u32) -> Tile {
+        Tile::new(id)
+    }
+}
+
+/// A grid of tiles, drawn from a single [`Tileset`].
+///
+/// Rather than drawing each tile individually, the layer is split into chunks of
+/// [`CHUNK_SIZE`](Self::CHUNK_SIZE) by `CHUNK_SIZE` tiles, and each chunk's geometry is
+/// stored on the GPU. This means that drawing a large layer only takes a handful of draw
+/// calls, and the geometry does not have to be sent to the GPU every frame.
+///
+/// Tiles are positioned on a grid of the layer's tile size. If the tileset's tiles are
+/// larger than the grid, they will be aligned to the bottom left of their grid cell,
+/// matching the behaviour of [Tiled](https://www.mapeditor.org/).
+///
+/// # Performance
+///
+/// Changing a tile will cause the chunk containing it to be rebuilt the next time it is
+/// drawn. This is much cheaper than rebuilding the whole layer, but you should still try
+/// to avoid changing lots of tiles every frame.
+///
+/// Chunks containing [animated tiles](Tileset::set_animation) will be rebuilt whenever the
+/// displayed frame changes.
+///
+/// Cloning a `TileLayer` copies the tile data, but not the GPU geometry - this will be
+/// rebuilt the first time that the clone is drawn.
+///
+/// # Examples
+///
+/// The [`tilemap`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/tilemap.rs)
+/// example demonstrates how to build a tile map and draw it with a camera.
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+    tileset: Tileset,
+    width: i32,
+    height: i32,
+    tile_width: f32,
+    tile_height: f32,
+    tiles: Vec<Option<Tile>>,
+    visible: bool,
+    time: Duration,
+
+    chunks_x: i32,
+    chunks_y: i32,
+    chunks: RefCell<Vec<Chunk>>,
+    index_buffer: RefCell<Option<IndexBuffer>>,
+    animation_state: RefCell<Vec<u32>>,
+}
+
+impl TileLayer {
+    /// The width and height of each chunk of the layer, in tiles.
+    pub const CHUNK_SIZE: i32 = 16;
+
+    /// Creates a new, empty layer, with the specified size (in tiles) and grid size
+    /// (in pixels).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is negative.
+    pub fn new(
+        tileset: Tileset,
+        width: i32,
+        height: i32,
+        tile_width: f32,
+        tile_height: f32,
+    ) -> TileLayer {
+        assert!(width >= 0 && height >= 0, "layer size cannot be negative");
+
+        let chunks_x = (width + Self::CHUNK_SIZE - 1) / Self::CHUNK_SIZE;
+        let chunks_y = (height + Self::CHUNK_SIZE - 1) / Self::CHUNK_SIZE;
+
+        let animation_state = tileset.animation_state(Duration::from_secs(0));
+
+        TileLayer {
+            tileset,
+            width,
+            height,
+            tile_width,
+            tile_height,
+            tiles: vec![None; (width * height) as usize],
+            visible: true,
+            time: Duration::from_secs(0),
+
+            chunks_x,
+            chunks_y,
+            chunks: RefCell::new(vec![Chunk::default(); (chunks_x * chunks_y) as usize]),
+            index_buffer: RefCell::new(None),
+            animation_state: RefCell::new(animation_state),
+        }
+    }
+
+    /// Returns a reference to the layer's tileset.
+    pub fn tileset(&self) -> &Tileset {
+        &self.tileset
+    }
+
+    /// Sets the layer's tileset.
+    ///
+    /// This will cause the whole layer to be rebuilt the next time it is drawn.
+    pub fn set_tileset(&mut self, tileset: Tileset) {
+        self.tileset = tileset;
+        self.animation_state
+            .replace(self.tileset.animation_state(self.time));
+
+        for chunk in self.chunks.get_mut() {
+            chunk.dirty = true;
+        }
+    }
+
+    /// Returns the width of the layer, in tiles.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Returns the height of the layer, in tiles.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Returns the width of each cell in the layer's grid.
+    pub fn tile_width(&self) -> f32 {
+        self.tile_width
+    }
+
+    /// Returns the height of each cell in the layer's grid.
+    pub fn tile_height(&self) -> f32 {
+        self.tile_height
+    }
+
+    /// Returns whether or not the layer will be drawn.
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Sets whether or not the layer will be drawn.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Returns the tile at the specified position, or `None` if the position is empty
+    /// or out of bounds.
+    pub fn get_tile(&self, x: i32, y: i32) -> Option<Tile> {
+        if self.in_bounds(x, y) {
+            self.tiles[(y * self.width + x) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Sets the tile at the specified position.
+    ///
+    /// Passing `None` will clear the tile. Only the chunk containing the tile will be
+    /// rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is out of bounds.
+    pub fn set_tile<T>(&mut self, x: i32, y: i32, tile: T)
+    where
+        T: Into<Option<Tile>>,
+    {
+        assert!(self.in_bounds(x, y), "tile position was out of bounds");
+
+        let tile = tile.into();
+        let index = (y * self.width + x) as usize;
+
+        if self.tiles[index] != tile {
+            self.tiles[index] = tile;
+
+            let chunk_index = (y / Self::CHUNK_SIZE) * self.chunks_x + (x / Self::CHUNK_SIZE);
+            self.chunks.get_mut()[chunk_index as usize].dirty = true;
+        }
+    }
+
+    /// Removes every tile from the layer.
+    pub fn clear(&mut self) {
+        for tile in &mut self.tiles {
+            *tile = None;
+        }
+
+        for chunk in self.chunks.get_mut() {
+            chunk.dirty = true;
+        }
+    }
+
+    /// Advances the timer for the layer's animated tiles.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed - as such, you should call it in
+    /// [`State::draw`](crate::State::draw) method for accurate results.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the timer for the layer's animated tiles by a specified amount.
+    pub fn advance_by(&mut self, duration: Duration) {
+        self.time += duration;
+    }
+
+    /// Returns the bounds of the layer, before any transformations are applied.
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(
+            0.0,
+            0.0,
+            self.width as f32 * self.tile_width,
+            self.height as f32 * self.tile_height,
+        )
+    }
+
+    /// Draws the chunks of the layer that are visible through the specified camera.
+    ///
+    /// The `params` are used to position the layer, in the same way as when drawing it via
+    /// [`Drawable`]. Rotation is not taken into account when working out which chunks are
+    /// visible - if you need to rotate a layer, draw it via [`Drawable`] instead.
+    pub fn draw_visible<P>(&self, ctx: &mut Context, camera: &Camera, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+        let visible = camera.visible_rect();
+
+        // Convert the visible area into the layer's local space.
+        let x1 = (visible.x - params.position.x) / params.scale.x + params.origin.x;
+        let y1 = (visible.y - params.position.y) / params.scale.y + params.origin.y;
+        let x2 = (visible.right() - params.position.x) / params.scale.x + params.origin.x;
+        let y2 = (visible.bottom() - params.position.y) / params.scale.y + params.origin.y;
+
+        let local = Rectangle::new(x1.min(x2), y1.min(y2), (x2 - x1).abs(), (y2 - y1).abs());
+
+        self.draw_chunks(ctx, params, Some(local));
+    }
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    /// Returns the area covered by a chunk's tiles, in local space.
+    fn chunk_bounds(&self, chunk_x: i32, chunk_y: i32) -> Rectangle {
+        let chunk_width = Self::CHUNK_SIZE as f32 * self.tile_width;
+        let chunk_height = Self::CHUNK_SIZE as f32 * self.tile_height;
+
+        // Tiles that are larger than the grid overhang the top and right of their cell.
+        let overhang_x = (self.tileset.tile_width as f32 - self.tile_width).max(0.0);
+        let overhang_y = (self.tileset.tile_height as f32 - self.tile_height).max(0.0);
+
+        Rectangle::new(
+            chunk_x as f32 * chunk_width,
+            chunk_y as f32 * chunk_height - overhang_y,
+            chunk_width + overhang_x,
+            chunk_height + overhang_y,
+        )
+    }
+
+    fn draw_chunks(&self, ctx: &mut Context, params: DrawParams, visible: Option<Rectangle>) {
+        if !self.visible {
+            return;
+        }
+
+        let mut chunks = self.chunks.borrow_mut();
+
+        let animation_state = self.tileset.animation_state(self.time);
+
+        if *self.animation_state.borrow() != animation_state {
+            for chunk in chunks.iter_mut().filter(|c| c.animated) {
+                chunk.dirty = true;
+            }
+
+            self.animation_state.replace(animation_state);
+        }
+
+        for chunk_y in 0..self.chunks_y {
+            for chunk_x in 0..self.chunks_x {
+                if let Some(visible) = visible {
+                    if !visible.intersects(&self.chunk_bounds(chunk_x, chunk_y)) {
+                        continue;
+                    }
+                }
+
+                let chunk = &mut chunks[(chunk_y * self.chunks_x + chunk_x) as usize];
+
+                // If the chunk can't be built, there's not much we can do other than skip it.
+                if chunk.dirty && self.rebuild_chunk(ctx, chunk, chunk_x, chunk_y).is_err() {
+                    continue;
+                }
+
+                if let Some(mesh) = &chunk.mesh {
+                    mesh.draw(ctx, params.clone());
+                }
+            }
+        }
+    }
+
+    fn rebuild_chunk(
+        &self,
+        ctx: &mut Context,
+        chunk: &mut Chunk,
+        chunk_x: i32,
+        chunk_y: i32,
+    ) -> Result {
+        let start_x = chunk_x * Self::CHUNK_SIZE;
+        let start_y = chunk_y * Self::CHUNK_SIZE;
+        let end_x = (start_x + Self::CHUNK_SIZE).min(self.width);
+        let end_y = (start_y + Self::CHUNK_SIZE).min(self.height);
+
+        let texture_width = self.tileset.texture.width() as f32;
+        let texture_height = self.tileset.texture.height() as f32;
+
+        let quad_width = self.tileset.tile_width as f32;
+        let quad_height = self.tileset.tile_height as f32;
+
+        let mut vertices = Vec::new();
+        let mut animated = false;
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let tile = match self.tiles[(y * self.width + x) as usize] {
+                    Some(tile) => tile,
+                    None => continue,
+                };
+
+                animated |= self.tileset.animations.contains_key(&tile.id);
+
+                let id = self.tileset.resolve_tile(tile.id, self.time);
+
+                let source = match self.tileset.tile_bounds(id) {
+                    Some(source) => source,
+                    None => continue,
+                };
+
+                let x1 = x as f32 * self.tile_width;
+                let y2 = (y + 1) as f32 * self.tile_height;
+                let x2 = x1 + quad_width;
+                let y1 = y2 - quad_height;
+
+                let u1 = source.x / texture_width;
+                let v1 = source.y / texture_height;
+                let u2 = source.right() / texture_width;
+                let v2 = source.bottom() / texture_height;
+
+                let uv = |corner: (bool, bool)| {
+                    let (right, bottom) = flip_corner(tile, corner);
+
+                    Vec2::new(if right { u2 } else { u1 }, if bottom { v2 } else { v1 })
+                };
+
+                vertices.extend_from_slice(&[
+                    Vertex::new(Vec2::new(x1, y1), uv((false, false)), Color::WHITE),
+                    Vertex::new(Vec2::new(x1, y2), uv((false, true)), Color::WHITE),
+                    Vertex::new(Vec2::new(x2, y2), uv((true, true)), Color::WHITE),
+                    Vertex::new(Vec2::new(x2, y1), uv((true, false)), Color::WHITE),
+                ]);
+            }
+        }
+
+        chunk.animated = animated;
+        chunk.dirty = false;
+
+        let quad_count = vertices.len() / 4;
+
+        if quad_count == 0 {
+            chunk.mesh = None;
+            return Ok(());
+        }
+
+        match &mut chunk.mesh {
+            Some(mesh) if chunk.capacity >= quad_count => {
+                mesh.vertex_buffer().set_data(ctx, &vertices, 0);
+                mesh.set_draw_range(0, quad_count * 6);
+            }
+
+            _ => {
+                let vertex_buffer = VertexBuffer::with_usage(ctx, &vertices, BufferUsage::Static)?;
+
+                let mut mesh = Mesh::indexed(vertex_buffer, self.index_buffer(ctx)?);
+                mesh.set_texture(self.tileset.texture.clone());
+                mesh.set_draw_range(0, quad_count * 6);
+
+                chunk.mesh = Some(mesh);
+                chunk.capacity = quad_count;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns an index buffer that is large enough to draw a full chunk, creating it
+    /// if it does not exist yet.
+    ///
+    /// The indices are the same for every chunk, so they only need to be uploaded once.
+    fn index_buffer(&self, ctx: &mut Context) -> Result<IndexBuffer> {
+        let mut index_buffer = self.index_buffer.borrow_mut();
+
+        if let Some(index_buffer) = &*index_buffer {
+            return Ok(index_buffer.clone());
+        }
+
+        let quads = (Self::CHUNK_SIZE * Self::CHUNK_SIZE) as usize;
+
+        let indices: Vec<u32> = super::INDEX_ARRAY
+            .iter()
+            .cycle()
+            .take(quads * 6)
+            .enumerate()
+            .map(|(i, vertex)| vertex + i as u32 / 6 * 4)
+            .collect();
+
+        let buffer = IndexBuffer::with_usage(ctx, &indices, BufferUsage::Static)?;
+
+        *index_buffer = Some(buffer.clone());
+
+        Ok(buffer)
+    }
+}
+
+impl Drawable for TileLayer {
+    fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        self.draw_chunks(ctx, params.into(), None);
+    }
+}
+
+/// A chunk of a layer's geometry, stored on the GPU.
+#[derive(Debug)]
+struct Chunk {
+    mesh: Option<Mesh>,
+    capacity: usize,
+    dirty: bool,
+    animated: bool,
+}
+
+impl Default for Chunk {
+    fn default() -> Chunk {
+        Chunk {
+            mesh: None,
+            capacity: 0,
+            dirty: true,
+            animated: false,
+        }
+    }
+}
+
+impl Clone for Chunk {
+    fn clone(&self) -> Chunk {
+        // The GPU buffers are shared between clones, so if they were copied over, updating
+        // one layer would corrupt the other. Instead, the clone rebuilds its own geometry.
+        Chunk::default()
+    }
+}
+
+/// A collection of [`TileLayer`]s, which are drawn on top of each other.
+///
+/// # Examples
+///
+/// The [`tilemap`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/tilemap.rs)
+/// example demonstrates how to build a tile map and draw it with a camera.
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    width: i32,
+    height: i32,
+    tile_width: f32,
+    tile_height: f32,
+    layers: Vec<TileLayer>,
+}
+
+impl TileMap {
+    /// Creates a new tile map with no layers, with the specified size (in tiles) and
+    /// grid size (in pixels).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is negative.
+    pub fn new(width: i32, height: i32, tile_width: f32, tile_height: f32) -> TileMap {
+        assert!(width >= 0 && height >= 0, "map size cannot be negative");
+
+        TileMap {
+            width,
+            height,
+            tile_width,
+            tile_height,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Returns the width of the map, in tiles.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Returns the height of the map, in tiles.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Returns the width of each cell in the map's grid.
+    pub fn tile_width(&self) -> f32 {
+        self.tile_width
+    }
+
+    /// Returns the height of each cell in the map's grid.
+    pub fn tile_height(&self) -> f32 {
+        self.tile_height
+    }
+
+    /// Adds a new, empty layer to the top of the map, using the specified tileset.
+    ///
+    /// The layer will be the same size as the map. A mutable reference to the layer
+    /// will be returned, so that tiles can be added to it.
+    pub fn add_layer(&mut self, tileset: Tileset) -> &mut TileLayer {
+        self.layers.push(TileLayer::new(
+            tileset,
+            self.width,
+            self.height,
+            self.tile_width,
+            self.tile_height,
+        ));
+
+        self.layers.last_mut().expect("layer was just added")
+    }
+
+    /// Adds an existing layer to the top of the map.
+    pub fn push_layer(&mut self, layer: TileLayer) {
+        self.layers.push(layer);
+    }
+
+    /// Returns the map's layers, from bottom to top.
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// Returns mutable references to the map's layers, from bottom to top.
+    pub fn layers_mut(&mut self) -> &mut [TileLayer] {
+        &mut self.layers
+    }
+
+    /// Returns a reference to the layer at the specified index, or `None` if the index
+    /// is out of bounds.
+    pub fn layer(&self, index: usize) -> Option<&TileLayer> {
+        self.layers.get(index)
+    }
+
+    /// Returns a mutable reference to the layer at the specified index, or `None` if
+    /// the index is out of bounds.
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut TileLayer> {
+        self.layers.get_mut(index)
+    }
+
+    /// Removes the layer at the specified index, and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove_layer(&mut self, index: usize) -> TileLayer {
+        self.layers.remove(index)
+    }
+
+    /// Advances the timer for the animated tiles in every layer.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed - as such, you should call it in
+    /// [`State::draw`](crate::State::draw) method for accurate results.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the timer for the animated tiles in every layer by a specified amount.
+    pub fn advance_by(&mut self, duration: Duration) {
+        for layer in &mut self.layers {
+            layer.advance_by(duration);
+        }
+    }
+
+    /// Draws the parts of the map that are visible through the specified camera.
+    ///
+    /// See [`TileLayer::draw_visible`] for more details.
+    pub fn draw_visible<P>(&self, ctx: &mut Context, camera: &Camera, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+
+        for layer in &self.layers {
+            layer.draw_visible(ctx, camera, params.clone());
+        }
+    }
+}
+
+impl Drawable for TileMap {
+    fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+
+        for layer in &self.layers {
+            layer.draw(ctx, params.clone());
+        }
+    }
+}
+
+/// Returns the number of tiles that will fit along one axis of a tileset.
+fn tiles_that_fit(size: i32, tile_size: i32, margin: i32, spacing: i32) -> i32 {
+    ((size - margin * 2 + spacing) / (tile_size + spacing)).max(0)
+}
+
+/// Returns the bounds of a tile within a tileset's texture.
+///
+/// The number of columns must be positive.
+pub(crate) fn tile_bounds(
+    id: u32,
+    columns: i32,
+    tile_width: i32,
+    tile_height: i32,
+    margin: i32,
+    spacing: i32,
+) -> Rectangle {
+    let column = id as i32 % columns;
+    let row = id as i32 / columns;
+
+    Rectangle::new(
+        (margin + column * (tile_width + spacing)) as f32,
+        (margin + row * (tile_height + spacing)) as f32,
+        tile_width as f32,
+        tile_height as f32,
+    )
+}
+
+/// Returns the frame of an animation that should be displayed after the given amount of time.
+fn current_frame(frames: &[TileFrame], time: Duration) -> Option<u32> {
+    let total: u128 = frames.iter().map(|f| f.duration.as_nanos()).sum();
+
+    if total == 0 {
+        return frames.first().map(|f| f.tile);
+    }
+
+    let mut remaining = time.as_nanos() % total;
+
+    for frame in frames {
+        let duration = frame.duration.as_nanos();
+
+        if remaining < duration {
+            return Some(frame.tile);
+        }
+
+        remaining -= duration;
+    }
+
+    None
+}
+
+/// Works out which corner of the source image should be displayed at a corner of the tile,
+/// taking the tile's flip flags into account.
+///
+/// Corners are represented as `(right, bottom)`.
+fn flip_corner(tile: Tile, (mut right, mut bottom): (bool, bool)) -> (bool, bool) {
+    if tile.flip_y {
+        bottom = !bottom;
+    }
+
+    if tile.flip_x {
+        right = !right;
+    }
+
+    if tile.flip_diagonal {
+        std::mem::swap(&mut right, &mut bottom);
+    }
+
+    (right, bottom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_tiles() {
+        assert_eq!(tiles_that_fit(64, 16, 0, 0), 4);
+        assert_eq!(tiles_that_fit(72, 16, 1, 2), 4);
+        assert_eq!(tiles_that_fit(71, 16, 1, 2), 3);
+        assert_eq!(tiles_that_fit(8, 16, 0, 0), 0);
+    }
+
+    #[test]
+    fn bounds_of_tiles() {
+        assert_eq!(
+            tile_bounds(5, 4, 16, 8, 0, 0),
+            Rectangle::new(16.0, 8.0, 16.0, 8.0)
+        );
+
+        assert_eq!(
+            tile_bounds(5, 4, 16, 8, 1, 2),
+            Rectangle::new(19.0, 11.0, 16.0, 8.0)
+        );
+    }
+
+    #[test]
+    fn animation_frames() {
+        let frames = [
+            TileFrame::new(4, Duration::from_millis(100)),
+            TileFrame::new(5, Duration::from_millis(50)),
+        ];
+
+        assert_eq!(current_frame(&frames, Duration::from_millis(0)), Some(4));
+        assert_eq!(current_frame(&frames, Duration::from_millis(120)), Some(5));
+        assert_eq!(current_frame(&frames, Duration::from_millis(150)), Some(4));
+    }
+
+    #[test]
+    fn flip_flags() {
+        let top_left = (false, false);
+
+        let mut tile = Tile::new(0);
+        assert_eq!(flip_corner(tile, top_left), (false, false));
+
+        tile.flip_x = true;
+        assert_eq!(flip_corner(tile, top_left), (true, false));
+
+        // Diagonal + horizontal is a 90 degree clockwise rotation, so the top left of the
+        // tile should show the bottom left of the image.
+        tile.flip_diagonal = true;
+        assert_eq!(flip_corner(tile, top_left), (false, true));
+    }
+}
@@ -1,19 +1,23 @@
 //! Functions and types relating to user interfaces.
 
-use crate::graphics::{self, DrawParams, Drawable, Rectangle, Texture};
+use crate::graphics::{self, DrawParams, Drawable, Rectangle, Texture, TextureRegion};
 use crate::Context;
 
 /// A panel made up of nine slices of an image. Useful for panels with borders.
 ///
 /// Note that `NineSlice` does not currently support the [`clip` `DrawParam`](DrawParams).
 ///
+/// A panel can be created from either a whole texture, or a [`TextureRegion`] (e.g. an image
+/// from a [`TextureAtlas`](super::TextureAtlas)). If the region has been trimmed, the trimmed
+/// bounds will be sliced, and the offset will be ignored.
+///
 /// # Examples
 ///
 /// The [`nineslice`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/nineslice.rs)
 /// example demonstrates how to draw a `NineSlice` panel.
 #[derive(Debug, Clone)]
 pub struct NineSlice {
-    texture: Texture,
+    region: TextureRegion,
     width: f32,
     height: f32,
     fill_rect: Rectangle,
@@ -25,8 +29,22 @@ impl NineSlice {
     /// The `fill_rect` is used to determine how to slice the texture - it should be set
     /// to the region of the texture that represents the center of the panel.
     pub fn new(texture: Texture, width: f32, height: f32, fill_rect: Rectangle) -> NineSlice {
+        NineSlice::from_region(TextureRegion::from(texture), width, height, fill_rect)
+    }
+
+    /// Creates a new panel from the given texture region.
+    ///
+    /// The `fill_rect` is used to determine how to slice the region - it should be set
+    /// to the area of the region that represents the center of the panel, relative to the
+    /// top left corner of the region's bounds.
+    pub fn from_region(
+        region: TextureRegion,
+        width: f32,
+        height: f32,
+        fill_rect: Rectangle,
+    ) -> NineSlice {
         NineSlice {
-            texture,
+            region,
             width,
             height,
             fill_rect,
@@ -35,15 +53,29 @@ impl NineSlice {
 
     /// Returns a reference to the underlying texture for the panel.
     pub fn texture(&self) -> &Texture {
-        &self.texture
+        self.region.texture()
     }
 
     /// Sets the underlying texture for the panel.
     ///
-    /// This will not adjust the way that the texture is sliced, so you may need to also call
+    /// The whole of the new texture will be used for the panel. This will not adjust the
+    /// way that the texture is sliced, so you may need to also call
     /// [`set_fill_rect`](Self::set_fill_rect).
     pub fn set_texture(&mut self, texture: Texture) {
-        self.texture = texture;
+        self.region = TextureRegion::from(texture);
+    }
+
+    /// Returns a reference to the texture region that the panel is sliced from.
+    pub fn region(&self) -> &TextureRegion {
+        &self.region
+    }
+
+    /// Sets the texture region that the panel is sliced from.
+    ///
+    /// This will not adjust the way that the region is sliced, so you may need to also call
+    /// [`set_fill_rect`](Self::set_fill_rect).
+    pub fn set_region(&mut self, region: TextureRegion) {
+        self.region = region;
     }
 
     /// Gets the width of the panel.
@@ -77,12 +109,12 @@ impl NineSlice {
         self.height = height;
     }
 
-    /// Gets the section of the texture that is being used to fill the center of the panel.
+    /// Gets the section of the texture region that is being used to fill the center of the panel.
     pub fn fill_rect(&self) -> &Rectangle {
         &self.fill_rect
     }
 
-    /// Sets the section of the texture that should fill the center of the panel.
+    /// Sets the section of the texture region that should fill the center of the panel.
     pub fn set_fill_rect(&mut self, fill_rect: Rectangle) {
         self.fill_rect = fill_rect;
     }
@@ -95,8 +127,11 @@ impl Drawable for NineSlice {
     {
         let params = params.into();
 
-        let texture_width = self.texture.width() as f32;
-        let texture_height = self.texture.height() as f32;
+        let texture = self.region.texture();
+        let bounds = self.region.bounds();
+
+        let texture_width = texture.width() as f32;
+        let texture_height = texture.height() as f32;
 
         let x1 = 0.0;
         let y1 = 0.0;
@@ -107,16 +142,16 @@ impl Drawable for NineSlice {
         let x4 = self.width;
         let y4 = self.height;
 
-        let u1 = 0.0;
-        let v1 = 0.0;
-        let u2 = self.fill_rect.x / texture_width;
-        let v2 = self.fill_rect.y / texture_height;
-        let u3 = (self.fill_rect.x + self.fill_rect.width) / texture_width;
-        let v3 = (self.fill_rect.y + self.fill_rect.height) / texture_height;
-        let u4 = 1.0;
-        let v4 = 1.0;
+        let u1 = bounds.x / texture_width;
+        let v1 = bounds.y / texture_height;
+        let u2 = (bounds.x + self.fill_rect.x) / texture_width;
+        let v2 = (bounds.y + self.fill_rect.y) / texture_height;
+        let u3 = (bounds.x + self.fill_rect.x + self.fill_rect.width) / texture_width;
+        let v3 = (bounds.y + self.fill_rect.y + self.fill_rect.height) / texture_height;
+        let u4 = (bounds.x + bounds.width) / texture_width;
+        let v4 = (bounds.y + bounds.height) / texture_height;
 
-        graphics::set_texture(ctx, &self.texture);
+        graphics::set_texture(ctx, texture);
 
         // Top left
         graphics::push_quad(ctx, x1, y1, x2, y2, u1, v1, u2, v2, &params);
@@ -0,0 +1,364 @@
+use std::rc::Rc;
+
+use crate::error::{Result, TetraError};
+use crate::graphics::{self, BufferUsage, Color, Shader, UniformBlock, UniformValue};
+use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
+use crate::platform::RawUniformBuffer;
+use crate::Context;
+
+/// Uniform data, stored in GPU memory, which can be shared between multiple shaders.
+///
+/// A uniform buffer can be attached to a uniform block in a shader by passing it to
+/// [`Shader::set_uniform`], using the name of the block (rather than the name of the
+/// uniforms inside it). Any number of shaders can use the same buffer, so data that
+/// is common to many shaders (e.g. the time, or the camera's position) only has to be
+/// uploaded once per frame.
+///
+/// # Data Format
+///
+/// The data in the buffer must match the layout of the uniform block in the shader. Blocks
+/// should be declared with the `std140` layout, so that their layout is predictable:
+///
+/// ```glsl
+/// layout(std140) uniform Frame {
+///     float time;
+///     vec2 resolution;
+/// };
+/// ```
+///
+/// The [`Std140Writer`] type can be used to build data that follows this layout.
+///
+/// Uniform blocks require GLSL 1.40 (OpenGL 3.1) or higher.
+///
+/// # Performance
+///
+/// Creating a `UniformBuffer` is a relatively expensive operation. If you can, store them in your
+/// [`State`](crate::State) struct rather than recreating them each frame.
+///
+/// Cloning a `UniformBuffer` is a very cheap operation, as the underlying data is shared between the
+/// original instance and the clone via [reference-counting](https://doc.rust-lang.org/std/rc/struct.Rc.html).
+/// This does mean, however, that updating a `UniformBuffer` will also update any other clones of
+/// that `UniformBuffer`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformBuffer {
+    pub(crate) handle: Rc<RawUniformBuffer>,
+}
+
+impl UniformBuffer {
+    /// Creates a new uniform buffer, with the specified size in bytes.
+    ///
+    /// The contents of the buffer will be undefined until data is written to it via
+    /// [`set_data`](Self::set_data).
+    ///
+    /// The buffer will be created with the [`BufferUsage::Dynamic`] usage hint - this can
+    /// be overridden via the [`with_usage`](Self::with_usage) constructor.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    ///   graphics API encounters an error.
+    pub fn new(ctx: &mut Context, size: usize) -> Result<UniformBuffer> {
+        UniformBuffer::with_usage(ctx, size, BufferUsage::Dynamic)
+    }
+
+    /// Creates a new uniform buffer, with the specified size in bytes and usage hint.
+    ///
+    /// The GPU may optionally use the usage hint to optimize data storage and access.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    ///   graphics API encounters an error.
+    pub fn with_usage(ctx: &mut Context, size: usize, usage: BufferUsage) -> Result<UniformBuffer> {
+        let buffer = ctx.device.new_uniform_buffer(size, usage)?;
+
+        Ok(UniformBuffer {
+            handle: Rc::new(buffer),
+        })
+    }
+
+    /// Returns the size of the buffer, in bytes.
+    pub fn size(&self) -> usize {
+        self.handle.size()
+    }
+
+    /// Uploads new data to the GPU, starting at the specified offset (in bytes).
+    ///
+    /// This will trigger a [`flush`](graphics::flush) to the graphics hardware, so that
+    /// anything that was drawn before the data changed will still use the old data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data would be written out of bounds.
+    pub fn set_data(&self, ctx: &mut Context, data: &[u8], offset: usize) {
+        graphics::flush(ctx);
+
+        ctx.device
+            .set_uniform_buffer_data(&self.handle, data, offset);
+    }
+}
+
+impl UniformValue for UniformBuffer {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        let mut blocks = shader.data.uniform_blocks.borrow_mut();
+
+        if let Some(block) = blocks.get_mut(name) {
+            block.buffer = self.clone();
+        } else {
+            // Each block in a shader gets its own binding point, which the buffer will
+            // be bound to whenever the shader is used.
+            let binding = blocks.len() as u32;
+
+            let has_block =
+                ctx.device
+                    .set_uniform_block_binding(&shader.data.handle.borrow(), name, binding);
+
+            if has_block {
+                blocks.insert(
+                    name.to_owned(),
+                    UniformBlock {
+                        buffer: self.clone(),
+                        binding,
+                    },
+                );
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    fn validate(&self, ctx: &Context, shader: &Shader, name: &str) -> Result {
+        // Uniform blocks are not listed alongside the shader's other uniforms, so they
+        // have to be looked up separately.
+        let handle = shader.data.handle.borrow();
+        let has_block = ctx.device.has_uniform_block(&handle, name);
+
+        if has_block {
+            Ok(())
+        } else {
+            Err(TetraError::InvalidUniform(format!(
+                "the shader has no active uniform block named '{}'",
+                name
+            )))
+        }
+    }
+}
+
+/// A helper for building uniform buffer data that matches the GLSL `std140` layout.
+///
+/// Values should be written in the same order that they are declared in the uniform
+/// block - the writer will take care of adding the padding that `std140` requires
+/// between them. Once all of the values have been written, the data can be uploaded
+/// via [`UniformBuffer::set_data`].
+///
+/// Booleans should be written as `u32`s, with `0` representing `false`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Std140Writer {
+    data: Vec<u8>,
+}
+
+impl Std140Writer {
+    /// Creates a new, empty writer.
+    pub fn new() -> Std140Writer {
+        Std140Writer { data: Vec::new() }
+    }
+
+    /// Writes a `float`.
+    pub fn write_f32(&mut self, value: f32) -> &mut Std140Writer {
+        self.align(4);
+        self.push_f32s(&[value]);
+        self
+    }
+
+    /// Writes an `int`.
+    pub fn write_i32(&mut self, value: i32) -> &mut Std140Writer {
+        self.align(4);
+        self.data.extend_from_slice(&value.to_ne_bytes());
+        self
+    }
+
+    /// Writes a `uint`.
+    pub fn write_u32(&mut self, value: u32) -> &mut Std140Writer {
+        self.align(4);
+        self.data.extend_from_slice(&value.to_ne_bytes());
+        self
+    }
+
+    /// Writes a `vec2`.
+    pub fn write_vec2(&mut self, value: Vec2<f32>) -> &mut Std140Writer {
+        self.align(8);
+        self.push_f32s(value.as_slice());
+        self
+    }
+
+    /// Writes a `vec3`.
+    pub fn write_vec3(&mut self, value: Vec3<f32>) -> &mut Std140Writer {
+        self.align(16);
+        self.push_f32s(value.as_slice());
+        self
+    }
+
+    /// Writes a `vec4`.
+    pub fn write_vec4(&mut self, value: Vec4<f32>) -> &mut Std140Writer {
+        self.align(16);
+        self.push_f32s(value.as_slice());
+        self
+    }
+
+    /// Writes a [`Color`], as a `vec4`.
+    pub fn write_color(&mut self, value: Color) -> &mut Std140Writer {
+        self.write_vec4(value.into())
+    }
+
+    /// Writes a `mat2`.
+    pub fn write_mat2(&mut self, value: Mat2<f32>) -> &mut Std140Writer {
+        self.push_columns(value.as_col_slice(), 2);
+        self
+    }
+
+    /// Writes a `mat3`.
+    pub fn write_mat3(&mut self, value: Mat3<f32>) -> &mut Std140Writer {
+        self.push_columns(value.as_col_slice(), 3);
+        self
+    }
+
+    /// Writes a `mat4`.
+    pub fn write_mat4(&mut self, value: Mat4<f32>) -> &mut Std140Writer {
+        self.push_columns(value.as_col_slice(), 4);
+        self
+    }
+
+    /// Writes a `float[]` array.
+    ///
+    /// In the `std140` layout, each element of a scalar array takes up as much space
+    /// as a `vec4`.
+    pub fn write_f32_array(&mut self, values: &[f32]) -> &mut Std140Writer {
+        self.push_columns(values, 1);
+        self
+    }
+
+    /// Writes a `vec2[]` array.
+    ///
+    /// In the `std140` layout, each element of a `vec2` array takes up as much space
+    /// as a `vec4`.
+    pub fn write_vec2_array(&mut self, values: &[Vec2<f32>]) -> &mut Std140Writer {
+        for value in values {
+            self.push_columns(value.as_slice(), 2);
+        }
+
+        self
+    }
+
+    /// Writes a `vec4[]` array.
+    pub fn write_vec4_array(&mut self, values: &[Vec4<f32>]) -> &mut Std140Writer {
+        for value in values {
+            self.push_columns(value.as_slice(), 4);
+        }
+
+        self
+    }
+
+    /// Writes a `mat4[]` array.
+    pub fn write_mat4_array(&mut self, values: &[Mat4<f32>]) -> &mut Std140Writer {
+        for value in values {
+            self.push_columns(value.as_col_slice(), 4);
+        }
+
+        self
+    }
+
+    /// Returns the data that has been written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the length of the data that has been written so far, in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no data has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Removes all of the data from the writer, so that it can be reused.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    fn align(&mut self, alignment: usize) {
+        let padding = (alignment - self.data.len() % alignment) % alignment;
+
+        self.data.resize(self.data.len() + padding, 0);
+    }
+
+    fn push_f32s(&mut self, values: &[f32]) {
+        for value in values {
+            self.data.extend_from_slice(&value.to_ne_bytes());
+        }
+    }
+
+    /// Writes the values as a series of columns, each of which is aligned to (and
+    /// padded out to) the size of a `vec4`, as `std140` requires for arrays and matrices.
+    fn push_columns(&mut self, values: &[f32], column_length: usize) {
+        for column in values.chunks(column_length) {
+            self.align(16);
+            self.push_f32s(column);
+            self.align(16);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_f32(writer: &Std140Writer, offset: usize) -> f32 {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&writer.as_bytes()[offset..offset + 4]);
+        f32::from_ne_bytes(bytes)
+    }
+
+    #[test]
+    fn std140_alignment() {
+        let mut writer = Std140Writer::new();
+
+        writer
+            .write_f32(1.0)
+            .write_vec3(Vec3::new(2.0, 3.0, 4.0))
+            .write_f32(5.0)
+            .write_vec2(Vec2::new(6.0, 7.0))
+            .write_mat4(Mat4::identity());
+
+        assert_eq!(read_f32(&writer, 0), 1.0);
+        assert_eq!(read_f32(&writer, 16), 2.0);
+        assert_eq!(read_f32(&writer, 24), 4.0);
+
+        // Scalars can be packed into the space after a vec3.
+        assert_eq!(read_f32(&writer, 28), 5.0);
+
+        assert_eq!(read_f32(&writer, 32), 6.0);
+        assert_eq!(read_f32(&writer, 48), 1.0);
+        assert_eq!(writer.len(), 112);
+    }
+
+    #[test]
+    fn std140_arrays_and_matrices() {
+        let mut writer = Std140Writer::new();
+
+        writer.write_f32_array(&[1.0, 2.0]).write_f32(3.0);
+
+        assert_eq!(read_f32(&writer, 0), 1.0);
+        assert_eq!(read_f32(&writer, 16), 2.0);
+        assert_eq!(read_f32(&writer, 32), 3.0);
+
+        writer.clear();
+        writer.write_mat3(Mat3::identity());
+
+        // Each column of a mat3 is padded out to the size of a vec4.
+        assert_eq!(writer.len(), 48);
+        assert_eq!(read_f32(&writer, 16 + 4), 1.0);
+        assert_eq!(read_f32(&writer, 32 + 8), 1.0);
+    }
+}
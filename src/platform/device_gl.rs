@@ -2,10 +2,13 @@ use std::cell::Cell;
 use std::mem;
 use std::rc::Rc;
 
-use glow::{Context as GlowContext, HasContext, PixelUnpackData};
+use glow::{Context as GlowContext, HasContext, PixelPackData, PixelUnpackData};
 
 use crate::error::{Result, TetraError};
-use crate::graphics::{BufferUsage, FilterMode, VertexWinding};
+use crate::graphics::{
+    BlendEquation, BlendFactor, BlendMode, BufferUsage, FilterMode, StencilAction, StencilState,
+    StencilTest, UniformInfo, UniformType, VertexWinding, WrapMode,
+};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 
 /// Utility function for calculating offsets/sizes.
@@ -13,10 +16,18 @@ fn size<T>(elements: usize) -> i32 {
     (elements * mem::size_of::<T>()) as i32
 }
 
+/// Utility function for calculating the number of levels in a full mipmap chain.
+fn mipmap_levels(width: i32, height: i32) -> i32 {
+    let largest = i32::max(width, height).max(1);
+
+    32 - largest.leading_zeros() as i32
+}
+
 type BufferId = <GlowContext as HasContext>::Buffer;
 type ProgramId = <GlowContext as HasContext>::Program;
 type TextureId = <GlowContext as HasContext>::Texture;
 type FramebufferId = <GlowContext as HasContext>::Framebuffer;
+type RenderbufferId = <GlowContext as HasContext>::Renderbuffer;
 type VertexArrayId = <GlowContext as HasContext>::VertexArray;
 
 pub type UniformLocation = <GlowContext as HasContext>::UniformLocation;
@@ -31,6 +42,27 @@ struct GraphicsState {
     current_textures: Vec<Cell<Option<TextureId>>>,
     current_framebuffer: Cell<Option<FramebufferId>>,
     current_vertex_array: Cell<Option<VertexArrayId>>,
+
+    texture_memory: Cell<usize>,
+    buffer_memory: Cell<usize>,
+}
+
+impl GraphicsState {
+    fn track_texture_memory(&self, bytes: usize, allocated: bool) {
+        track_memory(&self.texture_memory, bytes, allocated);
+    }
+
+    fn track_buffer_memory(&self, bytes: usize, allocated: bool) {
+        track_memory(&self.buffer_memory, bytes, allocated);
+    }
+}
+
+fn track_memory(counter: &Cell<usize>, bytes: usize, allocated: bool) {
+    if allocated {
+        counter.set(counter.get() + bytes);
+    } else {
+        counter.set(counter.get().saturating_sub(bytes));
+    }
 }
 
 pub struct GraphicsDevice {
@@ -43,15 +75,6 @@ impl GraphicsDevice {
             gl.enable(glow::CULL_FACE);
             gl.enable(glow::BLEND);
 
-            // This default might want to change if we introduce
-            // custom blending modes.
-            gl.blend_func_separate(
-                glow::SRC_ALPHA,
-                glow::ONE_MINUS_SRC_ALPHA,
-                glow::ONE,
-                glow::ONE_MINUS_SRC_ALPHA,
-            );
-
             // This is only needed for Core GL - if we wanted to be uber compatible, we'd
             // turn it off on older versions.
             let current_vertex_array = gl
@@ -75,14 +98,32 @@ impl GraphicsDevice {
                 current_textures: vec![Cell::new(None); texture_units],
                 current_framebuffer: Cell::new(None),
                 current_vertex_array: Cell::new(Some(current_vertex_array)),
+
+                texture_memory: Cell::new(0),
+                buffer_memory: Cell::new(0),
             };
 
-            Ok(GraphicsDevice {
+            let mut device = GraphicsDevice {
                 state: Rc::new(state),
-            })
+            };
+
+            device.set_blend_mode(BlendMode::Alpha);
+
+            Ok(device)
         }
     }
 
+    /// Returns the approximate number of bytes currently allocated for textures
+    /// and renderbuffers.
+    pub fn texture_memory(&self) -> usize {
+        self.state.texture_memory.get()
+    }
+
+    /// Returns the approximate number of bytes currently allocated for buffers.
+    pub fn buffer_memory(&self) -> usize {
+        self.state.buffer_memory.get()
+    }
+
     pub fn get_renderer(&self) -> String {
         unsafe { self.state.gl.get_parameter_string(glow::RENDERER) }
     }
@@ -110,6 +151,96 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        use BlendEquation as E;
+        use BlendFactor as F;
+
+        // Each mode is expressed as an (equation, source factor, destination factor)
+        // triple for the color channels, followed by another for the alpha channel.
+        let (color, alpha) = match blend_mode {
+            BlendMode::Alpha => (
+                (E::Add, F::SrcAlpha, F::OneMinusSrcAlpha),
+                (E::Add, F::One, F::OneMinusSrcAlpha),
+            ),
+            BlendMode::Add => ((E::Add, F::SrcAlpha, F::One), (E::Add, F::Zero, F::One)),
+            BlendMode::Subtract => (
+                (E::ReverseSubtract, F::SrcAlpha, F::One),
+                (E::Add, F::Zero, F::One),
+            ),
+            BlendMode::Multiply => (
+                (E::Add, F::DstColor, F::Zero),
+                (E::Add, F::DstAlpha, F::Zero),
+            ),
+            BlendMode::Premultiplied => (
+                (E::Add, F::One, F::OneMinusSrcAlpha),
+                (E::Add, F::One, F::OneMinusSrcAlpha),
+            ),
+            BlendMode::Replace => ((E::Add, F::One, F::Zero), (E::Add, F::One, F::Zero)),
+            BlendMode::Custom {
+                color_equation,
+                src_color,
+                dst_color,
+                alpha_equation,
+                src_alpha,
+                dst_alpha,
+            } => (
+                (color_equation, src_color, dst_color),
+                (alpha_equation, src_alpha, dst_alpha),
+            ),
+        };
+
+        unsafe {
+            self.state
+                .gl
+                .blend_equation_separate(color.0.into(), alpha.0.into());
+
+            self.state.gl.blend_func_separate(
+                color.1.into(),
+                color.2.into(),
+                alpha.1.into(),
+                alpha.2.into(),
+            );
+        }
+    }
+
+    pub fn set_stencil_state(&mut self, state: StencilState) {
+        unsafe {
+            if state.enabled {
+                self.state.gl.enable(glow::STENCIL_TEST);
+
+                self.state.gl.stencil_func(
+                    state.test.into(),
+                    i32::from(state.reference_value),
+                    u32::from(state.read_mask),
+                );
+
+                self.state
+                    .gl
+                    .stencil_op(glow::KEEP, glow::KEEP, state.action.into());
+
+                self.state.gl.stencil_mask(u32::from(state.write_mask));
+            } else {
+                self.state.gl.disable(glow::STENCIL_TEST);
+            }
+        }
+    }
+
+    pub fn clear_stencil(&mut self, value: u8) {
+        unsafe {
+            // The stencil mask also applies to clears, so it needs to be temporarily
+            // reset. It is up to the caller to restore the previous stencil state.
+            self.state.gl.stencil_mask(0xFF);
+            self.state.gl.clear_stencil(i32::from(value));
+            self.state.gl.clear(glow::STENCIL_BUFFER_BIT);
+        }
+    }
+
+    pub fn set_color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool) {
+        unsafe {
+            self.state.gl.color_mask(red, green, blue, alpha);
+        }
+    }
+
     pub fn front_face(&mut self, front_face: VertexWinding) {
         unsafe {
             self.state.gl.front_face(front_face.into());
@@ -144,6 +275,9 @@ impl GraphicsDevice {
                 usage.into(),
             );
 
+            self.state
+                .track_buffer_memory(buffer.size() * mem::size_of::<f32>(), true);
+
             Ok(buffer)
         }
     }
@@ -172,6 +306,22 @@ impl GraphicsDevice {
         }
     }
 
+    /// Allocates new storage for the buffer, discarding its existing contents.
+    ///
+    /// This allows the driver to hand back fresh memory rather than waiting for any pending
+    /// draw calls that use the old data to complete before it can be overwritten.
+    pub fn orphan_vertex_buffer(&mut self, buffer: &RawVertexBuffer, usage: BufferUsage) {
+        self.bind_vertex_buffer(Some(buffer));
+
+        unsafe {
+            self.state.gl.buffer_data_size(
+                glow::ARRAY_BUFFER,
+                size::<f32>(buffer.size()),
+                usage.into(),
+            );
+        }
+    }
+
     pub fn new_index_buffer(&mut self, count: usize, usage: BufferUsage) -> Result<RawIndexBuffer> {
         unsafe {
             let id = self
@@ -194,6 +344,9 @@ impl GraphicsDevice {
                 usage.into(),
             );
 
+            self.state
+                .track_buffer_memory(count * mem::size_of::<u32>(), true);
+
             Ok(buffer)
         }
     }
@@ -291,6 +444,39 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn get_active_uniforms(&self, program: &RawProgram) -> Vec<UniformInfo> {
+        unsafe {
+            let count = self.state.gl.get_active_uniforms(program.id);
+
+            (0..count)
+                .filter_map(|index| self.state.gl.get_active_uniform(program.id, index))
+                // Members of uniform blocks can't be set individually, so they have no
+                // location. glow doesn't expose `GL_UNIFORM_BLOCK_INDEX`, so this is
+                // used to filter them out instead.
+                .filter(|uniform| {
+                    self.state
+                        .gl
+                        .get_uniform_location(program.id, &uniform.name)
+                        .is_some()
+                })
+                .map(|uniform| {
+                    let mut name = uniform.name;
+
+                    // Arrays are reported using the name of their first element.
+                    if name.ends_with("[0]") {
+                        name.truncate(name.len() - 3);
+                    }
+
+                    UniformInfo {
+                        name,
+                        uniform_type: uniform_type_from_gl(uniform.utype),
+                        size: uniform.size.max(1) as usize,
+                    }
+                })
+                .collect()
+        }
+    }
+
     pub fn get_uniform_location(
         &self,
         program: &RawProgram,
@@ -434,8 +620,178 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn new_texture(&mut self, width: i32, height: i32) -> Result<RawTexture> {
-        // TODO: I don't think we need mipmaps?
+    pub fn set_uniform_i32_slice(
+        &mut self,
+        program: &RawProgram,
+        location: Option<&UniformLocation>,
+        values: &[i32],
+    ) {
+        self.bind_program(Some(program));
+
+        unsafe {
+            self.state.gl.uniform_1_i32_slice(location, values);
+        }
+    }
+
+    pub fn set_uniform_u32_slice(
+        &mut self,
+        program: &RawProgram,
+        location: Option<&UniformLocation>,
+        values: &[u32],
+    ) {
+        self.bind_program(Some(program));
+
+        unsafe {
+            self.state.gl.uniform_1_u32_slice(location, values);
+        }
+    }
+
+    /// Sets an array of float vectors, where `components` is the number of floats
+    /// in each element.
+    pub fn set_uniform_f32_slice(
+        &mut self,
+        program: &RawProgram,
+        location: Option<&UniformLocation>,
+        components: usize,
+        values: &[f32],
+    ) {
+        self.bind_program(Some(program));
+
+        unsafe {
+            match components {
+                1 => self.state.gl.uniform_1_f32_slice(location, values),
+                2 => self.state.gl.uniform_2_f32_slice(location, values),
+                3 => self.state.gl.uniform_3_f32_slice(location, values),
+                4 => self.state.gl.uniform_4_f32_slice(location, values),
+                _ => unreachable!("invalid number of vector components"),
+            }
+        }
+    }
+
+    /// Sets an array of square matrices, where `dimensions` is the number of rows/columns
+    /// in each element.
+    pub fn set_uniform_matrix_slice(
+        &mut self,
+        program: &RawProgram,
+        location: Option<&UniformLocation>,
+        dimensions: usize,
+        transpose: bool,
+        values: &[f32],
+    ) {
+        self.bind_program(Some(program));
+
+        unsafe {
+            match dimensions {
+                2 => self
+                    .state
+                    .gl
+                    .uniform_matrix_2_f32_slice(location, transpose, values),
+                3 => self
+                    .state
+                    .gl
+                    .uniform_matrix_3_f32_slice(location, transpose, values),
+                4 => self
+                    .state
+                    .gl
+                    .uniform_matrix_4_f32_slice(location, transpose, values),
+                _ => unreachable!("invalid matrix dimensions"),
+            }
+        }
+    }
+
+    /// Assigns a uniform block in the program to a binding point.
+    ///
+    /// Returns `false` if the program has no active uniform block with the specified name.
+    pub fn set_uniform_block_binding(
+        &mut self,
+        program: &RawProgram,
+        name: &str,
+        binding: u32,
+    ) -> bool {
+        unsafe {
+            match self.state.gl.get_uniform_block_index(program.id, name) {
+                Some(index) => {
+                    self.state
+                        .gl
+                        .uniform_block_binding(program.id, index, binding);
+
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    pub fn has_uniform_block(&self, program: &RawProgram, name: &str) -> bool {
+        unsafe {
+            self.state
+                .gl
+                .get_uniform_block_index(program.id, name)
+                .is_some()
+        }
+    }
+
+    pub fn new_uniform_buffer(
+        &mut self,
+        size: usize,
+        usage: BufferUsage,
+    ) -> Result<RawUniformBuffer> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .create_buffer()
+                .map_err(TetraError::PlatformError)?;
+
+            let buffer = RawUniformBuffer {
+                state: Rc::clone(&self.state),
+                id,
+                size,
+            };
+
+            self.state.gl.bind_buffer(glow::UNIFORM_BUFFER, Some(id));
+
+            self.state
+                .gl
+                .buffer_data_size(glow::UNIFORM_BUFFER, size as i32, usage.into());
+
+            self.state.track_buffer_memory(size, true);
+
+            Ok(buffer)
+        }
+    }
+
+    pub fn set_uniform_buffer_data(
+        &mut self,
+        buffer: &RawUniformBuffer,
+        data: &[u8],
+        offset: usize,
+    ) {
+        assert!(
+            data.len() + offset <= buffer.size(),
+            "tried to write out of bounds buffer data"
+        );
+
+        unsafe {
+            self.state
+                .gl
+                .bind_buffer(glow::UNIFORM_BUFFER, Some(buffer.id));
+
+            self.state
+                .gl
+                .buffer_sub_data_u8_slice(glow::UNIFORM_BUFFER, offset as i32, data);
+        }
+    }
+
+    pub fn bind_uniform_buffer(&mut self, buffer: &RawUniformBuffer, binding: u32) {
+        unsafe {
+            self.state
+                .gl
+                .bind_buffer_base(glow::UNIFORM_BUFFER, binding, Some(buffer.id));
+        }
+    }
+
+    pub fn new_texture(&mut self, width: i32, height: i32, mipmaps: bool) -> Result<RawTexture> {
         unsafe {
             let id = self
                 .state
@@ -449,6 +805,7 @@ impl GraphicsDevice {
                 id,
                 width,
                 height,
+                mipmaps: Cell::new(mipmaps),
             };
 
             self.bind_default_texture(Some(&texture));
@@ -469,9 +826,17 @@ impl GraphicsDevice {
                 .gl
                 .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_BASE_LEVEL, 0);
 
+            // If the texture has mipmaps, they will be generated once data has been
+            // written to it.
+            let max_level = if mipmaps {
+                mipmap_levels(width, height) - 1
+            } else {
+                0
+            };
+
             self.state
                 .gl
-                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, 0);
+                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, max_level);
 
             self.state.gl.tex_image_2d(
                 glow::TEXTURE_2D,
@@ -485,6 +850,8 @@ impl GraphicsDevice {
                 None,
             );
 
+            self.state.track_texture_memory(texture.memory_size(), true);
+
             Ok(texture)
         }
     }
@@ -523,12 +890,75 @@ impl GraphicsDevice {
                 glow::RGBA,
                 glow::UNSIGNED_BYTE,
                 PixelUnpackData::Slice(data),
-            )
+            );
+
+            if texture.mipmaps.get() {
+                self.state.gl.generate_mipmap(glow::TEXTURE_2D);
+            }
         }
 
         Ok(())
     }
 
+    pub fn set_texture_mipmaps(&mut self, texture: &RawTexture, mipmaps: bool) {
+        if texture.mipmaps.get() == mipmaps {
+            return;
+        }
+
+        self.bind_default_texture(Some(texture));
+
+        self.state
+            .track_texture_memory(texture.memory_size(), false);
+
+        texture.mipmaps.set(mipmaps);
+
+        self.state.track_texture_memory(texture.memory_size(), true);
+
+        let max_level = if mipmaps {
+            mipmap_levels(texture.width, texture.height) - 1
+        } else {
+            0
+        };
+
+        unsafe {
+            self.state
+                .gl
+                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, max_level);
+
+            if mipmaps {
+                self.state.gl.generate_mipmap(glow::TEXTURE_2D);
+            }
+        }
+    }
+
+    pub fn generate_texture_mipmaps(&mut self, texture: &RawTexture) {
+        if texture.mipmaps.get() {
+            self.bind_default_texture(Some(texture));
+
+            unsafe {
+                self.state.gl.generate_mipmap(glow::TEXTURE_2D);
+            }
+        }
+    }
+
+    pub fn get_texture_data(&mut self, texture: &RawTexture) -> Vec<u8> {
+        self.bind_default_texture(Some(texture));
+
+        let mut buffer = vec![0; (texture.width * texture.height * 4) as usize];
+
+        unsafe {
+            self.state.gl.get_tex_image(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelPackData::Slice(&mut buffer),
+            );
+        }
+
+        buffer
+    }
+
     pub fn set_texture_filter_mode(&mut self, texture: &RawTexture, filter_mode: FilterMode) {
         self.bind_default_texture(Some(texture));
 
@@ -539,17 +969,93 @@ impl GraphicsDevice {
                 filter_mode.into(),
             );
 
+            // Mipmaps are only used when minifying, so the magnification filter
+            // has to be one of the non-mipmapped modes.
+            let mag_filter = match filter_mode {
+                FilterMode::Nearest
+                | FilterMode::NearestMipmapNearest
+                | FilterMode::NearestMipmapLinear => glow::NEAREST,
+
+                FilterMode::Linear
+                | FilterMode::LinearMipmapNearest
+                | FilterMode::LinearMipmapLinear => glow::LINEAR,
+            };
+
             self.state.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MAG_FILTER,
-                filter_mode.into(),
+                mag_filter as i32,
+            );
+        }
+    }
+
+    pub fn set_texture_wrap_mode(&mut self, texture: &RawTexture, wrap_mode: WrapMode) {
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state.gl.tex_parameter_i32(
+                glow::TEXTURE_2D,
+                glow::TEXTURE_WRAP_S,
+                wrap_mode.into(),
             );
+
+            self.state.gl.tex_parameter_i32(
+                glow::TEXTURE_2D,
+                glow::TEXTURE_WRAP_T,
+                wrap_mode.into(),
+            );
+
+            if let WrapMode::ClampToBorder(color) = wrap_mode {
+                self.state.gl.tex_parameter_f32_slice(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_BORDER_COLOR,
+                    &[color.r, color.g, color.b, color.a],
+                );
+            }
+        }
+    }
+
+    pub fn new_renderbuffer(&mut self, width: i32, height: i32) -> Result<RawRenderbuffer> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .create_renderbuffer()
+                .map_err(TetraError::PlatformError)?;
+
+            // Renderbuffer bindings aren't cached, as we never need to bind them
+            // outside of this function.
+            self.state
+                .gl
+                .bind_renderbuffer(glow::RENDERBUFFER, Some(id));
+
+            // DEPTH24_STENCIL8 is used rather than STENCIL_INDEX8, as it is the
+            // most widely supported format for stencil attachments.
+            self.state.gl.renderbuffer_storage(
+                glow::RENDERBUFFER,
+                glow::DEPTH24_STENCIL8,
+                width,
+                height,
+            );
+
+            self.state.gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+            self.state
+                .track_texture_memory(width as usize * height as usize * 4, true);
+
+            Ok(RawRenderbuffer {
+                state: Rc::clone(&self.state),
+                id,
+                width,
+                height,
+            })
         }
     }
 
     pub fn new_framebuffer(
         &mut self,
         texture: &RawTexture,
+        depth_stencil: Option<&RawRenderbuffer>,
         rebind_previous: bool,
     ) -> Result<RawFramebuffer> {
         unsafe {
@@ -576,6 +1082,15 @@ impl GraphicsDevice {
                 0,
             );
 
+            if let Some(depth_stencil) = depth_stencil {
+                self.state.gl.framebuffer_renderbuffer(
+                    glow::FRAMEBUFFER,
+                    glow::DEPTH_STENCIL_ATTACHMENT,
+                    glow::RENDERBUFFER,
+                    Some(depth_stencil.id),
+                );
+            }
+
             if rebind_previous {
                 self.state
                     .gl
@@ -587,12 +1102,46 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn read_pixels(&mut self, x: i32, y: i32, width: i32, height: i32) -> Vec<u8> {
+        let mut buffer = vec![0; (width * height * 4) as usize];
+
+        unsafe {
+            self.state.gl.read_pixels(
+                x,
+                y,
+                width,
+                height,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelPackData::Slice(&mut buffer),
+            );
+        }
+
+        buffer
+    }
+
     pub fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
         unsafe {
             self.state.gl.viewport(x, y, width, height);
         }
     }
 
+    pub fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            self.state.gl.scissor(x, y, width, height);
+        }
+    }
+
+    pub fn scissor_test(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                self.state.gl.enable(glow::SCISSOR_TEST);
+            } else {
+                self.state.gl.disable(glow::SCISSOR_TEST);
+            }
+        }
+    }
+
     pub fn draw_arrays(
         &mut self,
         vertex_buffer: &RawVertexBuffer,
@@ -792,16 +1341,121 @@ impl From<VertexWinding> for u32 {
     }
 }
 
+#[doc(hidden)]
+impl From<BlendFactor> for u32 {
+    fn from(blend_factor: BlendFactor) -> u32 {
+        match blend_factor {
+            BlendFactor::Zero => glow::ZERO,
+            BlendFactor::One => glow::ONE,
+            BlendFactor::SrcColor => glow::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => glow::ONE_MINUS_SRC_COLOR,
+            BlendFactor::SrcAlpha => glow::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => glow::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstColor => glow::DST_COLOR,
+            BlendFactor::OneMinusDstColor => glow::ONE_MINUS_DST_COLOR,
+            BlendFactor::DstAlpha => glow::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => glow::ONE_MINUS_DST_ALPHA,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<BlendEquation> for u32 {
+    fn from(blend_equation: BlendEquation) -> u32 {
+        match blend_equation {
+            BlendEquation::Add => glow::FUNC_ADD,
+            BlendEquation::Subtract => glow::FUNC_SUBTRACT,
+            BlendEquation::ReverseSubtract => glow::FUNC_REVERSE_SUBTRACT,
+            BlendEquation::Min => glow::MIN,
+            BlendEquation::Max => glow::MAX,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<StencilTest> for u32 {
+    fn from(stencil_test: StencilTest) -> u32 {
+        match stencil_test {
+            StencilTest::Never => glow::NEVER,
+            StencilTest::LessThan => glow::LESS,
+            StencilTest::LessThanOrEqualTo => glow::LEQUAL,
+            StencilTest::EqualTo => glow::EQUAL,
+            StencilTest::NotEqualTo => glow::NOTEQUAL,
+            StencilTest::GreaterThan => glow::GREATER,
+            StencilTest::GreaterThanOrEqualTo => glow::GEQUAL,
+            StencilTest::Always => glow::ALWAYS,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<StencilAction> for u32 {
+    fn from(stencil_action: StencilAction) -> u32 {
+        match stencil_action {
+            StencilAction::Keep => glow::KEEP,
+            StencilAction::Zero => glow::ZERO,
+            StencilAction::Replace => glow::REPLACE,
+            StencilAction::Increment => glow::INCR,
+            StencilAction::IncrementWrap => glow::INCR_WRAP,
+            StencilAction::Decrement => glow::DECR,
+            StencilAction::DecrementWrap => glow::DECR_WRAP,
+            StencilAction::Invert => glow::INVERT,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl From<FilterMode> for i32 {
     fn from(filter_mode: FilterMode) -> i32 {
         match filter_mode {
             FilterMode::Nearest => glow::NEAREST as i32,
             FilterMode::Linear => glow::LINEAR as i32,
+            FilterMode::NearestMipmapNearest => glow::NEAREST_MIPMAP_NEAREST as i32,
+            FilterMode::NearestMipmapLinear => glow::NEAREST_MIPMAP_LINEAR as i32,
+            FilterMode::LinearMipmapNearest => glow::LINEAR_MIPMAP_NEAREST as i32,
+            FilterMode::LinearMipmapLinear => glow::LINEAR_MIPMAP_LINEAR as i32,
         }
     }
 }
 
+#[doc(hidden)]
+impl From<WrapMode> for i32 {
+    fn from(wrap_mode: WrapMode) -> i32 {
+        match wrap_mode {
+            WrapMode::ClampToEdge => glow::CLAMP_TO_EDGE as i32,
+            WrapMode::Repeat => glow::REPEAT as i32,
+            WrapMode::MirroredRepeat => glow::MIRRORED_REPEAT as i32,
+            WrapMode::ClampToBorder(_) => glow::CLAMP_TO_BORDER as i32,
+        }
+    }
+}
+
+fn uniform_type_from_gl(gl_type: u32) -> UniformType {
+    match gl_type {
+        glow::FLOAT => UniformType::Float,
+        glow::FLOAT_VEC2 => UniformType::Vec2,
+        glow::FLOAT_VEC3 => UniformType::Vec3,
+        glow::FLOAT_VEC4 => UniformType::Vec4,
+        glow::INT => UniformType::Int,
+        glow::INT_VEC2 => UniformType::IVec2,
+        glow::INT_VEC3 => UniformType::IVec3,
+        glow::INT_VEC4 => UniformType::IVec4,
+        glow::UNSIGNED_INT => UniformType::UInt,
+        glow::UNSIGNED_INT_VEC2 => UniformType::UVec2,
+        glow::UNSIGNED_INT_VEC3 => UniformType::UVec3,
+        glow::UNSIGNED_INT_VEC4 => UniformType::UVec4,
+        glow::BOOL => UniformType::Bool,
+        glow::BOOL_VEC2 => UniformType::BVec2,
+        glow::BOOL_VEC3 => UniformType::BVec3,
+        glow::BOOL_VEC4 => UniformType::BVec4,
+        glow::FLOAT_MAT2 => UniformType::Mat2,
+        glow::FLOAT_MAT3 => UniformType::Mat3,
+        glow::FLOAT_MAT4 => UniformType::Mat4,
+        glow::SAMPLER_2D => UniformType::Sampler2D,
+        other => UniformType::Other(other),
+    }
+}
+
 macro_rules! handle_impls {
     ($name:ty) => {
         impl PartialEq for $name {
@@ -844,6 +1498,9 @@ impl Drop for RawVertexBuffer {
 
             self.state.gl.delete_buffer(self.id);
         }
+
+        self.state
+            .track_buffer_memory(self.size() * mem::size_of::<f32>(), false);
     }
 }
 
@@ -872,11 +1529,40 @@ impl Drop for RawIndexBuffer {
 
             self.state.gl.delete_buffer(self.id);
         }
+
+        self.state
+            .track_buffer_memory(self.count * mem::size_of::<u32>(), false);
     }
 }
 
 handle_impls!(RawIndexBuffer);
 
+#[derive(Debug)]
+pub struct RawUniformBuffer {
+    state: Rc<GraphicsState>,
+    id: BufferId,
+
+    size: usize,
+}
+
+impl RawUniformBuffer {
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Drop for RawUniformBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.state.gl.delete_buffer(self.id);
+        }
+
+        self.state.track_buffer_memory(self.size, false);
+    }
+}
+
+handle_impls!(RawUniformBuffer);
+
 #[derive(Debug)]
 pub struct RawProgram {
     state: Rc<GraphicsState>,
@@ -904,6 +1590,7 @@ pub struct RawTexture {
 
     width: i32,
     height: i32,
+    mipmaps: Cell<bool>,
 }
 
 impl RawTexture {
@@ -914,6 +1601,29 @@ impl RawTexture {
     pub fn height(&self) -> i32 {
         self.height
     }
+
+    pub fn mipmaps(&self) -> bool {
+        self.mipmaps.get()
+    }
+
+    /// Returns the approximate amount of GPU memory used by the texture, including
+    /// any mipmap levels.
+    fn memory_size(&self) -> usize {
+        let levels = if self.mipmaps.get() {
+            mipmap_levels(self.width, self.height)
+        } else {
+            1
+        };
+
+        (0..levels)
+            .map(|level| {
+                let width = (self.width >> level).max(1) as usize;
+                let height = (self.height >> level).max(1) as usize;
+
+                width * height * 4
+            })
+            .sum()
+    }
 }
 
 impl Drop for RawTexture {
@@ -927,6 +1637,8 @@ impl Drop for RawTexture {
 
             self.state.gl.delete_texture(self.id);
         }
+
+        self.state.track_texture_memory(self.memory_size(), false);
     }
 }
 
@@ -951,3 +1663,25 @@ impl Drop for RawFramebuffer {
 }
 
 handle_impls!(RawFramebuffer);
+
+#[derive(Debug)]
+pub struct RawRenderbuffer {
+    state: Rc<GraphicsState>,
+    id: RenderbufferId,
+
+    width: i32,
+    height: i32,
+}
+
+impl Drop for RawRenderbuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.state.gl.delete_renderbuffer(self.id);
+        }
+
+        self.state
+            .track_texture_memory(self.width as usize * self.height as usize * 4, false);
+    }
+}
+
+handle_impls!(RawRenderbuffer);
@@ -63,7 +63,6 @@ impl Window {
 
         let gl_attr = video_sys.gl_attr();
 
-        // TODO: Will need to add some more here if we start using the depth/stencil buffers
         gl_attr.set_context_profile(GLProfile::Core);
         gl_attr.set_context_version(3, 2);
         gl_attr.set_red_size(8);
@@ -72,6 +71,10 @@ impl Window {
         gl_attr.set_alpha_size(8);
         gl_attr.set_double_buffer(true);
 
+        if settings.stencil_buffer {
+            gl_attr.set_stencil_size(8);
+        }
+
         if settings.multisampling > 0 {
             gl_attr.set_multisample_buffers(1);
             gl_attr.set_multisample_samples(settings.multisampling);
@@ -209,6 +212,11 @@ impl Window {
         (self.window_width, self.window_height)
     }
 
+    pub fn get_drawable_size(&self) -> (i32, i32) {
+        let (width, height) = self.sdl_window.drawable_size();
+        (width as i32, height as i32)
+    }
+
     pub fn set_window_size(&mut self, width: i32, height: i32) -> Result {
         self.window_width = width;
         self.window_height = height;
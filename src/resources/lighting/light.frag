@@ -0,0 +1,28 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform vec4 u_diffuse;
+uniform float u_falloff;
+uniform vec2 u_direction;
+uniform vec2 u_cone;
+
+out vec4 o_color;
+
+void main() {
+    vec2 offset = v_uv * 2.0 - 1.0;
+    float distance = length(offset);
+
+    float attenuation = pow(clamp(1.0 - distance, 0.0, 1.0), u_falloff);
+
+    // u_cone contains the cosines of the outer and inner edges of a spot light's cone.
+    if (distance > 0.0) {
+        attenuation *= smoothstep(u_cone.x, u_cone.y, dot(offset / distance, u_direction));
+    }
+
+    vec4 color = texture(u_texture, v_uv) * u_diffuse * v_color;
+
+    o_color = vec4(color.rgb * color.a * attenuation, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform sampler2D u_input;
+uniform vec4 u_diffuse;
+uniform float u_intensity;
+
+out vec4 o_color;
+
+void main() {
+    vec4 original = texture(u_input, v_uv);
+    vec3 bloom = texture(u_texture, v_uv).rgb * u_intensity;
+
+    o_color = vec4(original.rgb + bloom, original.a) * u_diffuse * v_color;
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform vec4 u_diffuse;
+uniform float u_threshold;
+
+out vec4 o_color;
+
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    float brightness = dot(color.rgb, LUMA);
+    float amount = max(brightness - u_threshold, 0.0) / max(brightness, 0.0001);
+
+    o_color = vec4(color.rgb * amount, color.a) * u_diffuse * v_color;
+}
\ No newline at end of file
@@ -0,0 +1,26 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform vec4 u_diffuse;
+uniform vec2 u_resolution;
+uniform vec2 u_direction;
+uniform float u_radius;
+
+out vec4 o_color;
+
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main() {
+    vec2 offset = u_direction * u_radius / u_resolution;
+    vec4 color = texture(u_texture, v_uv) * WEIGHTS[0];
+
+    for (int i = 1; i < 5; i++) {
+        color += texture(u_texture, v_uv + offset * float(i)) * WEIGHTS[i];
+        color += texture(u_texture, v_uv - offset * float(i)) * WEIGHTS[i];
+    }
+
+    o_color = color * u_diffuse * v_color;
+}
\ No newline at end of file
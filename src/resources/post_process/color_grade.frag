@@ -0,0 +1,25 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform vec4 u_diffuse;
+uniform float u_brightness;
+uniform float u_contrast;
+uniform float u_saturation;
+uniform vec4 u_tint;
+
+out vec4 o_color;
+
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+
+    vec3 rgb = color.rgb + u_brightness;
+    rgb = (rgb - 0.5) * u_contrast + 0.5;
+    rgb = mix(vec3(dot(rgb, LUMA)), rgb, u_saturation);
+
+    o_color = vec4(rgb, color.a) * u_tint * u_diffuse * v_color;
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform vec4 u_diffuse;
+uniform vec2 u_resolution;
+uniform float u_line_height;
+uniform float u_intensity;
+
+out vec4 o_color;
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    float line = mod(floor(v_uv.y * u_resolution.y / u_line_height), 2.0);
+
+    o_color = vec4(color.rgb * (1.0 - line * u_intensity), color.a) * u_diffuse * v_color;
+}
\ No newline at end of file
@@ -58,6 +58,14 @@ pub fn get_size(ctx: &Context) -> (i32, i32) {
     ctx.window.get_window_size()
 }
 
+/// Gets the size of the window's drawable area, in physical pixels.
+///
+/// This will usually be the same as [`get_size`], but may be larger if the window
+/// is high-DPI (see [`ContextBuilder::high_dpi`](crate::ContextBuilder::high_dpi)).
+pub fn get_physical_size(ctx: &Context) -> (i32, i32) {
+    ctx.window.get_drawable_size()
+}
+
 /// Sets the size of the window.
 ///
 /// # Errors